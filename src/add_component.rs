@@ -1,3 +1,4 @@
+use crate::pack::tight::PackSync;
 use crate::storage::EntityId;
 use crate::ViewMut;
 
@@ -51,14 +52,20 @@ impl<T: 'static> AddComponent for &mut ViewMut<'_, T> {
 
 macro_rules! impl_add_component {
     ($(($storage: ident, $index: tt))+) => {
-        impl<$($storage: AddComponent,)+> AddComponent for ($($storage,)+) {
+        impl<$($storage: AddComponent + PackSync,)+> AddComponent for ($($storage,)+) {
             type Component = ($($storage::Component,)+);
 
             #[inline]
             fn add_component_unchecked(&mut self, entity: EntityId, component: Self::Component) {
+                let types = [$(self.$index.storage_type_id()),+];
+                let was_broken = [$(self.$index.is_pack_broken()),+];
+
                 $(
                     self.$index.add_component_unchecked(entity, component.$index);
                 )+
+                $(
+                    self.$index.sync_pack(entity, &types, was_broken[$index]);
+                )+
             }
         }
     }
@@ -1,4 +1,7 @@
+use crate::pack::tight::PackSync;
+use crate::sparse_set::SparseSet;
 use crate::storage::EntityId;
+use crate::type_id::TypeId;
 use crate::view::ViewMut;
 
 pub trait AddEntity {
@@ -28,20 +31,28 @@ impl<T: 'static> AddEntity for &mut ViewMut<'_, T> {
 
     #[inline]
     fn add_entity(&mut self, entity: EntityId, component: Self::Component) {
+        let was_broken = self.metadata.pack.is_broken();
         self.insert(entity, component);
+        self.sync_tight_pack_new(entity, &[TypeId::of::<SparseSet<T>>()], was_broken);
     }
 }
 
 macro_rules! impl_view_add_entity {
     ($(($type: ident, $index: tt))+) => {
-        impl<$($type: AddEntity),+> AddEntity for ($($type,)+) {
+        impl<$($type: AddEntity + PackSync),+> AddEntity for ($($type,)+) {
             type Component = ($($type::Component,)+);
 
             #[inline]
             fn add_entity(&mut self, entity: EntityId , components: Self::Component) {
+                let types = [$(self.$index.storage_type_id()),+];
+                let was_broken = [$(self.$index.is_pack_broken()),+];
+
                 $(
                     self.$index.add_entity(entity, components.$index);
                 )+
+                $(
+                    self.$index.sync_pack_new(entity, &types, was_broken[$index]);
+                )+
             }
         }
     }
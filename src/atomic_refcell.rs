@@ -91,6 +91,23 @@ impl<T> AtomicRefCell<T> {
 }
 
 impl<T> AtomicRefCell<T> {
+    /// Creates a new `AtomicRefCell` containing `value` without any thread restriction.
+    ///
+    /// ### Safety
+    ///
+    /// If `T` isn't `Send` or `Sync`, restrictions have to be added with `copy_thread_restrictions` before the cell is shared.
+    #[inline]
+    pub(crate) unsafe fn new_unrestricted(value: T) -> Self {
+        AtomicRefCell {
+            borrow_state: RawRwLock::INIT,
+            #[cfg(feature = "non_send")]
+            send: None,
+            #[cfg(feature = "non_sync")]
+            is_sync: true,
+            _non_send_sync: PhantomData,
+            inner: UnsafeCell::new(value),
+        }
+    }
     #[inline]
     pub(crate) fn into_inner(self) -> T {
         self.inner.into_inner()
@@ -98,6 +115,27 @@ impl<T> AtomicRefCell<T> {
 }
 
 impl<T: ?Sized> AtomicRefCell<T> {
+    /// Gives `self` the same thread restrictions as `other`.  
+    /// `world_thread_id` is the thread `self` is bound to if `other` isn't `Send`.
+    #[cfg_attr(
+        not(any(feature = "non_send", feature = "non_sync")),
+        allow(unused_variables)
+    )]
+    #[inline]
+    pub(crate) fn copy_thread_restrictions<U: ?Sized>(
+        &mut self,
+        other: &AtomicRefCell<U>,
+        #[cfg(feature = "non_send")] world_thread_id: ThreadId,
+    ) {
+        #[cfg(feature = "non_send")]
+        {
+            self.send = other.send.map(|_| world_thread_id);
+        }
+        #[cfg(feature = "non_sync")]
+        {
+            self.is_sync = other.is_sync;
+        }
+    }
     /// Immutably borrows the wrapped value, returning an error if the value is currently mutably
     /// borrowed.
     ///
@@ -241,7 +279,9 @@ impl Drop for SharedBorrow<'_> {
 impl Clone for SharedBorrow<'_> {
     #[inline]
     fn clone(&self) -> Self {
-        debug_assert!(self.0.try_lock_shared());
+        // the lock has to be taken even when debug assertions are disabled
+        let is_locked = self.0.try_lock_shared();
+        debug_assert!(is_locked);
 
         SharedBorrow(self.0)
     }
@@ -0,0 +1,191 @@
+use crate::atomic_refcell::{Ref, SharedBorrow};
+use crate::borrow::{AllStoragesBorrow, Borrow};
+use crate::error;
+use crate::sparse_set::{AddComponent, Remove};
+use crate::storage::{AllStorages, EntityId};
+use crate::world::{SystemContext, TypeInfo, World};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use parking_lot::Mutex;
+
+type Command = Box<dyn FnOnce(&mut AllStorages) + Send + 'static>;
+
+/// Commands recorded by all `Commands` views and not applied yet.
+#[derive(Default)]
+pub(crate) struct CommandQueue {
+    buffers: Mutex<Vec<(Vec<usize>, Vec<Command>)>>,
+}
+
+impl CommandQueue {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.buffers.lock().is_empty()
+    }
+    fn push(&self, key: Vec<usize>, commands: Vec<Command>) {
+        self.buffers.lock().push((key, commands));
+    }
+    /// Removes all buffers from the queue, ordered by the position of the system that recorded them.
+    pub(crate) fn take(&self) -> Vec<(Vec<usize>, Vec<Command>)> {
+        let mut buffers = core::mem::take(&mut *self.buffers.lock());
+        // stable sort, buffers from the same system keep their order
+        buffers.sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+        buffers
+    }
+}
+
+/// Records structural changes to apply later to `AllStorages`.
+///
+/// Borrowing `Commands` doesn't borrow any storage, systems using it can run in parallel with any other system.
+/// Each borrow gets its own buffer, the commands are queued when the view is dropped.
+/// Workloads apply queued commands at the end of each batch, in the order of the systems in the workload,
+/// no matter which thread they ran on.
+/// Outside of workloads, commands are applied with [`World::apply_commands`].
+///
+/// The position of the system is captured when the view is borrowed with the system's other views.
+/// To record commands from other threads, with rayon for example, clone the view:
+/// clones get their own buffer but keep the position of the system.
+/// A `Commands` borrowed from a thread that isn't running a system is applied before the commands of all systems.
+///
+/// Commands that can't be applied, like adding a component to a deleted entity, are ignored.
+///
+/// ### Example
+/// ```
+/// use shipyard::{system, Commands, IntoIter, View, Workload, World};
+///
+/// fn spawn(mut commands: Commands, u32s: View<u32>) {
+///     for &i in u32s.iter() {
+///         commands.add_entity((i as usize,));
+///     }
+/// }
+///
+/// fn check(usizes: View<usize>) {
+///     assert_eq!(usizes.len(), 2);
+/// }
+///
+/// let mut world = World::new();
+/// world.add_entity((0u32,));
+/// world.add_entity((1u32,));
+///
+/// Workload::builder("Spawn")
+///     .with_system(system!(spawn))
+///     .with_system(system!(check))
+///     .after(system!(spawn))
+///     .add_to_world(&world)
+///     .unwrap();
+///
+/// world.run_default();
+/// ```
+///
+/// [`World::apply_commands`]: struct.World.html#method.apply_commands
+pub struct Commands<'a> {
+    queue: &'a CommandQueue,
+    key: Vec<usize>,
+    commands: Vec<Command>,
+    _all_borrow: Option<SharedBorrow<'a>>,
+}
+
+impl<'a> Commands<'a> {
+    fn new(all_storages: &'a AllStorages, all_borrow: Option<SharedBorrow<'a>>) -> Self {
+        Commands {
+            queue: &all_storages.commands,
+            #[cfg(feature = "std")]
+            key: crate::world::scheduler::current_system::current_key(),
+            #[cfg(not(feature = "std"))]
+            key: Vec::new(),
+            commands: Vec::new(),
+            _all_borrow: all_borrow,
+        }
+    }
+    /// Records the creation of an entity with `components`.
+    /// `C` must always be a tuple, even for a single component.
+    pub fn add_entity<C: AddComponent + Send + 'static>(&mut self, components: C) {
+        self.push(move |all_storages| {
+            all_storages.add_entity(components);
+        });
+    }
+    /// Records the addition of `components` to `entity`.
+    /// `C` must always be a tuple, even for a single component.
+    pub fn add_component<C: AddComponent + Send + 'static>(
+        &mut self,
+        entity: EntityId,
+        components: C,
+    ) {
+        self.push(move |all_storages| {
+            let _ = all_storages.add_component(entity, components);
+        });
+    }
+    /// Records the removal of `C` components from `entity`.
+    /// `C` must always be a tuple, even for a single component.
+    pub fn remove<C: Remove + 'static>(&mut self, entity: EntityId) {
+        self.push(move |all_storages| {
+            all_storages.remove::<C>(entity);
+        });
+    }
+    /// Records the deletion of `entity` with all its components.
+    pub fn delete_entity(&mut self, entity: EntityId) {
+        self.push(move |all_storages| {
+            all_storages.delete_entity(entity);
+        });
+    }
+    /// Records a custom command.
+    pub fn push<F: FnOnce(&mut AllStorages) + Send + 'static>(&mut self, command: F) {
+        self.commands.push(Box::new(command));
+    }
+    /// Returns the number of commands recorded by this view.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+    /// Returns `true` if this view didn't record any command.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+impl Clone for Commands<'_> {
+    /// Returns a new view with an empty buffer, its commands are applied with the ones of the system that borrowed `self`.
+    fn clone(&self) -> Self {
+        Commands {
+            queue: self.queue,
+            key: self.key.clone(),
+            commands: Vec::new(),
+            _all_borrow: self._all_borrow.clone(),
+        }
+    }
+}
+
+impl Drop for Commands<'_> {
+    fn drop(&mut self) {
+        if !self.commands.is_empty() {
+            self.queue.push(
+                core::mem::take(&mut self.key),
+                core::mem::take(&mut self.commands),
+            );
+        }
+    }
+}
+
+impl<'a> Borrow<'a> for Commands<'a> {
+    #[inline]
+    fn try_borrow(world: &'a World) -> Result<Self, error::GetStorage> {
+        let (all_storages, all_borrow) = unsafe {
+            Ref::destructure(
+                world
+                    .all_storages
+                    .try_borrow()
+                    .map_err(error::GetStorage::AllStoragesBorrow)?,
+            )
+        };
+
+        Ok(Commands::new(all_storages, Some(all_borrow)))
+    }
+
+    fn borrow_info(infos: &mut Vec<TypeInfo>) {
+        infos.push(SystemContext::type_info());
+    }
+}
+
+impl<'a> AllStoragesBorrow<'a> for Commands<'a> {
+    #[inline]
+    fn try_borrow(all_storages: &'a AllStorages) -> Result<Self, error::GetStorage> {
+        Ok(Commands::new(all_storages, None))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::pack::tight::PackSync;
 use crate::sparse_set::SparseSet;
 use crate::storage::EntityId;
 use crate::view::ViewMut;
@@ -47,12 +48,25 @@ impl<T: 'static> Delete for &mut ViewMut<'_, T> {
 
 macro_rules! impl_delete_component {
     ($(($storage: ident, $index: tt))+) => {
-        impl<$($storage: Delete),+> Delete for ($($storage,)+) {
+        impl<$($storage: Delete + PackSync),+> Delete for ($($storage,)+) {
             #[inline]
             fn delete(&mut self, entity: EntityId) -> bool {
-                $(
-                    self.$index.delete(entity)
-                )&&+
+                let types = [$(self.$index.storage_type_id()),+];
+                let was_broken = [$(self.$index.is_pack_broken()),+];
+
+                // all storages have to delete their component, even if one of them didn't have any
+                let deleted = [$(self.$index.delete(entity)),+];
+
+                // the pack is only still valid if the entity was removed from all storages
+                if deleted.iter().all(|&deleted| deleted) {
+                    $(
+                        self.$index.sync_pack(entity, &types, was_broken[$index]);
+                    )+
+
+                    true
+                } else {
+                    false
+                }
             }
         }
     }
@@ -0,0 +1,518 @@
+use crate::atomic_refcell::{AtomicRefCell, Ref, RefMut, SharedBorrow};
+use crate::sparse_set::{Metadata, SparseArray, BUCKET_SIZE};
+use crate::storage::{merged_id, EntityId};
+use crate::unknown_storage::UnknownStorage;
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, realloc, Layout};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+
+/// Describes a component defined at runtime, by a scripting layer for example.
+///
+/// Dynamic components are stored in a [`DynamicSparseSet`] registered under a `StorageId::Custom` id
+/// with [`World::register_dynamic_component`].
+/// They're then borrowed with [`World::borrow_dynamic`] and [`World::borrow_dynamic_mut`],
+/// and can be iterated together with typed views.
+///
+/// ### Example
+/// ```
+/// use shipyard::{ComponentDescriptor, EntitiesViewMut, IntoIter, ViewMut, World};
+///
+/// const POSITION: u64 = 0;
+///
+/// let world = World::new();
+///
+/// world.register_dynamic_component(POSITION, ComponentDescriptor::of::<[f32; 2]>("position"));
+///
+/// let entity = world.run(|mut entities: EntitiesViewMut, mut u32s: ViewMut<u32>| {
+///     entities.add_entity(&mut u32s, 0u32)
+/// });
+///
+/// let mut positions = world.borrow_dynamic_mut(POSITION);
+/// let mut position = [1.0f32, 2.0];
+/// unsafe { positions.insert(entity, &mut position as *mut [f32; 2] as *mut u8) };
+///
+/// let u32s = world.borrow::<ViewMut<u32>>();
+/// for (&x, position) in (&u32s, &positions).iter() {
+///     let position = unsafe { &*(position as *const [f32; 2]) };
+///     assert_eq!(x, 0);
+///     assert_eq!(*position, [1.0, 2.0]);
+/// }
+/// ```
+///
+/// [`DynamicSparseSet`]: struct.DynamicSparseSet.html
+/// [`World::register_dynamic_component`]: struct.World.html#method.register_dynamic_component
+/// [`World::borrow_dynamic`]: struct.World.html#method.borrow_dynamic
+/// [`World::borrow_dynamic_mut`]: struct.World.html#method.borrow_dynamic_mut
+#[derive(Clone, Copy, Debug)]
+pub struct ComponentDescriptor {
+    name: &'static str,
+    layout: Layout,
+    drop: Option<unsafe fn(*mut u8)>,
+}
+
+impl ComponentDescriptor {
+    /// Creates a descriptor for components of `layout`.
+    /// `drop` is called with a pointer to each component when it's deleted, it can be `None` for components without drop glue.
+    /// `name` is used in error messages and workload information.
+    pub fn new(name: &'static str, layout: Layout, drop: Option<unsafe fn(*mut u8)>) -> Self {
+        ComponentDescriptor { name, layout, drop }
+    }
+    /// Creates a descriptor with the layout and drop glue of `T`.
+    pub fn of<T: 'static + Send + Sync>(name: &'static str) -> Self {
+        ComponentDescriptor {
+            name,
+            layout: Layout::new::<T>(),
+            drop: if core::mem::needs_drop::<T>() {
+                Some(drop_component::<T>)
+            } else {
+                None
+            },
+        }
+    }
+    /// Returns the name of the component.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns the layout of the component.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+}
+
+unsafe fn drop_component<T>(component: *mut u8) {
+    ptr::drop_in_place(component as *mut T);
+}
+
+/// Growable buffer of components whose layout is only known at runtime.
+struct ErasedVec {
+    ptr: NonNull<u8>,
+    len: usize,
+    capacity: usize,
+    size: usize,
+    /// Size of a component padded to its alignment, the distance between two components.
+    stride: usize,
+    align: usize,
+    drop: Option<unsafe fn(*mut u8)>,
+}
+
+impl ErasedVec {
+    fn new(descriptor: &ComponentDescriptor) -> Self {
+        let padded = descriptor.layout.pad_to_align();
+
+        ErasedVec {
+            // dangling but aligned, zero sized components never allocate
+            ptr: unsafe { NonNull::new_unchecked(padded.align() as *mut u8) },
+            len: 0,
+            capacity: if padded.size() == 0 { usize::MAX } else { 0 },
+            size: descriptor.layout.size(),
+            stride: padded.size(),
+            align: padded.align(),
+            drop: descriptor.drop,
+        }
+    }
+    #[inline]
+    fn get(&self, index: usize) -> *mut u8 {
+        self.ptr.as_ptr().wrapping_add(index * self.stride)
+    }
+    fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+
+        if required <= self.capacity {
+            return;
+        }
+
+        let capacity = required.max(self.capacity * 2).max(4);
+        let layout = Layout::from_size_align(
+            self.stride
+                .checked_mul(capacity)
+                .expect("capacity overflow"),
+            self.align,
+        )
+        .expect("capacity overflow");
+
+        let ptr = unsafe {
+            if self.capacity == 0 {
+                alloc(layout)
+            } else {
+                realloc(
+                    self.ptr.as_ptr(),
+                    Layout::from_size_align_unchecked(self.stride * self.capacity, self.align),
+                    layout.size(),
+                )
+            }
+        };
+
+        self.ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+        self.capacity = capacity;
+    }
+    /// Moves the component pointed by `component` at the end of the buffer.
+    unsafe fn push(&mut self, component: *const u8) {
+        self.reserve(1);
+        ptr::copy_nonoverlapping(component, self.get(self.len), self.size);
+        self.len += 1;
+    }
+    /// Drops the component at `index` and moves `component` in its place.
+    unsafe fn replace(&mut self, index: usize, component: *const u8) {
+        self.drop_at(index);
+        ptr::copy_nonoverlapping(component, self.get(index), self.size);
+    }
+    unsafe fn drop_at(&self, index: usize) {
+        if let Some(drop) = self.drop {
+            (drop)(self.get(index));
+        }
+    }
+    /// Removes the component at `index`, dropping it if `drop` is `true`, and moves the last component in its place.
+    unsafe fn swap_remove(&mut self, index: usize, drop: bool) {
+        if drop {
+            self.drop_at(index);
+        }
+
+        self.len -= 1;
+        if index != self.len {
+            ptr::copy_nonoverlapping(self.get(self.len), self.get(index), self.size);
+        }
+    }
+    fn clear(&mut self) {
+        let len = self.len;
+
+        // the length is reset first in case a drop function panics
+        self.len = 0;
+        for index in 0..len {
+            unsafe { self.drop_at(index) };
+        }
+    }
+}
+
+impl Drop for ErasedVec {
+    fn drop(&mut self) {
+        self.clear();
+
+        if self.stride != 0 && self.capacity != 0 {
+            unsafe {
+                dealloc(
+                    self.ptr.as_ptr(),
+                    Layout::from_size_align_unchecked(self.stride * self.capacity, self.align),
+                );
+            }
+        }
+    }
+}
+
+/// Type erased storage of components described by a [`ComponentDescriptor`].
+///
+/// Components are accessed through raw pointers, it's up to the caller to read and write them with the right layout.
+/// Iterating a dynamic storage yields `*const u8` for [`DynamicView`] and `*mut u8` for [`DynamicViewMut`].
+///
+/// [`ComponentDescriptor`]: struct.ComponentDescriptor.html
+/// [`DynamicView`]: struct.DynamicView.html
+/// [`DynamicViewMut`]: struct.DynamicViewMut.html
+pub struct DynamicSparseSet {
+    descriptor: ComponentDescriptor,
+    sparse: SparseArray<[EntityId; BUCKET_SIZE]>,
+    pub(crate) dense: Vec<EntityId>,
+    data: ErasedVec,
+    /// Dynamic storages are never packed, this only allows them to be iterated with typed storages.
+    pub(crate) metadata: Metadata<()>,
+    /// Dynamic components can't be cloned, cloning an entity with one is an error unless the storage is skipped.
+    skip_cloning: bool,
+}
+
+// SAFE `insert`'s caller guarantees components can be sent and shared between threads
+unsafe impl Send for DynamicSparseSet {}
+
+unsafe impl Sync for DynamicSparseSet {}
+
+impl DynamicSparseSet {
+    /// Creates an empty storage of components described by `descriptor`.
+    pub fn new(descriptor: ComponentDescriptor) -> Self {
+        DynamicSparseSet {
+            descriptor,
+            sparse: SparseArray::new(),
+            dense: Vec::new(),
+            data: ErasedVec::new(&descriptor),
+            metadata: Default::default(),
+            skip_cloning: false,
+        }
+    }
+    /// Returns the descriptor of the components in this storage.
+    pub fn descriptor(&self) -> &ComponentDescriptor {
+        &self.descriptor
+    }
+    /// Returns the index of `entity`'s component.
+    /// This index is only valid for this storage and until a modification happens.
+    #[inline]
+    pub fn index_of(&self, entity: EntityId) -> Option<usize> {
+        self.sparse.get(entity).and_then(|sparse_entity| {
+            if entity.gen() == sparse_entity.gen() {
+                Some(sparse_entity.uindex())
+            } else {
+                None
+            }
+        })
+    }
+    /// Returns the `EntityId` at a given `index`.
+    #[inline]
+    pub fn id_at(&self, index: usize) -> Option<EntityId> {
+        self.dense.get(index).copied()
+    }
+    /// Returns `true` if `entity` owns a component in this storage.
+    #[inline]
+    pub fn contains(&self, entity: EntityId) -> bool {
+        self.index_of(entity).is_some()
+    }
+    /// Returns the length of the storage.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+    /// Returns true if the storage's length is 0.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+    /// Returns a pointer to `entity`'s component.
+    #[inline]
+    pub fn get(&self, entity: EntityId) -> Option<*const u8> {
+        self.index_of(entity)
+            .map(|index| self.data.get(index) as *const u8)
+    }
+    /// Returns a pointer to `entity`'s component.
+    #[inline]
+    pub fn get_mut(&mut self, entity: EntityId) -> Option<*mut u8> {
+        self.index_of(entity).map(|index| self.data.get(index))
+    }
+    /// Moves the component pointed by `component` to `entity`, dropping the component it had if there was one.
+    /// The component is dropped instead if `entity` is older than the entity owning the component at its index.
+    ///
+    /// ### Safety
+    ///
+    /// `component` has to point to a valid value of the layout described by this storage's descriptor,
+    /// that can be sent and shared between threads.
+    /// The value is moved, it shouldn't be used or dropped afterwards.
+    pub unsafe fn insert(&mut self, mut entity: EntityId, component: *mut u8) {
+        self.sparse.allocate_at(entity);
+
+        // at this point there can't be nothing at the sparse index
+        let sparse_entity = self.sparse.get_mut_unchecked(entity);
+
+        if sparse_entity.is_dead() {
+            *sparse_entity = EntityId::new_from_parts(self.dense.len() as u64, entity.gen(), 0);
+
+            entity.clear_meta();
+            self.dense.push(entity);
+            self.data.push(component);
+        } else if entity.gen() >= sparse_entity.gen() {
+            let index = sparse_entity.uindex();
+
+            sparse_entity.copy_gen(entity);
+            self.dense.get_unchecked_mut(index).copy_index_gen(entity);
+            self.data.replace(index, component);
+        } else if let Some(drop) = self.descriptor.drop {
+            (drop)(component);
+        }
+    }
+    /// Deletes `entity`'s component from this storage.
+    /// Returns `true` if `entity` had a component.
+    #[inline]
+    pub fn delete(&mut self, entity: EntityId) -> bool {
+        unsafe { self.actual_remove(entity, true) }
+    }
+    /// Removes `entity`'s component, dropping it if `drop` is `true`.
+    ///
+    /// ### Safety
+    ///
+    /// If `drop` is `false` the component has to be moved out before it's called.
+    unsafe fn actual_remove(&mut self, entity: EntityId, drop: bool) -> bool {
+        let sparse_entity = match self.sparse.get(entity) {
+            Some(sparse_entity) => sparse_entity,
+            None => return false,
+        };
+
+        if entity.gen() >= sparse_entity.gen() {
+            *self.sparse.get_mut_unchecked(entity) = EntityId::dead();
+
+            self.dense.swap_remove(sparse_entity.uindex());
+            self.data.swap_remove(sparse_entity.uindex(), drop);
+
+            if sparse_entity.uindex() < self.dense.len() {
+                let last = *self.dense.get_unchecked(sparse_entity.uindex());
+                self.sparse
+                    .get_mut_unchecked(last)
+                    .copy_index(sparse_entity);
+            }
+
+            entity.gen() == sparse_entity.gen()
+        } else {
+            false
+        }
+    }
+    /// Makes [`AllStorages::clone_entity`] ignore this storage, clones won't have a component in it.  
+    /// Without it, cloning an entity with a component in this storage is an error since dynamic components can't be cloned.
+    ///
+    /// [`AllStorages::clone_entity`]: struct.AllStorages.html#method.clone_entity
+    pub fn skip_cloning(&mut self) {
+        self.skip_cloning = true;
+    }
+    /// Deletes all components in this storage.
+    pub fn clear(&mut self) {
+        for &id in &self.dense {
+            unsafe {
+                *self.sparse.get_mut_unchecked(id) = EntityId::dead();
+            }
+        }
+
+        self.dense.clear();
+        self.data.clear();
+    }
+    #[inline]
+    pub(crate) fn get_data(&self, index: usize) -> *const u8 {
+        self.data.get(index)
+    }
+    #[inline]
+    pub(crate) fn window_mut(&mut self) -> DynamicWindowMut<'_> {
+        DynamicWindowMut { storage: self }
+    }
+}
+
+impl UnknownStorage for DynamicSparseSet {
+    #[inline]
+    fn delete(&mut self, entity: EntityId) {
+        DynamicSparseSet::delete(self, entity);
+    }
+    #[inline]
+    fn clear(&mut self) {
+        DynamicSparseSet::clear(self);
+    }
+    #[inline]
+    fn can_clone(&self, entity: EntityId) -> Result<(), &'static str> {
+        if !self.skip_cloning && self.contains(entity) {
+            Err(self.descriptor.name)
+        } else {
+            Ok(())
+        }
+    }
+    #[inline]
+    fn new_empty(&self) -> Option<Box<AtomicRefCell<dyn UnknownStorage>>> {
+        // SAFE the caller copies this storage's thread restrictions
+        Some(Box::new(unsafe {
+            AtomicRefCell::new_unrestricted(DynamicSparseSet::new(self.descriptor))
+        }))
+    }
+    fn move_component(
+        &mut self,
+        entity: EntityId,
+        other: &mut dyn UnknownStorage,
+        new_entity: EntityId,
+    ) {
+        if let Some(other) = other.any_mut().downcast_mut::<DynamicSparseSet>() {
+            assert_layout(&self.descriptor, &other.descriptor);
+
+            if let Some(index) = self.index_of(entity) {
+                // SAFE the component is moved to `other` before being removed without drop
+                unsafe {
+                    other.insert(new_entity, self.data.get(index));
+                    self.actual_remove(entity, false);
+                }
+            }
+        }
+    }
+    fn merge(&mut self, other: &mut dyn UnknownStorage, entities_map: &[(EntityId, EntityId)]) {
+        if let Some(other) = other.any_mut().downcast_mut::<DynamicSparseSet>() {
+            assert_layout(&self.descriptor, &other.descriptor);
+
+            let dense = core::mem::take(&mut other.dense);
+            other.sparse = SparseArray::new();
+            // the length is reset first in case a drop function panics, the remaining components are leaked
+            other.data.len = 0;
+
+            // SAFE each component is either moved or dropped exactly once
+            unsafe {
+                for (index, &entity) in dense.iter().enumerate() {
+                    match merged_id(entities_map, entity) {
+                        Some(new_entity) => self.insert(new_entity, other.data.get(index)),
+                        None => other.data.drop_at(index),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Panics if a component registered with the same id in two `World`s doesn't have the same layout in both,
+/// its components can't be moved from one to the other.
+#[track_caller]
+fn assert_layout(descriptor: &ComponentDescriptor, other: &ComponentDescriptor) {
+    assert!(
+        descriptor.layout == other.layout,
+        "Dynamic component \"{}\" doesn't have the same layout in both Worlds.",
+        descriptor.name
+    );
+}
+
+/// Allows iterating a `DynamicSparseSet` mutably, yields `*mut u8`.
+pub struct DynamicWindowMut<'a> {
+    storage: &'a DynamicSparseSet,
+}
+
+impl<'a> DynamicWindowMut<'a> {
+    #[inline]
+    pub(crate) fn get(&self, index: usize) -> *mut u8 {
+        self.storage.data.get(index)
+    }
+}
+
+impl<'a> Deref for DynamicWindowMut<'a> {
+    type Target = DynamicSparseSet;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.storage
+    }
+}
+
+/// Shared view over a [`DynamicSparseSet`], borrowed by id with [`World::borrow_dynamic`] or [`AllStorages::borrow_dynamic`].
+///
+/// [`DynamicSparseSet`]: struct.DynamicSparseSet.html
+/// [`World::borrow_dynamic`]: struct.World.html#method.borrow_dynamic
+/// [`AllStorages::borrow_dynamic`]: struct.AllStorages.html#method.borrow_dynamic
+pub struct DynamicView<'a> {
+    pub(crate) storage: Ref<'a, &'a DynamicSparseSet>,
+    pub(crate) _all_borrow: Option<SharedBorrow<'a>>,
+}
+
+impl Deref for DynamicView<'_> {
+    type Target = DynamicSparseSet;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.storage
+    }
+}
+
+/// Exclusive view over a [`DynamicSparseSet`], borrowed by id with [`World::borrow_dynamic_mut`] or [`AllStorages::borrow_dynamic_mut`].
+///
+/// [`DynamicSparseSet`]: struct.DynamicSparseSet.html
+/// [`World::borrow_dynamic_mut`]: struct.World.html#method.borrow_dynamic_mut
+/// [`AllStorages::borrow_dynamic_mut`]: struct.AllStorages.html#method.borrow_dynamic_mut
+pub struct DynamicViewMut<'a> {
+    pub(crate) storage: RefMut<'a, &'a mut DynamicSparseSet>,
+    pub(crate) _all_borrow: Option<SharedBorrow<'a>>,
+}
+
+impl Deref for DynamicViewMut<'_> {
+    type Target = DynamicSparseSet;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.storage
+    }
+}
+
+impl DerefMut for DynamicViewMut<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.storage
+    }
+}
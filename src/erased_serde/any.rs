@@ -79,7 +79,7 @@ impl Drop for Any {
 struct Fingerprint {
     size: usize,
     align: usize,
-    id: usize,
+    id: &'static str,
 }
 
 impl Fingerprint {
@@ -87,11 +87,10 @@ impl Fingerprint {
         Fingerprint {
             size: mem::size_of::<T>(),
             align: mem::align_of::<T>(),
-            // This is not foolproof -- theoretically Rust or LLVM could
-            // deduplicate some or all of these methods. But in practice it's
-            // great and I am comfortable relying on this in debug mode to catch
-            // bugs early.
-            id: Fingerprint::of::<T> as usize,
+            // The address of `Fingerprint::of::<T>` isn't stable across codegen units
+            // so the type's name is used instead. It isn't guaranteed to be unique
+            // but in practice it's enough to catch bugs early.
+            id: any::type_name::<T>(),
         }
     }
 }
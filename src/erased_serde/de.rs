@@ -2,6 +2,7 @@
 
 use super::any::Any;
 use super::error::Error;
+use alloc::boxed::Box;
 use core::fmt::{self, Display};
 use serde::serde_if_integer128;
 
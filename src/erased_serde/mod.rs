@@ -8,6 +8,6 @@ mod de;
 mod error;
 mod ser;
 
-pub use de::{deserialize, DeserializeSeed, Deserializer, Visitor};
+pub use de::{deserialize, Deserializer};
 pub use error::{Error, Result};
 pub use ser::{serialize, Ok, Serialize, Serializer};
@@ -2,6 +2,7 @@
 
 use crate::EntityId;
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::fmt::{Debug, Display, Formatter};
 #[cfg(feature = "std")]
 use std::error::Error;
@@ -166,6 +167,10 @@ impl Display for AddComponent {
 pub enum AddWorkload {
     AlreadyExists,
     Borrow,
+    /// The system is part of a cycle of ordering constraints.
+    Cycle(&'static str),
+    /// No workload with this name was added to the `World` before.
+    MissingWorkload(&'static str),
 }
 
 #[cfg(feature = "std")]
@@ -178,6 +183,14 @@ impl Debug for AddWorkload {
             Self::Borrow => {
                 fmt.write_str("Cannot mutably borrow the scheduler while it's already borrowed.")
             }
+            Self::Cycle(system) => fmt.write_fmt(format_args!(
+                "{} is part of a cycle of ordering constraints, it has to run both before and after other systems.",
+                system
+            )),
+            Self::MissingWorkload(workload) => fmt.write_fmt(format_args!(
+                "No workload named {:?} was added to the World, it has to be added before being used in another workload.",
+                workload
+            )),
         }
     }
 }
@@ -224,6 +237,12 @@ pub enum RunWorkload {
     Scheduler,
     Run((&'static str, Run)),
     MissingWorkload,
+    /// The `FixedTimestep` or `FixedTimesteps` unique storage couldn't be borrowed.
+    FixedTimestep(GetStorage),
+    /// `AllStorages` couldn't be borrowed to apply the commands recorded during a batch.
+    ApplyCommands(Borrow),
+    /// `AllStorages` couldn't be borrowed to start a new events run.
+    UpdateEvents(Borrow),
 }
 
 impl RunWorkload {
@@ -256,6 +275,18 @@ impl Debug for RunWorkload {
             Self::Run((system_name, run)) => {
                 fmt.write_fmt(format_args!("System {} failed: {:?}", system_name, run))
             }
+            Self::FixedTimestep(get_storage) => fmt.write_fmt(format_args!(
+                "Cannot borrow the FixedTimestep or FixedTimesteps unique storage: {:?}",
+                get_storage
+            )),
+            Self::ApplyCommands(borrow) => fmt.write_fmt(format_args!(
+                "Cannot borrow AllStorages to apply commands: {:?}",
+                borrow
+            )),
+            Self::UpdateEvents(borrow) => fmt.write_fmt(format_args!(
+                "Cannot borrow AllStorages to start a new events run: {:?}",
+                borrow
+            )),
         }
     }
 }
@@ -314,6 +345,40 @@ impl Display for Run {
     }
 }
 
+/// Error returned by [`try_tight_pack`] and [`try_loose_pack`].
+///
+/// [`try_tight_pack`]: ../trait.TightPack.html#tymethod.try_tight_pack
+/// [`try_loose_pack`]: ../trait.LoosePack.html#tymethod.try_loose_pack
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Pack {
+    AlreadyTightPack(&'static str),
+    AlreadyLoosePack(&'static str),
+}
+
+#[cfg(feature = "std")]
+impl Error for Pack {}
+
+impl Debug for Pack {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::AlreadyTightPack(name) => fmt.write_fmt(format_args!(
+                "{} storage is already tight packed with other storages.",
+                name
+            )),
+            Self::AlreadyLoosePack(name) => fmt.write_fmt(format_args!(
+                "{} storage is already loose packed with other storages.",
+                name
+            )),
+        }
+    }
+}
+
+impl Display for Pack {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Debug::fmt(self, fmt)
+    }
+}
+
 /// Error when trying to use update pack related function on non update packed storage.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct NotUpdatePack;
@@ -453,3 +518,53 @@ impl Display for Apply {
         Debug::fmt(self, f)
     }
 }
+
+/// Error returned by [`AllStorages::try_clone_entity`].
+///
+/// [`AllStorages::try_clone_entity`]: ../struct.AllStorages.html#method.try_clone_entity
+#[derive(Clone, PartialEq, Eq)]
+pub enum CloneEntity {
+    EntityIsNotAlive,
+    /// Names of the components that can't be cloned.
+    NotCloneable(Vec<&'static str>),
+}
+
+#[cfg(feature = "std")]
+impl Error for CloneEntity {}
+
+impl Debug for CloneEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::EntityIsNotAlive => f.write_str("Entity has to be alive to be cloned."),
+            Self::NotCloneable(names) => f.write_fmt(format_args!(
+                "The entity has components that can't be cloned: {:?}. Their storage has to enable or skip cloning.",
+                names
+            )),
+        }
+    }
+}
+
+impl Display for CloneEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Debug::fmt(self, f)
+    }
+}
+
+/// Error returned when no template is registered under the requested name.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct MissingTemplate;
+
+#[cfg(feature = "std")]
+impl Error for MissingTemplate {}
+
+impl Debug for MissingTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.write_str("No template is registered under this name.")
+    }
+}
+
+impl Display for MissingTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Debug::fmt(self, f)
+    }
+}
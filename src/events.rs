@@ -0,0 +1,352 @@
+use crate::atomic_refcell::{Ref, RefMut, SharedBorrow};
+use crate::borrow::{AllStoragesBorrow, Borrow, Mutability};
+use crate::error;
+use crate::storage::{AllStorages, StorageId};
+use crate::unknown_storage::UnknownStorage;
+use crate::world::{SystemContext, TypeInfo, World};
+use alloc::vec::Vec;
+use core::any::type_name;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+/// Storage of `T` events, sent with [`EventWriter`] and read with [`EventReader`].
+///
+/// Events are double-buffered: they can be read during the workload run they were sent in and the next run of the same workload,
+/// then they're dropped. This way a system reading events doesn't have to run after the systems sending them.
+/// Each workload counts its own runs, running a workload doesn't drop the events sent by other workloads,
+/// so events sent by the workload of [`World::run_fixed_timestep`] aren't dropped by the fixed steps.
+/// Nested workloads don't start a new run, their events belong to the outer workload.
+/// Events sent outside of workloads, with [`World::run`] for example, are dropped after two runs of any workload
+/// that can borrow events: workloads with systems borrowing events, [`Commands`] or `AllStorages`, or without borrow info.
+///
+/// Each reader has its own cursor, all readers see all events once.
+/// Inside workloads the cursor is tied to the position of the system in the workload,
+/// outside of workloads all readers share the same cursor.
+/// Without the `std` feature all readers share the same cursor and all events are dropped after two runs of any workload.
+///
+/// [`EventWriter`]: struct.EventWriter.html
+/// [`EventReader`]: struct.EventReader.html
+/// [`World::run_fixed_timestep`]: struct.World.html#method.run_fixed_timestep
+/// [`World::run`]: struct.World.html#method.run
+/// [`Commands`]: struct.Commands.html
+pub struct Events<T> {
+    /// Events that weren't dropped yet, in the order they were sent.
+    events: Vec<Event<T>>,
+    /// Id of the next event sent.
+    next_id: usize,
+    /// Id of the next event to read for each reader, `None` is the reader outside of workloads.
+    cursors: Mutex<HashMap<Option<SystemId>, usize>>,
+}
+
+struct Event<T> {
+    id: usize,
+    /// Workload the event was sent from, `None` outside of workloads.
+    workload: Option<usize>,
+    /// Run of `workload` during which the event was sent.
+    run: usize,
+    event: T,
+}
+
+/// Top-level workload and position of a system, two systems never share a cursor,
+/// even if they have the same name or are the same system added twice.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct SystemId {
+    workload: usize,
+    key: Vec<usize>,
+}
+
+/// Number of runs started by each workload, `None` counts the runs of all workloads.
+#[derive(Default)]
+pub(crate) struct EventRuns(Mutex<HashMap<Option<usize>, usize>>);
+
+impl EventRuns {
+    /// Starts a new run of `workload`, events it sent two runs ago can't be read anymore.
+    pub(crate) fn start(&self, workload: usize) {
+        let mut runs = self.0.lock();
+
+        for workload in [None, Some(workload)].iter() {
+            let run = runs.entry(*workload).or_insert(0);
+            *run = run.wrapping_add(1);
+        }
+    }
+    fn current(&self, workload: Option<usize>) -> usize {
+        self.0.lock().get(&workload).copied().unwrap_or(0)
+    }
+    fn snapshot(&self) -> HashMap<Option<usize>, usize> {
+        self.0.lock().clone()
+    }
+}
+
+impl<T> Event<T> {
+    /// Returns `true` if the event can't be read anymore.
+    fn is_expired(&self, runs: &HashMap<Option<usize>, usize>) -> bool {
+        let current = runs.get(&self.workload).copied().unwrap_or(0);
+
+        current.wrapping_sub(self.run) >= 2
+    }
+}
+
+impl<T> Events<T> {
+    fn new() -> Self {
+        Events {
+            events: Vec::new(),
+            next_id: 0,
+            cursors: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Returns the events that can still be read and were sent after `cursor`.
+    fn unread<'a>(
+        &'a self,
+        runs: &'a HashMap<Option<usize>, usize>,
+        cursor: usize,
+    ) -> impl Iterator<Item = &'a Event<T>> + 'a {
+        // events are sorted by id
+        let first = match self.events.binary_search_by_key(&cursor, |event| event.id) {
+            Ok(index) | Err(index) => index,
+        };
+
+        self.events[first..]
+            .iter()
+            .filter(move |event| !event.is_expired(runs))
+    }
+}
+
+impl<T: 'static> UnknownStorage for Events<T> {}
+
+/// Exclusive view over `T` events, used to send them.
+///
+/// ### Example
+/// ```
+/// use shipyard::{system, EventReader, EventWriter, Workload, World};
+///
+/// struct Collision(u32);
+///
+/// fn detect(mut collisions: EventWriter<Collision>) {
+///     collisions.send(Collision(0));
+///     collisions.send(Collision(1));
+/// }
+///
+/// fn play_sound(mut collisions: EventReader<Collision>) {
+///     assert_eq!(collisions.iter().count(), 2);
+/// }
+///
+/// fn count(mut collisions: EventReader<Collision>) {
+///     assert_eq!(collisions.iter().map(|collision| collision.0).sum::<u32>(), 1);
+/// }
+///
+/// let world = World::new();
+///
+/// Workload::builder("Physics")
+///     .with_system(system!(detect))
+///     .with_system(system!(play_sound))
+///     .with_system(system!(count))
+///     .add_to_world(&world)
+///     .unwrap();
+///
+/// world.run_default();
+/// ```
+pub struct EventWriter<'a, T> {
+    events: RefMut<'a, &'a mut Events<T>>,
+    /// Workload the events are sent from.
+    workload: Option<usize>,
+    run: usize,
+    _all_borrow: Option<SharedBorrow<'a>>,
+}
+
+impl<'a, T: 'static + Send + Sync> EventWriter<'a, T> {
+    fn new(
+        all_storages: &'a AllStorages,
+        all_borrow: Option<SharedBorrow<'a>>,
+    ) -> Result<Self, error::GetStorage> {
+        let mut events = all_storages.custom_storage_or_insert_mut(Events::new)?;
+
+        let runs = all_storages.events_runs.snapshot();
+        events.events.retain(|event| !event.is_expired(&runs));
+
+        #[cfg(feature = "std")]
+        let workload = crate::world::scheduler::current_system::current_workload();
+        #[cfg(not(feature = "std"))]
+        let workload = None;
+
+        Ok(EventWriter {
+            events,
+            workload,
+            run: all_storages.events_runs.current(workload),
+            _all_borrow: all_borrow,
+        })
+    }
+}
+
+impl<T> EventWriter<'_, T> {
+    /// Sends `event` to all readers.
+    pub fn send(&mut self, event: T) {
+        let id = self.events.next_id;
+        self.events.next_id += 1;
+
+        self.events.events.push(Event {
+            id,
+            workload: self.workload,
+            run: self.run,
+            event,
+        });
+    }
+    /// Sends all `events` to all readers, in order.
+    pub fn send_batch<I: IntoIterator<Item = T>>(&mut self, events: I) {
+        for event in events {
+            self.send(event);
+        }
+    }
+}
+
+/// Shared view over `T` events, used to read them.
+/// Multiple systems reading the same events can run in parallel.
+///
+/// ### Example
+/// ```
+/// use shipyard::{EventReader, EventWriter, World};
+///
+/// let world = World::new();
+///
+/// world.run(|mut events: EventWriter<u32>| {
+///     events.send_batch(vec![0, 1, 2]);
+/// });
+///
+/// world.run(|mut events: EventReader<u32>| {
+///     assert_eq!(events.len(), 3);
+///     assert_eq!(events.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+///     assert!(events.is_empty());
+/// });
+/// ```
+pub struct EventReader<'a, T> {
+    events: Ref<'a, &'a Events<T>>,
+    /// Runs of all workloads when the reader was borrowed.
+    runs: HashMap<Option<usize>, usize>,
+    reader: Option<SystemId>,
+    _all_borrow: Option<SharedBorrow<'a>>,
+}
+
+impl<'a, T: 'static + Send + Sync> EventReader<'a, T> {
+    fn new(
+        all_storages: &'a AllStorages,
+        all_borrow: Option<SharedBorrow<'a>>,
+    ) -> Result<Self, error::GetStorage> {
+        #[cfg(feature = "std")]
+        let reader =
+            crate::world::scheduler::current_system::current_workload().map(|workload| SystemId {
+                workload,
+                key: crate::world::scheduler::current_system::current_key(),
+            });
+        #[cfg(not(feature = "std"))]
+        let reader = None;
+
+        Ok(EventReader {
+            events: all_storages.custom_storage_or_insert(Events::new)?,
+            runs: all_storages.events_runs.snapshot(),
+            reader,
+            _all_borrow: all_borrow,
+        })
+    }
+}
+
+impl<T> EventReader<'_, T> {
+    /// Returns the id of the next event this reader will read.
+    fn cursor(&self) -> usize {
+        self.events
+            .cursors
+            .lock()
+            .get(&self.reader)
+            .copied()
+            .unwrap_or(0)
+    }
+    /// Returns the number of events this reader didn't read yet.
+    pub fn len(&self) -> usize {
+        self.events.unread(&self.runs, self.cursor()).count()
+    }
+    /// Returns `true` if this reader read all events.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the events this reader didn't read yet, in the order they were sent.
+    /// They're marked as read, even if the iterator isn't consumed.
+    pub fn iter(&mut self) -> impl Iterator<Item = &T> + '_ {
+        let next_id = self.events.next_id;
+        let cursor = self
+            .events
+            .cursors
+            .lock()
+            .insert(self.reader.clone(), next_id)
+            .unwrap_or(0);
+
+        self.events
+            .unread(&self.runs, cursor)
+            .map(|event| &event.event)
+    }
+}
+
+impl<'a, T: 'static + Send + Sync> Borrow<'a> for EventWriter<'a, T> {
+    #[inline]
+    fn try_borrow(world: &'a World) -> Result<Self, error::GetStorage> {
+        let (all_storages, all_borrow) = unsafe {
+            Ref::destructure(
+                world
+                    .all_storages
+                    .try_borrow()
+                    .map_err(error::GetStorage::AllStoragesBorrow)?,
+            )
+        };
+
+        EventWriter::new(all_storages, Some(all_borrow))
+    }
+
+    fn borrow_info(infos: &mut Vec<TypeInfo>) {
+        infos.push(TypeInfo {
+            name: type_name::<Events<T>>(),
+            mutability: Mutability::Exclusive,
+            storage_id: StorageId::of::<Events<T>>(),
+            is_send: true,
+            is_sync: true,
+        });
+        infos.push(SystemContext::type_info());
+    }
+}
+
+impl<'a, T: 'static + Send + Sync> AllStoragesBorrow<'a> for EventWriter<'a, T> {
+    #[inline]
+    fn try_borrow(all_storages: &'a AllStorages) -> Result<Self, error::GetStorage> {
+        EventWriter::new(all_storages, None)
+    }
+}
+
+impl<'a, T: 'static + Send + Sync> Borrow<'a> for EventReader<'a, T> {
+    #[inline]
+    fn try_borrow(world: &'a World) -> Result<Self, error::GetStorage> {
+        let (all_storages, all_borrow) = unsafe {
+            Ref::destructure(
+                world
+                    .all_storages
+                    .try_borrow()
+                    .map_err(error::GetStorage::AllStoragesBorrow)?,
+            )
+        };
+
+        EventReader::new(all_storages, Some(all_borrow))
+    }
+
+    fn borrow_info(infos: &mut Vec<TypeInfo>) {
+        infos.push(TypeInfo {
+            name: type_name::<Events<T>>(),
+            mutability: Mutability::Shared,
+            storage_id: StorageId::of::<Events<T>>(),
+            is_send: true,
+            is_sync: true,
+        });
+        infos.push(SystemContext::type_info());
+    }
+}
+
+impl<'a, T: 'static + Send + Sync> AllStoragesBorrow<'a> for EventReader<'a, T> {
+    #[inline]
+    fn try_borrow(all_storages: &'a AllStorages) -> Result<Self, error::GetStorage> {
+        EventReader::new(all_storages, None)
+    }
+}
@@ -0,0 +1,187 @@
+use super::{Child, Parent};
+use crate::get::Get;
+use crate::storage::EntityId;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// Iterator over the children of an entity, in order.
+pub struct ChildrenIter<C> {
+    pub(super) get_child: C,
+    /// Next child and number of children left.
+    pub(super) cursor: (EntityId, usize),
+}
+
+impl<'a, C> Iterator for ChildrenIter<C>
+where
+    C: Get<Out = &'a Child> + Copy,
+{
+    type Item = EntityId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor.1 > 0 {
+            self.cursor.1 -= 1;
+            let ret = self.cursor.0;
+            self.cursor.0 = self.get_child.get(self.cursor.0).unwrap().next;
+            Some(ret)
+        } else {
+            None
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.cursor.1, Some(self.cursor.1))
+    }
+}
+
+impl<'a, C> ExactSizeIterator for ChildrenIter<C> where C: Get<Out = &'a Child> + Copy {}
+
+/// Iterator over the ancestors of an entity, from its parent to the root.
+pub struct AncestorIter<C> {
+    pub(super) get_child: C,
+    pub(super) cursor: EntityId,
+}
+
+impl<'a, C> Iterator for AncestorIter<C>
+where
+    C: Get<Out = &'a Child> + Copy,
+{
+    type Item = EntityId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.get_child.get(self.cursor).ok().map(|child| {
+            self.cursor = child.parent;
+            child.parent
+        })
+    }
+}
+
+/// Depth-first iterator over the descendants of an entity.
+pub struct DescendantsIter<P, C> {
+    pub(super) get_parent: P,
+    pub(super) get_child: C,
+    pub(super) cursors: Vec<(EntityId, usize)>,
+}
+
+impl<'a, P, C> Iterator for DescendantsIter<P, C>
+where
+    P: Get<Out = &'a Parent> + Copy,
+    C: Get<Out = &'a Child> + Copy,
+{
+    type Item = EntityId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(cursor) = self.cursors.last_mut() {
+            if cursor.1 > 0 {
+                cursor.1 -= 1;
+                let ret = cursor.0;
+                cursor.0 = self.get_child.get(cursor.0).unwrap().next;
+                if let Ok(parent) = self.get_parent.get(ret) {
+                    self.cursors.push((parent.first_child, parent.num_children));
+                }
+                return Some(ret);
+            } else {
+                self.cursors.pop();
+            }
+        }
+
+        None
+    }
+}
+
+/// Breadth-first iterator over the descendants of an entity.
+/// Parents are always visited before their children.
+pub struct BreadthFirstIter<P, C> {
+    pub(super) get_parent: P,
+    pub(super) get_child: C,
+    pub(super) cursors: VecDeque<(EntityId, usize)>,
+}
+
+impl<'a, P, C> Iterator for BreadthFirstIter<P, C>
+where
+    P: Get<Out = &'a Parent> + Copy,
+    C: Get<Out = &'a Child> + Copy,
+{
+    type Item = EntityId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(cursor) = self.cursors.front_mut() {
+            if cursor.1 > 0 {
+                cursor.1 -= 1;
+                let ret = cursor.0;
+                cursor.0 = self.get_child.get(cursor.0).unwrap().next;
+                if let Ok(parent) = self.get_parent.get(ret) {
+                    self.cursors
+                        .push_back((parent.first_child, parent.num_children));
+                }
+                return Some(ret);
+            } else {
+                self.cursors.pop_front();
+            }
+        }
+
+        None
+    }
+}
+
+/// Iterates a hierarchy.
+/// Implemented for `(P, C)` where `P` and `C` can be any view over `Parent` and `Child` respectively, like `(&View<Parent>, &View<Child>)`.
+pub trait HierarchyIter<'a, P, C> {
+    /// Returns the ancestors of `id`, from its parent to the root.
+    fn ancestors(&self, id: EntityId) -> AncestorIter<C>;
+    /// Returns the children of `id`, in order.
+    fn children(&self, id: EntityId) -> ChildrenIter<C>;
+    /// Returns the descendants of `id`, depth-first.
+    fn descendants(&self, id: EntityId) -> DescendantsIter<P, C>;
+    /// Returns the descendants of `id`, breadth-first.
+    fn breadth_first(&self, id: EntityId) -> BreadthFirstIter<P, C>;
+}
+
+impl<'a, P, C> HierarchyIter<'a, P, C> for (P, C)
+where
+    P: Get<Out = &'a Parent> + Copy,
+    C: Get<Out = &'a Child> + Copy,
+{
+    fn ancestors(&self, id: EntityId) -> AncestorIter<C> {
+        let (_, children) = self;
+
+        AncestorIter {
+            get_child: *children,
+            cursor: id,
+        }
+    }
+    fn children(&self, id: EntityId) -> ChildrenIter<C> {
+        let (parents, children) = self;
+
+        ChildrenIter {
+            get_child: *children,
+            cursor: parents
+                .get(id)
+                .map_or((id, 0), |parent| (parent.first_child, parent.num_children)),
+        }
+    }
+    fn descendants(&self, id: EntityId) -> DescendantsIter<P, C> {
+        let (parents, children) = self;
+
+        DescendantsIter {
+            get_parent: *parents,
+            get_child: *children,
+            cursors: parents.get(id).map_or_else(
+                |_| Vec::new(),
+                |parent| alloc::vec![(parent.first_child, parent.num_children)],
+            ),
+        }
+    }
+    fn breadth_first(&self, id: EntityId) -> BreadthFirstIter<P, C> {
+        let (parents, children) = self;
+
+        let mut cursors = VecDeque::new();
+        if let Ok(parent) = parents.get(id) {
+            cursors.push_back((parent.first_child, parent.num_children));
+        }
+
+        BreadthFirstIter {
+            get_parent: *parents,
+            get_child: *children,
+            cursors,
+        }
+    }
+}
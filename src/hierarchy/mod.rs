@@ -0,0 +1,350 @@
+//! Parent/child relationships between entities.
+//!
+//! The hierarchy is stored in two components: [`Parent`] for entities with children and [`Child`] for entities with a parent.
+//! It's modified with [`Hierarchy`], implemented for `(EntitiesViewMut, ViewMut<Parent>, ViewMut<Child>)`,
+//! and traversed with [`HierarchyIter`], implemented for any pair of views over `Parent` and `Child`.
+//!
+//! Deleting an entity with [`AllStorages::delete_entity`] also deletes all its descendants.
+//! Stripping an entity with [`AllStorages::strip`] detaches it from its parent, its children become roots.
+//!
+//! Components depending on their parent's, like transforms, can be updated with [`propagate`].
+//!
+//! ### Example
+//! ```
+//! use shipyard::hierarchy::{Child, Hierarchy, HierarchyIter, Parent};
+//! use shipyard::{EntitiesViewMut, View, ViewMut, World};
+//!
+//! let mut world = World::new();
+//!
+//! let (root, child, grandchild) = world.run(
+//!     |mut hierarchy: (EntitiesViewMut, ViewMut<Parent>, ViewMut<Child>)| {
+//!         let root = hierarchy.0.add_entity((), ());
+//!         let child = hierarchy.attach_new(root);
+//!         let grandchild = hierarchy.attach_new(child);
+//!
+//!         (root, child, grandchild)
+//!     },
+//! );
+//!
+//! world.run(|parents: View<Parent>, children: View<Child>| {
+//!     let hierarchy = (&parents, &children);
+//!
+//!     assert!(hierarchy.descendants(root).eq(vec![child, grandchild]));
+//!     assert!(hierarchy.ancestors(grandchild).eq(vec![child, root]));
+//! });
+//!
+//! world.delete_entity(root);
+//! assert!(!world.borrow::<shipyard::EntitiesView>().is_alive(grandchild));
+//! ```
+//!
+//! [`Parent`]: struct.Parent.html
+//! [`Child`]: struct.Child.html
+//! [`Hierarchy`]: trait.Hierarchy.html
+//! [`HierarchyIter`]: trait.HierarchyIter.html
+//! [`propagate`]: fn.propagate.html
+//! [`AllStorages::delete_entity`]: ../struct.AllStorages.html#method.delete_entity
+//! [`AllStorages::strip`]: ../struct.AllStorages.html#method.strip
+
+mod iter;
+mod propagate;
+
+pub use iter::{AncestorIter, BreadthFirstIter, ChildrenIter, DescendantsIter, HierarchyIter};
+#[cfg(feature = "parallel")]
+pub use propagate::par_propagate;
+pub use propagate::{propagate, Propagate};
+
+use crate::sparse_set::{Links, SparseSet};
+use crate::storage::{merged_id, AllStorages, EntityId};
+use crate::view::{EntitiesViewMut, ViewMut};
+use alloc::vec::Vec;
+
+/// Component of entities with at least one child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent {
+    num_children: usize,
+    first_child: EntityId,
+}
+
+impl Parent {
+    /// Returns the number of children of this entity.
+    pub fn num_children(&self) -> usize {
+        self.num_children
+    }
+    /// Returns the first child of this entity.
+    pub fn first_child(&self) -> EntityId {
+        self.first_child
+    }
+}
+
+/// Component of entities with a parent.
+///
+/// Siblings form a circular list: the last child's `next` is the first child and the first child's `prev` is the last child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Child {
+    parent: EntityId,
+    prev: EntityId,
+    next: EntityId,
+}
+
+impl Child {
+    /// Returns the parent of this entity.
+    pub fn parent(&self) -> EntityId {
+        self.parent
+    }
+    /// Returns the previous sibling of this entity.
+    pub fn prev(&self) -> EntityId {
+        self.prev
+    }
+    /// Returns the next sibling of this entity.
+    pub fn next(&self) -> EntityId {
+        self.next
+    }
+}
+
+/// Modifies a hierarchy.
+/// Implemented for `(EntitiesViewMut, ViewMut<Parent>, ViewMut<Child>)`.
+pub trait Hierarchy {
+    /// Removes the child status of `id`, its own children are not affected.
+    fn detach(&mut self, id: EntityId);
+    /// Attaches `id` as the last child of `parent`, detaching it from its previous parent if it had one.
+    ///
+    /// ### Panics
+    ///
+    /// - `parent` is `id` or one of its descendants.
+    fn attach(&mut self, id: EntityId, parent: EntityId);
+    /// Creates a new entity and attaches it as the last child of `parent`.
+    fn attach_new(&mut self, parent: EntityId) -> EntityId;
+    /// Removes `id` from the hierarchy, its children become roots.
+    fn remove(&mut self, id: EntityId);
+    /// Removes `id` and all its descendants from the hierarchy.
+    /// The entities are not deleted, use `AllStorages::delete_entity` to delete a subtree.
+    fn remove_all(&mut self, id: EntityId);
+    /// Sorts the children of `id` using `compare`.
+    fn sort_children_by<F>(&mut self, id: EntityId, compare: F)
+    where
+        F: FnMut(&EntityId, &EntityId) -> core::cmp::Ordering;
+}
+
+impl Hierarchy for (EntitiesViewMut<'_>, ViewMut<'_, Parent>, ViewMut<'_, Child>) {
+    fn detach(&mut self, id: EntityId) {
+        detach(&mut self.1, &mut self.2, id);
+    }
+    #[track_caller]
+    fn attach(&mut self, id: EntityId, parent: EntityId) {
+        assert!(
+            parent != id && !is_ancestor(&self.2, id, parent),
+            "An entity can't be attached to itself or one of its descendants."
+        );
+
+        // the entity we want to attach might already be attached to another parent
+        self.detach(id);
+
+        let (entities, parents, children) = self;
+
+        setup_links(parents, children);
+
+        if let Some(p) = parents.private_get_mut(parent) {
+            p.num_children += 1;
+
+            // the new child goes between the last and the first child
+            let next = p.first_child;
+            let prev = children[next].prev;
+
+            children[prev].next = id;
+            children[next].prev = id;
+
+            entities.add_component(id, children, Child { parent, prev, next });
+        } else {
+            // first child, the sibling list only contains the new child
+            entities.add_component(
+                id,
+                children,
+                Child {
+                    parent,
+                    prev: id,
+                    next: id,
+                },
+            );
+            entities.add_component(
+                parent,
+                parents,
+                Parent {
+                    num_children: 1,
+                    first_child: id,
+                },
+            );
+        }
+    }
+    fn attach_new(&mut self, parent: EntityId) -> EntityId {
+        let id = self.0.add_entity((), ());
+        self.attach(id, parent);
+        id
+    }
+    fn remove(&mut self, id: EntityId) {
+        remove(&mut self.1, &mut self.2, id);
+    }
+    fn remove_all(&mut self, id: EntityId) {
+        let descendants = descendants(&self.1, &self.2, id);
+
+        remove(&mut self.1, &mut self.2, id);
+        for descendant in descendants {
+            remove(&mut self.1, &mut self.2, descendant);
+        }
+    }
+    fn sort_children_by<F>(&mut self, id: EntityId, compare: F)
+    where
+        F: FnMut(&EntityId, &EntityId) -> core::cmp::Ordering,
+    {
+        let (_, parents, children_storage) = self;
+
+        let mut children = Vec::new();
+        if let Some(parent) = parents.private_get(id) {
+            let mut child = parent.first_child;
+            for _ in 0..parent.num_children {
+                children.push(child);
+                child = children_storage[child].next;
+            }
+        }
+
+        if children.len() > 1 {
+            children.sort_by(compare);
+
+            parents[id].first_child = children[0];
+
+            for pair in children.windows(2) {
+                children_storage[pair[0]].next = pair[1];
+                children_storage[pair[1]].prev = pair[0];
+            }
+
+            let first = children[0];
+            let last = *children.last().unwrap();
+            children_storage[first].prev = last;
+            children_storage[last].next = first;
+        }
+    }
+}
+
+/// Returns `true` if `ancestor` is one of `id`'s ancestors.
+fn is_ancestor(children: &SparseSet<Child>, ancestor: EntityId, mut id: EntityId) -> bool {
+    while let Some(child) = children.private_get(id) {
+        if child.parent == ancestor {
+            return true;
+        }
+
+        id = child.parent;
+    }
+
+    false
+}
+
+/// Removes `id`'s `Child` component and unlinks it from its siblings.
+fn detach(parents: &mut SparseSet<Parent>, children: &mut SparseSet<Child>, id: EntityId) {
+    if let Some(child) = children.remove(id) {
+        let parent = &mut parents[child.parent];
+        parent.num_children -= 1;
+
+        if parent.num_children == 0 {
+            parents.remove(child.parent);
+        } else {
+            if parent.first_child == id {
+                parent.first_child = child.next;
+            }
+
+            children[child.prev].next = child.next;
+            children[child.next].prev = child.prev;
+        }
+    }
+}
+
+/// Detaches `id` and all its children.
+fn remove(parents: &mut SparseSet<Parent>, children: &mut SparseSet<Child>, id: EntityId) {
+    detach(parents, children, id);
+
+    if let Some(parent) = parents.remove(id) {
+        let mut child = parent.first_child;
+        for _ in 0..parent.num_children {
+            child = children.remove(child).unwrap().next;
+        }
+    }
+}
+
+/// Returns all descendants of `id`, parents are always before their children.
+fn descendants(
+    parents: &SparseSet<Parent>,
+    children: &SparseSet<Child>,
+    id: EntityId,
+) -> Vec<EntityId> {
+    let mut descendants = Vec::new();
+    let mut stack = alloc::vec![id];
+
+    while let Some(id) = stack.pop() {
+        if let Some(parent) = parents.private_get(id) {
+            let mut child = parent.first_child;
+            for _ in 0..parent.num_children {
+                descendants.push(child);
+                stack.push(child);
+                child = children.private_get(child).unwrap().next;
+            }
+        }
+    }
+
+    descendants
+}
+
+/// Lets `AllStorages` keep the hierarchy valid when entities are stripped, deleted, cloned, moved or merged.
+fn setup_links(parents: &mut SparseSet<Parent>, children: &mut SparseSet<Child>) {
+    if children.metadata.links.is_none() {
+        parents.metadata.links = Some(Links {
+            remap: remap_parent,
+            unlink: None,
+            dependents: None,
+        });
+        children.metadata.links = Some(Links {
+            remap: remap_child,
+            unlink: Some(unlink),
+            dependents: Some(dependents),
+        });
+    }
+}
+
+/// Removes `entity` from the hierarchy, its children become roots.
+fn unlink(children: &mut SparseSet<Child>, entity: EntityId, all_storages: &AllStorages) {
+    if let Ok(mut parents) = all_storages.custom_storage_mut::<SparseSet<Parent>>() {
+        remove(&mut parents, children, entity);
+    }
+}
+
+/// Returns all descendants of `entity`, parents are always before their children.
+fn dependents(
+    children: &SparseSet<Child>,
+    entity: EntityId,
+    all_storages: &AllStorages,
+) -> Vec<EntityId> {
+    if let Ok(parents) = all_storages.custom_storage::<SparseSet<Parent>>() {
+        descendants(&parents, children, entity)
+    } else {
+        Vec::new()
+    }
+}
+
+fn remap_parent(parent: &mut Parent, entities_map: &[(EntityId, EntityId)]) -> bool {
+    if let Some(first_child) = merged_id(entities_map, parent.first_child) {
+        parent.first_child = first_child;
+        true
+    } else {
+        false
+    }
+}
+
+fn remap_child(child: &mut Child, entities_map: &[(EntityId, EntityId)]) -> bool {
+    if let (Some(parent), Some(prev), Some(next)) = (
+        merged_id(entities_map, child.parent),
+        merged_id(entities_map, child.prev),
+        merged_id(entities_map, child.next),
+    ) {
+        *child = Child { parent, prev, next };
+        true
+    } else {
+        false
+    }
+}
@@ -0,0 +1,288 @@
+use super::{Child, Parent};
+use crate::sparse_set::SparseSet;
+use crate::storage::EntityId;
+use alloc::vec::Vec;
+
+/// Computes a global component from the local component of an entity and the global component of its parent.
+/// Implemented by the local component, typically a local transform.
+///
+/// ### Example
+/// ```
+/// use shipyard::hierarchy::{self, Child, Hierarchy, Parent, Propagate};
+/// use shipyard::{EntitiesViewMut, View, ViewMut, World};
+///
+/// struct LocalPosition(f32);
+/// struct GlobalPosition(f32);
+///
+/// impl Propagate for LocalPosition {
+///     type Global = GlobalPosition;
+///
+///     fn root(local: &Self) -> GlobalPosition {
+///         GlobalPosition(local.0)
+///     }
+///     fn combine(parent: &GlobalPosition, local: &Self) -> GlobalPosition {
+///         GlobalPosition(parent.0 + local.0)
+///     }
+/// }
+///
+/// let world = World::new();
+///
+/// let child = world.run(
+///     |(mut hierarchy, mut locals): (
+///         (EntitiesViewMut, ViewMut<Parent>, ViewMut<Child>),
+///         ViewMut<LocalPosition>,
+///     )| {
+///         locals.update_pack();
+///
+///         let root = hierarchy.0.add_entity(&mut locals, LocalPosition(1.));
+///         let child = hierarchy.attach_new(root);
+///         hierarchy.0.add_component(child, &mut locals, LocalPosition(2.));
+///
+///         child
+///     },
+/// );
+///
+/// world.run(
+///     |parents: View<Parent>,
+///      children: View<Child>,
+///      locals: View<LocalPosition>,
+///      mut globals: ViewMut<GlobalPosition>| {
+///         hierarchy::propagate(&parents, &children, &locals, &mut globals);
+///
+///         assert_eq!(globals[child].0, 3.);
+///     },
+/// );
+/// ```
+pub trait Propagate: 'static + Sized {
+    /// The global component computed from this local component.
+    type Global: 'static;
+
+    /// Returns the global component of an entity without parent.
+    fn root(local: &Self) -> Self::Global;
+    /// Returns the global component of an entity from its parent's global component.
+    fn combine(parent: &Self::Global, local: &Self) -> Self::Global;
+}
+
+/// Updates the global component of all entities with a local component, parents before their children.
+///
+/// When both `locals` and `children` track modification, only entities whose local component was inserted or modified,
+/// whose global component is missing, whose `Child` component was inserted, modified or removed,
+/// or one of whose ancestors was updated, are updated.
+/// Otherwise all global components are recomputed, reparenting can't be detected without tracking `Child`.
+/// The modification flags are not cleared.
+///
+/// Entities without a local component are not part of the propagation, nor are their descendants.
+pub fn propagate<L: Propagate>(
+    parents: &SparseSet<Parent>,
+    children: &SparseSet<Child>,
+    locals: &SparseSet<L>,
+    globals: &mut SparseSet<L::Global>,
+) {
+    let dirtiness = Dirtiness::new(children, locals);
+    let mut updates = Vec::new();
+
+    for root in roots(children, locals) {
+        propagate_subtree(
+            root,
+            &dirtiness,
+            parents,
+            children,
+            locals,
+            globals,
+            &mut updates,
+        );
+    }
+
+    for (entity, global) in updates {
+        globals.insert(entity, global);
+    }
+}
+
+/// Same as [`propagate`] but each subtree starting at a root is processed in parallel.
+///
+/// [`propagate`]: fn.propagate.html
+#[cfg(feature = "parallel")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parallel")))]
+pub fn par_propagate<L>(
+    parents: &SparseSet<Parent>,
+    children: &SparseSet<Child>,
+    locals: &SparseSet<L>,
+    globals: &mut SparseSet<L::Global>,
+) where
+    L: Propagate + Sync,
+    L::Global: Send + Sync,
+{
+    use rayon::prelude::*;
+
+    let dirtiness = Dirtiness::new(children, locals);
+    let roots = roots(children, locals).collect::<Vec<_>>();
+    let shared_globals = &*globals;
+
+    let updates = roots
+        .into_par_iter()
+        .map(|root| {
+            let mut updates = Vec::new();
+
+            propagate_subtree(
+                root,
+                &dirtiness,
+                parents,
+                children,
+                locals,
+                shared_globals,
+                &mut updates,
+            );
+
+            updates
+        })
+        .collect::<Vec<_>>();
+
+    for (entity, global) in updates.into_iter().flatten() {
+        globals.insert(entity, global);
+    }
+}
+
+/// Returns the entities with a local component and no parent.
+fn roots<'a, L>(
+    children: &'a SparseSet<Child>,
+    locals: &'a SparseSet<L>,
+) -> impl Iterator<Item = EntityId> + 'a {
+    locals.dense.iter().filter_map(move |&entity| {
+        let mut entity = entity;
+        entity.clear_meta();
+
+        if children.contains(entity) {
+            None
+        } else {
+            Some(entity)
+        }
+    })
+}
+
+/// Decides which entities have to be propagated.
+struct Dirtiness {
+    /// `false` when everything has to be recomputed.
+    is_tracked: bool,
+    /// Sorted entities whose `Child` component was removed, they might have been detached.
+    detached: Vec<EntityId>,
+}
+
+impl Dirtiness {
+    fn new<L>(children: &SparseSet<Child>, locals: &SparseSet<L>) -> Self {
+        match (&locals.metadata.update, &children.metadata.update) {
+            (Some(_), Some(update)) => {
+                let mut detached = update
+                    .removed
+                    .iter()
+                    .copied()
+                    .chain(update.deleted.iter().map(|(entity, _)| *entity))
+                    .collect::<Vec<_>>();
+                detached.sort_unstable();
+
+                Dirtiness {
+                    is_tracked: true,
+                    detached,
+                }
+            }
+            _ => Dirtiness {
+                is_tracked: false,
+                detached: Vec::new(),
+            },
+        }
+    }
+    /// Returns `true` if `entity`'s local component or its place in the hierarchy changed.
+    fn is_dirty<L>(
+        &self,
+        children: &SparseSet<Child>,
+        locals: &SparseSet<L>,
+        entity: EntityId,
+        index: usize,
+    ) -> bool {
+        if !self.is_tracked {
+            return true;
+        }
+
+        let local = locals.dense[index];
+        if local.is_inserted() || local.is_modified() {
+            return true;
+        }
+
+        if let Some(child_index) = children.index_of(entity) {
+            let child = children.dense[child_index];
+            if child.is_inserted() || child.is_modified() {
+                return true;
+            }
+        }
+
+        self.detached.binary_search(&entity).is_ok()
+    }
+}
+
+/// Where the global component of an entity's parent is.
+enum ParentGlobal<'a, G> {
+    /// The entity is a root.
+    None,
+    /// The parent's global component didn't change.
+    Current(&'a G),
+    /// Index of the parent's new global component in `updates`.
+    Updated(usize),
+}
+
+// derive would require `G: Copy`
+impl<G> Clone for ParentGlobal<'_, G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G> Copy for ParentGlobal<'_, G> {}
+
+/// Computes the global components of `root` and its descendants, updates are stored in `updates`.
+fn propagate_subtree<L: Propagate>(
+    root: EntityId,
+    dirtiness: &Dirtiness,
+    parents: &SparseSet<Parent>,
+    children: &SparseSet<Child>,
+    locals: &SparseSet<L>,
+    globals: &SparseSet<L::Global>,
+    updates: &mut Vec<(EntityId, L::Global)>,
+) {
+    // an explicit stack keeps deep hierarchies from overflowing the call stack
+    let mut stack = alloc::vec![(root, ParentGlobal::None, false)];
+
+    while let Some((entity, parent, is_parent_dirty)) = stack.pop() {
+        let index = match locals.index_of(entity) {
+            Some(index) => index,
+            None => continue,
+        };
+
+        let local = &locals.data[index];
+        let global = globals.private_get(entity);
+        let is_dirty = is_parent_dirty
+            || global.is_none()
+            || dirtiness.is_dirty(children, locals, entity, index);
+
+        let global = if is_dirty {
+            let new_global = match parent {
+                ParentGlobal::None => L::root(local),
+                ParentGlobal::Current(parent) => L::combine(parent, local),
+                ParentGlobal::Updated(parent) => L::combine(&updates[parent].1, local),
+            };
+            updates.push((entity, new_global));
+
+            ParentGlobal::Updated(updates.len() - 1)
+        } else {
+            ParentGlobal::Current(global.unwrap())
+        };
+
+        if let Some(parent) = parents.private_get(entity) {
+            let mut child = parent.first_child;
+
+            for _ in 0..parent.num_children {
+                stack.push((child, global, is_dirty));
+
+                child = children.private_get(child).unwrap().next;
+            }
+        }
+    }
+}
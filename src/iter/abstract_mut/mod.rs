@@ -3,6 +3,7 @@ mod inserted_or_modified;
 mod modified;
 mod not;
 
+use crate::dynamic::{DynamicSparseSet, DynamicWindowMut};
 use crate::r#mut::Mut;
 use crate::sparse_set::{FullRawWindowMut, SparseSet};
 use crate::storage::EntityId;
@@ -95,6 +96,64 @@ impl<'tmp, T> AbstractMut for FullRawWindowMut<'tmp, T> {
     }
 }
 
+impl AbstractMut for &DynamicSparseSet {
+    type Out = *const u8;
+    type Index = usize;
+
+    #[inline]
+    unsafe fn get_data(&self, index: usize) -> Self::Out {
+        DynamicSparseSet::get_data(self, index)
+    }
+    #[inline]
+    unsafe fn get_datas(&self, index: Self::Index) -> Self::Out {
+        DynamicSparseSet::get_data(self, index)
+    }
+    #[inline]
+    fn indices_of(&self, entity_id: EntityId, _: usize, _: u16) -> Option<Self::Index> {
+        self.index_of(entity_id)
+    }
+    #[inline]
+    unsafe fn indices_of_unchecked(&self, entity_id: EntityId, _: usize, _: u16) -> Self::Index {
+        match self.index_of(entity_id) {
+            Some(index) => index,
+            None => core::hint::unreachable_unchecked(),
+        }
+    }
+    #[inline]
+    unsafe fn get_id(&self, index: usize) -> EntityId {
+        *self.dense.get_unchecked(index)
+    }
+}
+
+impl AbstractMut for DynamicWindowMut<'_> {
+    type Out = *mut u8;
+    type Index = usize;
+
+    #[inline]
+    unsafe fn get_data(&self, index: usize) -> Self::Out {
+        self.get(index)
+    }
+    #[inline]
+    unsafe fn get_datas(&self, index: Self::Index) -> Self::Out {
+        self.get(index)
+    }
+    #[inline]
+    fn indices_of(&self, entity_id: EntityId, _: usize, _: u16) -> Option<Self::Index> {
+        self.index_of(entity_id)
+    }
+    #[inline]
+    unsafe fn indices_of_unchecked(&self, entity_id: EntityId, _: usize, _: u16) -> Self::Index {
+        match self.index_of(entity_id) {
+            Some(index) => index,
+            None => core::hint::unreachable_unchecked(),
+        }
+    }
+    #[inline]
+    unsafe fn get_id(&self, index: usize) -> EntityId {
+        *self.dense.get_unchecked(index)
+    }
+}
+
 macro_rules! impl_abstract_mut {
     ($(($type: ident, $index: tt))+) => {
         impl<$($type: AbstractMut),+> AbstractMut for ($($type,)+) where $(<$type as AbstractMut>::Index: From<usize>),+ {
@@ -22,7 +22,7 @@ impl<Storage: FastAbstractMut> Iterator for FastChunkExact<Storage> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current + self.step < self.end {
+        if self.current + self.step <= self.end {
             self.current += self.step;
 
             Some(unsafe {
@@ -47,7 +47,7 @@ impl<Storage: FastAbstractMut> Iterator for FastChunkExact<Storage> {
         Self: Sized,
         F: FnMut(B, Self::Item) -> B,
     {
-        while self.current + self.step < self.end {
+        while self.current + self.step <= self.end {
             self.current += self.step;
 
             init = f(init, unsafe {
@@ -72,7 +72,7 @@ impl<Storage: FastAbstractMut> ExactSizeIterator for FastChunkExact<Storage> {
 impl<Storage: FastAbstractMut> DoubleEndedIterator for FastChunkExact<Storage> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        if self.current + self.step < self.end {
+        if self.current + self.step <= self.end {
             self.end -= self.step;
 
             Some(unsafe {
@@ -88,7 +88,7 @@ impl<Storage: FastAbstractMut> DoubleEndedIterator for FastChunkExact<Storage> {
         Self: Sized,
         F: FnMut(B, Self::Item) -> B,
     {
-        while self.current + self.step < self.end {
+        while self.current + self.step <= self.end {
             self.end -= self.step;
 
             init = f(init, unsafe {
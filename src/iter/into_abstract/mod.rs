@@ -4,6 +4,7 @@ mod modified;
 mod not;
 
 use super::abstract_mut::AbstractMut;
+use crate::dynamic::{DynamicSparseSet, DynamicView, DynamicViewMut, DynamicWindowMut};
 use crate::sparse_set::{FullRawWindowMut, Metadata, SparseSet};
 use crate::sparse_set::{SparseArray, BUCKET_SIZE};
 use crate::storage::EntityId;
@@ -106,3 +107,81 @@ impl<'a: 'b, 'b, T: 'static> IntoAbstract for &'b mut ViewMut<'a, T> {
         self.dense.as_ptr()
     }
 }
+
+impl<'a> IntoAbstract for &'a DynamicView<'_> {
+    type AbsView = &'a DynamicSparseSet;
+    type Pack = ();
+
+    #[inline]
+    fn into_abstract(self) -> Self::AbsView {
+        self
+    }
+    #[inline]
+    fn len(&self) -> Option<(usize, bool)> {
+        Some(((**self).len(), true))
+    }
+    #[inline]
+    fn metadata(&self) -> &Metadata<Self::Pack> {
+        &self.metadata
+    }
+    #[inline]
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<DynamicSparseSet>()
+    }
+    #[inline]
+    fn dense(&self) -> *const EntityId {
+        self.dense.as_ptr()
+    }
+}
+
+impl<'a: 'b, 'b> IntoAbstract for &'b DynamicViewMut<'a> {
+    type AbsView = &'b DynamicSparseSet;
+    type Pack = ();
+
+    #[inline]
+    fn into_abstract(self) -> Self::AbsView {
+        self
+    }
+    #[inline]
+    fn len(&self) -> Option<(usize, bool)> {
+        Some(((**self).len(), true))
+    }
+    #[inline]
+    fn metadata(&self) -> &Metadata<Self::Pack> {
+        &self.metadata
+    }
+    #[inline]
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<DynamicSparseSet>()
+    }
+    #[inline]
+    fn dense(&self) -> *const EntityId {
+        self.dense.as_ptr()
+    }
+}
+
+impl<'a: 'b, 'b> IntoAbstract for &'b mut DynamicViewMut<'a> {
+    type AbsView = DynamicWindowMut<'b>;
+    type Pack = ();
+
+    #[inline]
+    fn into_abstract(self) -> Self::AbsView {
+        self.window_mut()
+    }
+    #[inline]
+    fn len(&self) -> Option<(usize, bool)> {
+        Some(((**self).len(), true))
+    }
+    #[inline]
+    fn metadata(&self) -> &Metadata<Self::Pack> {
+        &self.metadata
+    }
+    #[inline]
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<DynamicSparseSet>()
+    }
+    #[inline]
+    fn dense(&self) -> *const EntityId {
+        self.dense.as_ptr()
+    }
+}
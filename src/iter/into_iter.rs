@@ -5,6 +5,8 @@ use super::mixed::Mixed;
 #[cfg(feature = "parallel")]
 use super::par_iter::ParIter;
 use super::tight::Tight;
+use crate::pack::loose::{loose_pack_entities, loose_pack_info};
+use crate::pack::tight::{tight_pack_info, tight_pack_len};
 use crate::sparse_set::SparseSet;
 use crate::storage::EntityId;
 use crate::type_id::TypeId;
@@ -226,6 +228,56 @@ macro_rules! impl_into_iter {
                     }
                 )+
 
+                let pack_infos = [
+                    tight_pack_info(self.$index1.type_id(), self.$index1.len(), self.$index1.metadata(), || self.$index1.dense()),
+                    $(tight_pack_info(self.$index.type_id(), self.$index.len(), self.$index.metadata(), || self.$index.dense()),)+
+                ];
+
+                if let Some((len, pack_mask)) = tight_pack_len(&pack_infos) {
+                    let unpacked = type_ids.len() - pack_mask.count_ones() as usize;
+
+                    if unpacked == 0 {
+                        return Iter::Tight(Tight {
+                            current: 0,
+                            end: len,
+                            storage: (self.$index1.into_abstract(), $(self.$index.into_abstract(),)+),
+                        });
+                    }
+
+                    let factor = len + len * unpacked * ACCESS_FACTOR;
+
+                    if factor < factored_len {
+                        smallest = len;
+                        mask = pack_mask;
+                        factored_len = factor;
+
+                        if pack_mask & (1 << $index1) != 0 {
+                            smallest_dense = self.$index1.dense();
+                        }
+                        $(
+                            else if pack_mask & (1 << $index) != 0 {
+                                smallest_dense = self.$index.dense();
+                            }
+                        )+
+                    }
+                }
+
+                let loose_infos = [
+                    loose_pack_info(self.$index1.type_id(), self.$index1.metadata()),
+                    $(loose_pack_info(self.$index.type_id(), self.$index.metadata()),)+
+                ];
+
+                if let Some((entities, len)) = loose_pack_entities(&loose_infos) {
+                    let factor = len * type_ids.len() * ACCESS_FACTOR;
+
+                    if factor < factored_len {
+                        smallest = len;
+                        smallest_dense = entities;
+                        mask = 0;
+                        factored_len = factor;
+                    }
+                }
+
                 drop(factored_len);
 
                 if smallest == core::usize::MAX {
@@ -5,6 +5,9 @@
 //! ## Features
 //!
 //! - **panic** *(default)* adds panicking functions
+//! - **gen32** &mdash; uses 30 bits for `EntityId`'s index and 32 for its generation instead of 46 and 16.  
+//!   Like any feature it's enabled for the whole dependency graph as soon as one crate asks for it.
+//!   Ids serialized in binary formats can't be loaded with the other split, human readable ones can as long as they fit.
 //! - **parallel** *(default)* &mdash; adds parallel iterators and dispatch
 //! - **serde1** &mdash; adds (de)serialization support with [serde](https://github.com/serde-rs/serde)
 //! - **non_send** &mdash; add methods and types required to work with `!Send` components
@@ -23,32 +26,40 @@ extern crate alloc;
 
 mod atomic_refcell;
 mod borrow;
+mod commands;
 mod delete;
+mod dynamic;
 pub mod error;
+mod events;
 mod get;
+pub mod hierarchy;
 mod not;
 mod pack {
+    pub(crate) mod loose;
+    pub(crate) mod tight;
     pub(crate) mod update;
 }
 mod add_component;
 mod add_entity;
 mod contains;
+#[cfg(feature = "serde1")]
+mod erased_serde;
 pub mod iter;
 mod r#mut;
+mod relation;
 mod remove;
 mod reserve;
+#[cfg(feature = "serde1")]
+mod serde_setup;
 mod sparse_set;
 mod storage;
 mod system;
 mod system_macro;
+mod template;
 mod type_id;
 mod unknown_storage;
 mod view;
 mod world;
-//#[cfg(feature = "serde1")]
-//mod erased_serde;
-//#[cfg(feature = "serde1")]
-//mod serde_setup;
 
 #[cfg(feature = "non_send")]
 pub use crate::borrow::NonSend;
@@ -61,26 +72,41 @@ pub use add_component::AddComponent;
 pub use add_entity::AddEntity;
 pub use atomic_refcell::{ExclusiveBorrow, Ref, RefMut, SharedBorrow};
 pub use borrow::{AllStoragesBorrow, Borrow, FakeBorrow, Mutability};
+pub use commands::Commands;
 pub use contains::Contains;
 pub use delete::Delete;
+pub use dynamic::{ComponentDescriptor, DynamicSparseSet, DynamicView, DynamicViewMut};
+pub use events::{EventReader, EventWriter, Events};
 pub use get::Get;
 pub use iter::{IntoFastIter, IntoIter, IntoWithId};
 pub use not::Not;
+pub use pack::loose::LoosePack;
+#[doc(hidden)]
+pub use pack::tight::PackSync;
+pub use pack::tight::TightPack;
 pub use pack::update::{Inserted, InsertedOrModified, Modified};
 pub use r#mut::Mut;
+pub use relation::{RelationView, RelationViewMut, Relations};
 pub use remove::Remove;
 pub use reserve::{BulkEntityIter, BulkReserve};
-pub use sparse_set::{sort, sort::IntoSortable, SparseSet};
+#[cfg(feature = "serde1")]
+pub use serde_setup::{
+    EntitiesMap, GlobalDeConfig, GlobalSerConfig, Identifier, SerConfig, VisitEntityIds,
+};
+pub use sparse_set::{sort, sort::IntoSortable, CallbackId, SparseSet};
 pub use storage::{
-    AllStorages, CustomDeleteAny, DeleteAny, Entities, EntityId, Retain, StorageId, Unique,
+    AllStorages, CustomDeleteAny, DeleteAny, Entities, EntitiesIter, EntityId, GenerationExhausted,
+    Retain, StorageId, Unique,
 };
 #[doc(hidden)]
 pub use system::{AllSystem, Nothing, System};
+pub use template::Template;
 pub use unknown_storage::UnknownStorage;
 pub use view::{
     AllStoragesViewMut, EntitiesView, EntitiesViewMut, UniqueView, UniqueViewMut, View, ViewMut,
 };
 pub use world::scheduler::info;
-pub use world::{Workload, WorkloadBuilder, World};
-//#[cfg(feature = "serde1")]
-//pub use serde_setup::{GlobalDeConfig, GlobalSerConfig, SerConfig};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use world::scheduler::profiling;
+pub use world::{FixedTimestep, FixedTimesteps, Label, Workload, WorkloadBuilder, World};
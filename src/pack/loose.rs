@@ -0,0 +1,154 @@
+use crate::error;
+use crate::sparse_set::{LoosePackInfo, Metadata, Pack, SparseSet};
+use crate::storage::EntityId;
+use crate::type_id::TypeId;
+use crate::view::ViewMut;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::any::type_name;
+
+/// Loose packs storages.
+pub trait LoosePack {
+    /// Loose packs the storages.  
+    /// The storages share an index of the entities owning a component in all of them, components aren't moved.  
+    /// Iterating these storages, alone or with other storages, can then go over this index
+    /// instead of looking for the entities of the smallest storage in all other storages.  
+    /// Loose packing storages that are already loose packed together rebuilds the index.
+    ///
+    /// Unlike tight packs, the index is kept up to date by all operations, even the ones modifying a single storage.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{EntitiesViewMut, IntoIter, LoosePack, ViewMut, World};
+    ///
+    /// let world = World::new();
+    ///
+    /// world.run(
+    ///     |mut entities: EntitiesViewMut, mut usizes: ViewMut<usize>, mut u32s: ViewMut<u32>| {
+    ///         (&mut usizes, &mut u32s).loose_pack();
+    ///
+    ///         entities.add_entity((&mut usizes, &mut u32s), (0usize, 1u32));
+    ///         entities.add_entity(&mut usizes, 2usize);
+    ///         let entity = entities.add_entity(&mut u32s, 3u32);
+    ///         entities.add_component(entity, &mut usizes, 4usize);
+    ///
+    ///         assert_eq!((&usizes, &u32s).iter().count(), 2);
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// ### Errors
+    ///
+    /// - One of the storages is already loose packed with other storages.
+    /// - One of the storages is tight packed.
+    fn try_loose_pack(self) -> Result<(), error::Pack>;
+    /// Loose packs the storages.  
+    /// Unwraps errors.
+    ///
+    /// ### Errors
+    ///
+    /// - One of the storages is already loose packed with other storages.
+    /// - One of the storages is tight packed.
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    fn loose_pack(self)
+    where
+        Self: Sized,
+    {
+        match self.try_loose_pack() {
+            Ok(()) => (),
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+}
+
+macro_rules! impl_loose_pack {
+    ($(($type: ident, $index: tt))+) => {
+        impl<$($type: 'static),+> LoosePack for ($(&mut ViewMut<'_, $type>,)+) {
+            fn try_loose_pack(self) -> Result<(), error::Pack> {
+                let mut types: Box<[TypeId]> = Box::new([$(TypeId::of::<SparseSet<$type>>()),+]);
+                types.sort_unstable();
+
+                $(
+                    match &self.$index.metadata.pack {
+                        Pack::Loose(pack) if pack.types != types => {
+                            return Err(error::Pack::AlreadyLoosePack(type_name::<$type>()));
+                        }
+                        Pack::Tight(_) => {
+                            return Err(error::Pack::AlreadyTightPack(type_name::<$type>()));
+                        }
+                        _ => {}
+                    }
+                )+
+
+                let pack = LoosePackInfo::new(types);
+
+                $(
+                    for &entity in &self.$index.dense {
+                        pack.insert(entity);
+                    }
+                )+
+
+                $(
+                    self.$index.metadata.pack = Pack::Loose(pack.clone());
+                )+
+
+                Ok(())
+            }
+        }
+    }
+}
+
+macro_rules! loose_pack {
+    ($(($type: ident, $index: tt))+; ($type1: ident, $index1: tt) $(($queue_type: ident, $queue_index: tt))*) => {
+        impl_loose_pack![$(($type, $index))*];
+        loose_pack![$(($type, $index))* ($type1, $index1); $(($queue_type, $queue_index))*];
+    };
+    ($(($type: ident, $index: tt))+;) => {
+        impl_loose_pack![$(($type, $index))*];
+    }
+}
+
+loose_pack![(A, 0) (B, 1); (C, 2) (D, 3) (E, 4) (F, 5) (G, 6) (H, 7) (I, 8) (J, 9)];
+
+/// Returns the storage's loose pack if the storage is iterated and is part of the pack.
+#[inline]
+pub(crate) fn loose_pack_info<T>(
+    type_id: TypeId,
+    metadata: &Metadata<T>,
+) -> (TypeId, Option<&LoosePackInfo>) {
+    match &metadata.pack {
+        Pack::Loose(pack) if pack.types.contains(&type_id) => (type_id, Some(pack)),
+        _ => (type_id, None),
+    }
+}
+
+/// Looks for a loose pack whose storages are all iterated.  
+/// Returns a pointer to the packed entities and their number.
+///
+/// The pointer stays valid as long as no storage of the pack is modified,
+/// which is guaranteed while they're borrowed by the iterator.
+pub(crate) fn loose_pack_entities(
+    infos: &[(TypeId, Option<&LoosePackInfo>)],
+) -> Option<(*const EntityId, usize)> {
+    'packs: for (_, pack) in infos {
+        let pack = match pack {
+            Some(pack) => pack,
+            None => continue,
+        };
+
+        for pack_type in pack.types.iter() {
+            match infos.iter().find(|(type_id, _)| type_id == pack_type) {
+                Some((_, Some(other))) if Arc::ptr_eq(&other.index, &pack.index) => {}
+                _ => continue 'packs,
+            }
+        }
+
+        let index = pack.index.lock();
+
+        return Some((index.entities.as_ptr(), index.entities.len()));
+    }
+
+    None
+}
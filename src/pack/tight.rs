@@ -0,0 +1,249 @@
+use crate::error;
+use crate::sparse_set::{Metadata, Pack, SparseSet, TightPackInfo};
+use crate::storage::EntityId;
+use crate::type_id::TypeId;
+use crate::view::ViewMut;
+use alloc::boxed::Box;
+use core::any::type_name;
+
+/// Tight packs storages.
+pub trait TightPack {
+    /// Tight packs the storages.  
+    /// Entities owning a component in all storages are moved at the front of each storage, in the same order.  
+    /// Iterating exactly these storages then walks the same contiguous slice in each storage,
+    /// `fast_iter` can also produce `into_chunk_exact` slices over all of them.  
+    /// Packing storages that are already packed together rebuilds the pack.
+    ///
+    /// To stay packed, components have to be added or removed from all storages of the pack at once,
+    /// with a tuple of views or `AllStorages`. Deleting entities keeps the pack as well.  
+    /// Modifying a single storage breaks the pack, iterators won't use it until `tight_pack` is called again.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{EntitiesViewMut, IntoFastIter, TightPack, ViewMut, World};
+    ///
+    /// let world = World::new();
+    ///
+    /// world.run(
+    ///     |mut entities: EntitiesViewMut, mut usizes: ViewMut<usize>, mut u32s: ViewMut<u32>| {
+    ///         (&mut usizes, &mut u32s).tight_pack();
+    ///
+    ///         entities.add_entity((&mut usizes, &mut u32s), (0usize, 1u32));
+    ///         entities.add_entity(&mut usizes, 2usize);
+    ///         entities.add_entity((&mut usizes, &mut u32s), (3usize, 4u32));
+    ///
+    ///         let mut iter = (&mut usizes, &u32s)
+    ///             .fast_iter()
+    ///             .into_chunk_exact(2)
+    ///             .ok()
+    ///             .unwrap();
+    ///
+    ///         assert_eq!(iter.next(), Some((&mut [0, 3][..], &[1, 4][..])));
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// ### Errors
+    ///
+    /// - One of the storages is already tight packed with other storages.
+    /// - One of the storages is loose packed.
+    fn try_tight_pack(self) -> Result<(), error::Pack>;
+    /// Tight packs the storages.  
+    /// Unwraps errors.
+    ///
+    /// ### Errors
+    ///
+    /// - One of the storages is already tight packed with other storages.
+    /// - One of the storages is loose packed.
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    fn tight_pack(self)
+    where
+        Self: Sized,
+    {
+        match self.try_tight_pack() {
+            Ok(()) => (),
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+}
+
+macro_rules! impl_tight_pack {
+    ($(($type: ident, $index: tt))+) => {
+        impl<$($type: 'static),+> TightPack for ($(&mut ViewMut<'_, $type>,)+) {
+            fn try_tight_pack(self) -> Result<(), error::Pack> {
+                let mut types: Box<[TypeId]> = Box::new([$(TypeId::of::<SparseSet<$type>>()),+]);
+                types.sort_unstable();
+
+                $(
+                    match &self.$index.metadata.pack {
+                        Pack::Tight(pack) if pack.types != types => {
+                            return Err(error::Pack::AlreadyTightPack(type_name::<$type>()));
+                        }
+                        Pack::Loose(_) => {
+                            return Err(error::Pack::AlreadyLoosePack(type_name::<$type>()));
+                        }
+                        _ => {}
+                    }
+                )+
+
+                $(
+                    self.$index.metadata.pack = Pack::Tight(TightPackInfo::new(types.clone()));
+                )+
+
+                let mut smallest: &[EntityId] = &self.0.dense;
+                $(
+                    if self.$index.dense.len() < smallest.len() {
+                        smallest = &self.$index.dense;
+                    }
+                )+
+                let entities = smallest.to_vec();
+
+                for entity in entities {
+                    if $(self.$index.contains(entity))&&+ {
+                        $(
+                            self.$index.pack(entity);
+                        )+
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+macro_rules! tight_pack {
+    ($(($type: ident, $index: tt))+; ($type1: ident, $index1: tt) $(($queue_type: ident, $queue_index: tt))*) => {
+        impl_tight_pack![$(($type, $index))*];
+        tight_pack![$(($type, $index))* ($type1, $index1); $(($queue_type, $queue_index))*];
+    };
+    ($(($type: ident, $index: tt))+;) => {
+        impl_tight_pack![$(($type, $index))*];
+    }
+}
+
+tight_pack![(A, 0) (B, 1); (C, 2) (D, 3) (E, 4) (F, 5) (G, 6) (H, 7) (I, 8) (J, 9)];
+
+/// Lets operations modifying multiple storages at once keep their packs valid.
+#[doc(hidden)]
+pub trait PackSync {
+    fn storage_type_id(&self) -> TypeId;
+    fn is_pack_broken(&self) -> bool;
+    fn sync_pack(&mut self, entity: EntityId, types: &[TypeId], was_broken: bool);
+    fn sync_pack_new(&mut self, entity: EntityId, types: &[TypeId], was_broken: bool);
+}
+
+impl PackSync for () {
+    #[inline]
+    fn storage_type_id(&self) -> TypeId {
+        TypeId::of::<()>()
+    }
+    #[inline]
+    fn is_pack_broken(&self) -> bool {
+        false
+    }
+    #[inline]
+    fn sync_pack(&mut self, _: EntityId, _: &[TypeId], _: bool) {}
+    #[inline]
+    fn sync_pack_new(&mut self, _: EntityId, _: &[TypeId], _: bool) {}
+}
+
+impl<T: 'static> PackSync for ViewMut<'_, T> {
+    #[inline]
+    fn storage_type_id(&self) -> TypeId {
+        TypeId::of::<SparseSet<T>>()
+    }
+    #[inline]
+    fn is_pack_broken(&self) -> bool {
+        self.metadata.pack.is_broken()
+    }
+    #[inline]
+    fn sync_pack(&mut self, entity: EntityId, types: &[TypeId], was_broken: bool) {
+        self.sync_tight_pack(entity, types, was_broken);
+    }
+    #[inline]
+    fn sync_pack_new(&mut self, entity: EntityId, types: &[TypeId], was_broken: bool) {
+        self.sync_tight_pack_new(entity, types, was_broken);
+    }
+}
+
+impl<T: 'static> PackSync for &mut ViewMut<'_, T> {
+    #[inline]
+    fn storage_type_id(&self) -> TypeId {
+        TypeId::of::<SparseSet<T>>()
+    }
+    #[inline]
+    fn is_pack_broken(&self) -> bool {
+        self.metadata.pack.is_broken()
+    }
+    #[inline]
+    fn sync_pack(&mut self, entity: EntityId, types: &[TypeId], was_broken: bool) {
+        self.sync_tight_pack(entity, types, was_broken);
+    }
+    #[inline]
+    fn sync_pack_new(&mut self, entity: EntityId, types: &[TypeId], was_broken: bool) {
+        self.sync_tight_pack_new(entity, types, was_broken);
+    }
+}
+
+/// Returns the storage's tight pack and its dense array if the storage is iterated as a whole and is part of the pack.
+#[inline]
+pub(crate) fn tight_pack_info<T>(
+    type_id: TypeId,
+    len: Option<(usize, bool)>,
+    metadata: &Metadata<T>,
+    dense: impl FnOnce() -> *const EntityId,
+) -> (TypeId, Option<(&TightPackInfo, *const EntityId)>) {
+    match (&metadata.pack, len) {
+        (Pack::Tight(pack), Some((_, true))) if pack.types.contains(&type_id) => {
+            (type_id, Some((pack, dense())))
+        }
+        _ => (type_id, None),
+    }
+}
+
+/// Looks for a valid tight pack whose storages are all iterated.  
+/// Returns the number of packed entities and a mask of the storages part of the pack.
+///
+/// On top of their length, the storages have to agree on the first and last packed entities,
+/// packs that got out of sync aren't used.
+pub(crate) fn tight_pack_len(
+    infos: &[(TypeId, Option<(&TightPackInfo, *const EntityId)>)],
+) -> Option<(usize, u16)> {
+    'packs: for (_, pack) in infos {
+        let (pack, dense) = match pack {
+            Some((pack, dense)) if !pack.is_broken => (pack, *dense),
+            _ => continue,
+        };
+
+        let mut mask = 0;
+        for pack_type in pack.types.iter() {
+            match infos.iter().position(|(type_id, _)| type_id == pack_type) {
+                Some(index) => match infos[index].1 {
+                    Some((other, other_dense))
+                        if !other.is_broken
+                            && other.len == pack.len
+                            && other.types == pack.types
+                            // SAFE both dense arrays hold at least `pack.len` entities
+                            && (pack.len == 0
+                                || unsafe {
+                                    *dense == *other_dense
+                                        && *dense.add(pack.len - 1)
+                                            == *other_dense.add(pack.len - 1)
+                                }) =>
+                    {
+                        mask |= 1 << index;
+                    }
+                    _ => continue 'packs,
+                },
+                None => continue 'packs,
+            }
+        }
+
+        return Some((pack.len, mask));
+    }
+
+    None
+}
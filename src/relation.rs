@@ -0,0 +1,315 @@
+use crate::atomic_refcell::{AtomicRefCell, Ref, RefMut, SharedBorrow};
+use crate::borrow::{AllStoragesBorrow, Borrow, Mutability};
+use crate::error;
+use crate::storage::{merged_id, AllStorages, EntityId, StorageId};
+use crate::unknown_storage::UnknownStorage;
+use crate::world::{TypeInfo, World};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::ops::{Deref, DerefMut};
+use hashbrown::HashMap;
+
+/// Storage of `R` relations between a source and a target entity, accessed with [`RelationView`] and [`RelationViewMut`].
+///
+/// Each `(source, target)` pair can only have one relation of each type, `R` is the relation's payload and can be `()`.
+/// Targets of a source and sources of a target are kept in insertion order.
+///
+/// Deleting or stripping an entity with [`AllStorages::delete_entity`] or [`AllStorages::strip`] removes all relations it's part of,
+/// as source or target.
+///
+/// ### Example
+/// ```
+/// use shipyard::{EntitiesViewMut, RelationViewMut, World};
+///
+/// struct Targets;
+///
+/// let mut world = World::new();
+///
+/// let (ship, asteroid0, asteroid1) = world.run(
+///     |mut entities: EntitiesViewMut, mut targets: RelationViewMut<Targets>| {
+///         let ship = entities.add_entity((), ());
+///         let asteroid0 = entities.add_entity((), ());
+///         let asteroid1 = entities.add_entity((), ());
+///
+///         targets.insert(ship, asteroid0, Targets);
+///         targets.insert(ship, asteroid1, Targets);
+///
+///         assert!(targets.targets(ship).eq(vec![asteroid0, asteroid1]));
+///         assert!(targets.sources(asteroid1).eq(vec![ship]));
+///
+///         (ship, asteroid0, asteroid1)
+///     },
+/// );
+///
+/// world.delete_entity(asteroid0);
+///
+/// world.run(|targets: RelationViewMut<Targets>| {
+///     assert!(targets.targets(ship).eq(vec![asteroid1]));
+/// });
+/// ```
+///
+/// [`RelationView`]: struct.RelationView.html
+/// [`RelationViewMut`]: struct.RelationViewMut.html
+/// [`AllStorages::delete_entity`]: struct.AllStorages.html#method.delete_entity
+/// [`AllStorages::strip`]: struct.AllStorages.html#method.strip
+pub struct Relations<R> {
+    payloads: HashMap<(EntityId, EntityId), R>,
+    targets: HashMap<EntityId, Vec<EntityId>>,
+    sources: HashMap<EntityId, Vec<EntityId>>,
+}
+
+impl<R> Default for Relations<R> {
+    fn default() -> Self {
+        Relations {
+            payloads: HashMap::new(),
+            targets: HashMap::new(),
+            sources: HashMap::new(),
+        }
+    }
+}
+
+impl<R> Relations<R> {
+    /// Adds a relation from `source` to `target`.
+    /// If the relation was already present, its payload is replaced and the old one returned.
+    pub fn insert(&mut self, source: EntityId, target: EntityId, payload: R) -> Option<R> {
+        let old = self.payloads.insert((source, target), payload);
+
+        if old.is_none() {
+            self.targets.entry(source).or_default().push(target);
+            self.sources.entry(target).or_default().push(source);
+        }
+
+        old
+    }
+    /// Removes the relation from `source` to `target` and returns its payload.
+    pub fn remove(&mut self, source: EntityId, target: EntityId) -> Option<R> {
+        let payload = self.payloads.remove(&(source, target))?;
+
+        remove_link(&mut self.targets, source, target);
+        remove_link(&mut self.sources, target, source);
+
+        Some(payload)
+    }
+    /// Removes all relations `entity` is part of, as source or target.
+    pub fn remove_entity(&mut self, entity: EntityId) {
+        if let Some(targets) = self.targets.remove(&entity) {
+            for target in targets {
+                self.payloads.remove(&(entity, target));
+                remove_link(&mut self.sources, target, entity);
+            }
+        }
+
+        if let Some(sources) = self.sources.remove(&entity) {
+            for source in sources {
+                self.payloads.remove(&(source, entity));
+                remove_link(&mut self.targets, source, entity);
+            }
+        }
+    }
+    /// Returns `true` if there is a relation from `source` to `target`.
+    pub fn contains(&self, source: EntityId, target: EntityId) -> bool {
+        self.payloads.contains_key(&(source, target))
+    }
+    /// Returns the payload of the relation from `source` to `target`.
+    pub fn get(&self, source: EntityId, target: EntityId) -> Option<&R> {
+        self.payloads.get(&(source, target))
+    }
+    /// Returns the payload of the relation from `source` to `target`.
+    pub fn get_mut(&mut self, source: EntityId, target: EntityId) -> Option<&mut R> {
+        self.payloads.get_mut(&(source, target))
+    }
+    /// Returns all entities `source` has a relation to, in insertion order.
+    pub fn targets(&self, source: EntityId) -> impl Iterator<Item = EntityId> + '_ {
+        self.targets.get(&source).into_iter().flatten().copied()
+    }
+    /// Returns all entities having a relation to `target`, in insertion order.
+    pub fn sources(&self, target: EntityId) -> impl Iterator<Item = EntityId> + '_ {
+        self.sources.get(&target).into_iter().flatten().copied()
+    }
+    /// Returns all relations as `((source, target), payload)`, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((EntityId, EntityId), &R)> {
+        self.payloads.iter().map(|(&pair, payload)| (pair, payload))
+    }
+    /// Returns the number of relations.
+    pub fn len(&self) -> usize {
+        self.payloads.len()
+    }
+    /// Returns `true` if there is no relation.
+    pub fn is_empty(&self) -> bool {
+        self.payloads.is_empty()
+    }
+    /// Removes all relations.
+    pub fn clear(&mut self) {
+        self.payloads.clear();
+        self.targets.clear();
+        self.sources.clear();
+    }
+}
+
+/// Removes `to` from `from`'s list, the list is removed once empty.
+fn remove_link(links: &mut HashMap<EntityId, Vec<EntityId>>, from: EntityId, to: EntityId) {
+    if let Some(list) = links.get_mut(&from) {
+        if let Some(index) = list.iter().position(|&entity| entity == to) {
+            list.remove(index);
+        }
+
+        if list.is_empty() {
+            links.remove(&from);
+        }
+    }
+}
+
+impl<R: 'static> UnknownStorage for Relations<R> {
+    #[inline]
+    fn delete(&mut self, entity: EntityId) {
+        self.remove_entity(entity);
+    }
+    #[inline]
+    fn clear(&mut self) {
+        Relations::clear(self);
+    }
+    #[inline]
+    fn new_empty(&self) -> Option<Box<AtomicRefCell<dyn UnknownStorage>>> {
+        // SAFE the caller copies this storage's thread restrictions
+        Some(Box::new(unsafe {
+            AtomicRefCell::new_unrestricted(Relations::<R>::default())
+        }))
+    }
+    fn merge(&mut self, other: &mut dyn UnknownStorage, entities_map: &[(EntityId, EntityId)]) {
+        if let Some(other) = other.any_mut().downcast_mut::<Relations<R>>() {
+            let mut payloads = core::mem::take(&mut other.payloads);
+            let targets = core::mem::take(&mut other.targets);
+            other.sources.clear();
+
+            // targets are kept in insertion order
+            for (source, targets) in targets {
+                for target in targets {
+                    let payload = payloads.remove(&(source, target)).unwrap();
+
+                    if let (Some(source), Some(target)) = (
+                        merged_id(entities_map, source),
+                        merged_id(entities_map, target),
+                    ) {
+                        self.insert(source, target, payload);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shared view over `R` relations.
+pub struct RelationView<'a, R> {
+    relations: Ref<'a, &'a Relations<R>>,
+    _all_borrow: Option<SharedBorrow<'a>>,
+}
+
+impl<R> Deref for RelationView<'_, R> {
+    type Target = Relations<R>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.relations
+    }
+}
+
+/// Exclusive view over `R` relations.
+pub struct RelationViewMut<'a, R> {
+    relations: RefMut<'a, &'a mut Relations<R>>,
+    _all_borrow: Option<SharedBorrow<'a>>,
+}
+
+impl<R> Deref for RelationViewMut<'_, R> {
+    type Target = Relations<R>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.relations
+    }
+}
+
+impl<R> DerefMut for RelationViewMut<'_, R> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.relations
+    }
+}
+
+impl<'a, R: 'static + Send + Sync> Borrow<'a> for RelationView<'a, R> {
+    #[inline]
+    fn try_borrow(world: &'a World) -> Result<Self, error::GetStorage> {
+        let (all_storages, all_borrow) = unsafe {
+            Ref::destructure(
+                world
+                    .all_storages
+                    .try_borrow()
+                    .map_err(error::GetStorage::AllStoragesBorrow)?,
+            )
+        };
+
+        Ok(RelationView {
+            relations: all_storages.custom_storage_or_insert(Relations::default)?,
+            _all_borrow: Some(all_borrow),
+        })
+    }
+
+    fn borrow_info(infos: &mut Vec<TypeInfo>) {
+        infos.push(TypeInfo {
+            name: type_name::<Relations<R>>(),
+            mutability: Mutability::Shared,
+            storage_id: StorageId::of::<Relations<R>>(),
+            is_send: true,
+            is_sync: true,
+        });
+    }
+}
+
+impl<'a, R: 'static + Send + Sync> AllStoragesBorrow<'a> for RelationView<'a, R> {
+    #[inline]
+    fn try_borrow(all_storages: &'a AllStorages) -> Result<Self, error::GetStorage> {
+        Ok(RelationView {
+            relations: all_storages.custom_storage_or_insert(Relations::default)?,
+            _all_borrow: None,
+        })
+    }
+}
+
+impl<'a, R: 'static + Send + Sync> Borrow<'a> for RelationViewMut<'a, R> {
+    #[inline]
+    fn try_borrow(world: &'a World) -> Result<Self, error::GetStorage> {
+        let (all_storages, all_borrow) = unsafe {
+            Ref::destructure(
+                world
+                    .all_storages
+                    .try_borrow()
+                    .map_err(error::GetStorage::AllStoragesBorrow)?,
+            )
+        };
+
+        Ok(RelationViewMut {
+            relations: all_storages.custom_storage_or_insert_mut(Relations::default)?,
+            _all_borrow: Some(all_borrow),
+        })
+    }
+
+    fn borrow_info(infos: &mut Vec<TypeInfo>) {
+        infos.push(TypeInfo {
+            name: type_name::<Relations<R>>(),
+            mutability: Mutability::Exclusive,
+            storage_id: StorageId::of::<Relations<R>>(),
+            is_send: true,
+            is_sync: true,
+        });
+    }
+}
+
+impl<'a, R: 'static + Send + Sync> AllStoragesBorrow<'a> for RelationViewMut<'a, R> {
+    #[inline]
+    fn try_borrow(all_storages: &'a AllStorages) -> Result<Self, error::GetStorage> {
+        Ok(RelationViewMut {
+            relations: all_storages.custom_storage_or_insert_mut(Relations::default)?,
+            _all_borrow: None,
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use crate::pack::tight::PackSync;
 use crate::sparse_set::SparseSet;
 use crate::storage::EntityId;
 use crate::view::ViewMut;
@@ -51,14 +52,23 @@ impl<T: 'static> Remove for &mut ViewMut<'_, T> {
 
 macro_rules! impl_remove_component {
     ($(($storage: ident, $index: tt))+) => {
-        impl<$($storage: Remove),+> Remove for ($($storage,)+) {
+        impl<$($storage: Remove + PackSync),+> Remove for ($($storage,)+) {
             type Out = ($($storage::Out,)+);
 
             #[inline]
             fn remove(&mut self, entity: EntityId) -> Self::Out {
-                ($(
+                let types = [$(self.$index.storage_type_id()),+];
+                let was_broken = [$(self.$index.is_pack_broken()),+];
+
+                let out = ($(
                     self.$index.remove(entity),
-                )+)
+                )+);
+
+                $(
+                    self.$index.sync_pack(entity, &types, was_broken[$index]);
+                )+
+
+                out
             }
         }
     }
@@ -1,242 +1,208 @@
+use crate::storage::EntityId;
 use alloc::borrow::Cow;
-
-pub(crate) static ANCHOR: () = ();
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
 
 /// Defines how the `World` should be serialized.
 #[derive(Clone, Copy)]
 pub struct GlobalSerConfig {
-    pub same_binary: bool,
+    /// Serializes `Entities` alongside the storages.
+    /// Without it components will be loaded with the same `EntityId` they were saved with
+    /// and can only be loaded in a `World` that never had any entity.  
+    /// The entities owning these components are then made alive, the indices in between are free to be reused.
     pub with_entities: bool,
-    pub with_shared: WithShared,
 }
 
 impl Default for GlobalSerConfig {
     fn default() -> Self {
         GlobalSerConfig {
-            same_binary: true,
             with_entities: true,
-            with_shared: WithShared::PerStorage,
         }
     }
 }
 
-/// Defines how the `World` should be deserialized.
-#[derive(Clone, Copy)]
+/// Defines how a serialized `World` should be loaded.
+#[derive(Clone, Copy, Default)]
 pub struct GlobalDeConfig {
-    pub existing_entities: ExistingEntities,
-    pub with_shared: WithShared,
+    /// Gives a new `EntityId` to all deserialized entities, even when the `World` never had any entity.  
+    /// Requires the `World` to be serialized with its entities.
+    pub remap_entities: bool,
 }
 
-impl Default for GlobalDeConfig {
-    fn default() -> Self {
-        GlobalDeConfig {
-            existing_entities: ExistingEntities::AsNew,
-            with_shared: WithShared::PerStorage,
-        }
-    }
+/// Maps the `EntityId`s of a serialized `World` to the ones they have once loaded.
+pub struct EntitiesMap {
+    pub(crate) map: HashMap<EntityId, EntityId>,
+    pub(crate) loading: Loading,
 }
 
-/// Describes what the deserialize process should do when it encounters an already existing entity.
-/// - AsNew will deserialize the entity's components with a new `EntityId`
-/// - Merge will only deserialize the components the entity didn't have
-/// - Replace will delete all components present and add the ones from the deserializetion
-/// - Skip will not deserialize this entity
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum ExistingEntities {
-    AsNew,
-    Merge,
-    Replace,
-    Skip,
+/// How the loaded components find their entity.
+pub(crate) enum Loading {
+    /// Entities got new ids, listed in `EntitiesMap::map`.
+    Remapped,
+    /// Serialized entities kept their ids, components can only belong to the ones alive.
+    Kept(Vec<EntityId>),
+    /// Entities weren't serialized, components' ids are collected to be made alive once all storages are loaded.
+    Collected(Mutex<Vec<EntityId>>),
 }
 
-/// Describes how shared components should be (de)seriliazed.
-/// - All will (de)serialize shared component for all storages
-/// - None will not (de)serialize shared components for any storage
-/// - PerStorage will (de)serailize shared components following each storage's config
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum WithShared {
-    All,
-    None,
-    PerStorage,
+impl EntitiesMap {
+    pub(crate) fn new(loading: Loading) -> Self {
+        EntitiesMap {
+            map: HashMap::new(),
+            loading,
+        }
+    }
+    /// Returns the `EntityId` `old` has once loaded.  
+    /// When entities weren't remapped, this is always `old`.
+    pub fn get(&self, old: EntityId) -> Option<EntityId> {
+        if self.is_remapped() {
+            self.map.get(&old).copied()
+        } else {
+            Some(old)
+        }
+    }
+    /// Returns the id of the entity owning a loaded component, `None` if it isn't one of the loaded entities.
+    pub(crate) fn load(&self, old: EntityId) -> Option<EntityId> {
+        match &self.loading {
+            Loading::Remapped => self.map.get(&old).copied(),
+            Loading::Kept(alive) => match alive.get(old.uindex()) {
+                Some(&entity) if entity == old => Some(old),
+                _ => None,
+            },
+            Loading::Collected(ids) => {
+                ids.lock().push(old);
+                Some(old)
+            }
+        }
+    }
+    /// Returns true if the entities got new `EntityId`s when loaded.
+    pub fn is_remapped(&self) -> bool {
+        matches!(self.loading, Loading::Remapped)
+    }
+    /// Returns an iterator over all remapped entities, old `EntityId` first.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, EntityId)> + '_ {
+        self.map.iter().map(|(&old, &new)| (old, new))
+    }
+    /// Returns the number of remapped entities.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+    /// Returns true if no entity was remapped.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
 }
 
-pub struct Identifier(pub(crate) Cow<'static, str>);
+/// Gives access to the `EntityId`s stored inside a component.  
+/// Storages setup with `setup_serde_remap` use it to keep these ids pointing to the right entity
+/// when loaded into a `World` that already has entities.
+///
+/// ### Example
+/// ```
+/// use shipyard::{EntityId, VisitEntityIds};
+///
+/// struct Parent(EntityId);
+///
+/// impl VisitEntityIds for Parent {
+///     fn visit_entity_ids(&mut self, visitor: &mut dyn FnMut(&mut EntityId)) {
+///         self.0.visit_entity_ids(visitor);
+///     }
+/// }
+/// ```
+pub trait VisitEntityIds {
+    /// Calls `visitor` on each `EntityId` of `self`.
+    fn visit_entity_ids(&mut self, visitor: &mut dyn FnMut(&mut EntityId));
+}
 
-impl Identifier {
-    pub fn new<I: Into<Cow<'static, str>>>(identifier: I) -> Self {
-        Identifier(identifier.into())
+impl VisitEntityIds for EntityId {
+    #[inline]
+    fn visit_entity_ids(&mut self, visitor: &mut dyn FnMut(&mut EntityId)) {
+        (visitor)(self)
     }
 }
 
-/// Defines how a storage should be serialized.
-pub struct SerConfig {
-    pub identifier: Option<Identifier>,
-    pub with_shared: bool,
+impl<T: VisitEntityIds> VisitEntityIds for Option<T> {
+    #[inline]
+    fn visit_entity_ids(&mut self, visitor: &mut dyn FnMut(&mut EntityId)) {
+        if let Some(inner) = self {
+            inner.visit_entity_ids(visitor);
+        }
+    }
 }
 
-impl Default for SerConfig {
-    fn default() -> Self {
-        SerConfig {
-            identifier: None,
-            with_shared: false,
+impl<T: VisitEntityIds> VisitEntityIds for Vec<T> {
+    #[inline]
+    fn visit_entity_ids(&mut self, visitor: &mut dyn FnMut(&mut EntityId)) {
+        for inner in self {
+            inner.visit_entity_ids(visitor);
         }
     }
 }
 
-pub(crate) struct SerInfos {
-    pub(crate) same_binary: bool,
-    pub(crate) with_entities: bool,
+/// Rewrites the `EntityId`s of `component` the way `entities_map` defines it.  
+/// Ids not present in `entities_map` are left untouched.
+pub(crate) fn remap<T: VisitEntityIds>(component: &mut T, entities_map: &EntitiesMap) {
+    if entities_map.is_remapped() {
+        component.visit_entity_ids(&mut |id| {
+            if let Some(&new) = entities_map.map.get(id) {
+                *id = new;
+            }
+        });
+    }
 }
 
-impl serde::Serialize for SerInfos {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        use serde::ser::SerializeStruct;
-
-        let mut state = serializer.serialize_struct("SerInfos", 2)?;
-
-        state.serialize_field("same_binary", &self.same_binary)?;
-        state.serialize_field("with_entities", &self.with_entities)?;
+/// Name used to find a storage when deserializing.
+/// It has to be unique for each storage of the `World`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identifier(pub(crate) Cow<'static, str>);
 
-        state.end()
+impl Identifier {
+    /// Creates an identifier, `identifier` is the name of the storage in the serialized data.
+    pub fn new<I: Into<Cow<'static, str>>>(identifier: I) -> Self {
+        Identifier(identifier.into())
     }
 }
 
-impl<'de> serde::Deserialize<'de> for SerInfos {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        const FIELDS: &'static [&'static str] = &["same_binary", "with_entities"];
-
-        enum Field {
-            SameBinary,
-            WithEntities,
-        }
-
-        struct FieldVisitor;
-        impl<'de> serde::de::Visitor<'de> for FieldVisitor {
-            type Value = Field;
-
-            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                formatter.write_str("field identifier")
-            }
-
-            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                match value {
-                    0u64 => Ok(Field::SameBinary),
-                    1u64 => Ok(Field::WithEntities),
-                    _ => Err(serde::de::Error::invalid_value(
-                        serde::de::Unexpected::Unsigned(value),
-                        &"field index 0 <= i < 2",
-                    )),
-                }
-            }
-
-            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                match value {
-                    "same_binary" => Ok(Field::SameBinary),
-                    "with_entities" => Ok(Field::WithEntities),
-                    _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
-                }
-            }
-
-            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                match value {
-                    b"same_binary" => Ok(Field::SameBinary),
-                    b"with_entities" => Ok(Field::WithEntities),
-                    _ => Err(serde::de::Error::invalid_value(
-                        serde::de::Unexpected::Bytes(value),
-                        &"field are `same_binary` and `with_entities`",
-                    )),
-                }
-            }
-        }
-
-        impl<'de> serde::Deserialize<'de> for Field {
-            #[inline]
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: serde::Deserializer<'de>,
-            {
-                deserializer.deserialize_identifier(FieldVisitor)
-            }
-        }
-
-        struct Visitor;
-        impl<'de> serde::de::Visitor<'de> for Visitor {
-            type Value = SerInfos;
-
-            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                formatter.write_str("struct SerInfos")
-            }
+/// Defines how a storage should be serialized.
+#[derive(Default)]
+pub struct SerConfig {
+    /// Name of the storage in the serialized data.
+    /// Defaults to the component's type name, which isn't guaranteed to be stable across compiler versions.
+    pub identifier: Option<Identifier>,
+}
 
-            #[inline]
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-            where
-                A: serde::de::SeqAccess<'de>,
-            {
-                let same_binary = seq.next_element::<bool>()?.ok_or_else(|| {
-                    serde::de::Error::invalid_length(0, &"struct SerInfos with 2 elements")
-                })?;
-                let with_entities = seq.next_element::<bool>()?.ok_or_else(|| {
-                    serde::de::Error::invalid_length(1, &"struct SerInfos with 2 elements")
-                })?;
-
-                Ok(SerInfos {
-                    same_binary,
-                    with_entities,
-                })
-            }
+pub(crate) type SerializationFn<S> =
+    fn(
+        &S,
+        &mut dyn crate::erased_serde::Serializer,
+    ) -> Result<crate::erased_serde::Ok, crate::erased_serde::Error>;
+
+pub(crate) type DeserializationFn<S> = fn(
+    &mut S,
+    &EntitiesMap,
+    &mut dyn crate::erased_serde::Deserializer<'_>,
+) -> Result<(), crate::erased_serde::Error>;
+
+/// (De)serialization functions of a storage, monomorphized when the storage opts in.
+pub(crate) struct SerdeInfos<S> {
+    pub(crate) serialization: SerializationFn<S>,
+    pub(crate) deserialization: DeserializationFn<S>,
+    pub(crate) identifier: Identifier,
+}
 
-            #[inline]
-            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-            where
-                A: serde::de::MapAccess<'de>,
-            {
-                let mut same_binary: Option<bool> = None;
-                let mut with_entities: Option<bool> = None;
-
-                while let Some(key) = map.next_key::<Field>()? {
-                    match key {
-                        Field::SameBinary => {
-                            if same_binary.is_some() {
-                                return Err(serde::de::Error::duplicate_field("same_binary"));
-                            }
-                            same_binary = Some(map.next_value::<bool>()?);
-                        }
-                        Field::WithEntities => {
-                            if with_entities.is_some() {
-                                return Err(serde::de::Error::duplicate_field("with_entities"));
-                            }
-                            with_entities = Some(map.next_value::<bool>()?);
-                        }
-                    }
-                }
-
-                let same_binary =
-                    same_binary.ok_or_else(|| serde::de::Error::missing_field("same_binary"))?;
-                let with_entities = with_entities
-                    .ok_or_else(|| serde::de::Error::missing_field("with_entities"))?;
-
-                Ok(SerInfos {
-                    same_binary,
-                    with_entities,
-                })
-            }
+impl<S> SerdeInfos<S> {
+    pub(crate) fn new<T: ?Sized>(
+        ser_config: SerConfig,
+        serialization: SerializationFn<S>,
+        deserialization: DeserializationFn<S>,
+    ) -> Self {
+        SerdeInfos {
+            serialization,
+            deserialization,
+            identifier: ser_config
+                .identifier
+                .unwrap_or_else(|| Identifier::new(core::any::type_name::<T>())),
         }
-
-        deserializer.deserialize_struct("SerInfos", FIELDS, Visitor)
     }
 }
@@ -1,13 +1,30 @@
 use crate::sparse_set::SparseSet;
 use crate::storage::{AllStorages, EntityId, StorageId};
+use crate::type_id::TypeId;
+use alloc::vec::Vec;
 
 pub trait AddComponent {
     fn add_component(self, all_storages: &mut AllStorages, entity: EntityId);
+    /// Same as `add_component` but `entity` doesn't have any component yet.
+    fn add_entity(self, all_storages: &mut AllStorages, entity: EntityId);
+    /// Same as `add_entity` but `entity` also gets components in other storages,
+    /// `types` lists all storages `entity` will have a component in.
+    fn add_entity_among(self, all_storages: &mut AllStorages, entity: EntityId, types: &[TypeId]);
+    /// Pushes the storages the components are added to.
+    fn push_types(types: &mut Vec<TypeId>)
+    where
+        Self: Sized;
 }
 
 impl AddComponent for () {
     #[inline]
     fn add_component(self, _: &mut AllStorages, _: EntityId) {}
+    #[inline]
+    fn add_entity(self, _: &mut AllStorages, _: EntityId) {}
+    #[inline]
+    fn add_entity_among(self, _: &mut AllStorages, _: EntityId, _: &[TypeId]) {}
+    #[inline]
+    fn push_types(_: &mut Vec<TypeId>) {}
 }
 
 impl<T: 'static + Send + Sync> AddComponent for (T,) {
@@ -16,6 +33,25 @@ impl<T: 'static + Send + Sync> AddComponent for (T,) {
         all_storages
             .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<T>>(), SparseSet::new)
             .insert(entity, self.0);
+        all_storages.run_global_callbacks(StorageId::of::<SparseSet<T>>());
+    }
+    #[inline]
+    fn add_entity(self, all_storages: &mut AllStorages, entity: EntityId) {
+        self.add_entity_among(all_storages, entity, &[TypeId::of::<SparseSet<T>>()]);
+    }
+    #[inline]
+    fn add_entity_among(self, all_storages: &mut AllStorages, entity: EntityId, types: &[TypeId]) {
+        let sparse_set = all_storages
+            .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<T>>(), SparseSet::new);
+
+        let was_broken = sparse_set.metadata.pack.is_broken();
+        sparse_set.insert(entity, self.0);
+        sparse_set.sync_tight_pack_new(entity, types, was_broken);
+        all_storages.run_global_callbacks(StorageId::of::<SparseSet<T>>());
+    }
+    #[inline]
+    fn push_types(types: &mut Vec<TypeId>) {
+        types.push(TypeId::of::<SparseSet<T>>());
     }
 }
 
@@ -23,11 +59,49 @@ macro_rules! impl_add_component {
     ($(($type: ident, $index: tt))+) => {
         impl<$($type: 'static + Send + Sync,)+> AddComponent for ($($type,)+) {
             fn add_component(self, all_storages: &mut AllStorages, entity: EntityId) {
+                let types = [$(TypeId::of::<SparseSet<$type>>()),+];
+                let was_broken = [$(
+                    all_storages
+                        .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::<$type>::new)
+                        .metadata.pack.is_broken()
+                ),+];
+
                 $(
                     all_storages
                         .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::new)
                         .insert(entity, self.$index);
                 )+
+                $(
+                    all_storages
+                        .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::<$type>::new)
+                        .sync_tight_pack(entity, &types, was_broken[$index]);
+                )+
+                $(
+                    all_storages.run_global_callbacks(StorageId::of::<SparseSet<$type>>());
+                )+
+            }
+            fn add_entity(self, all_storages: &mut AllStorages, entity: EntityId) {
+                let types = [$(TypeId::of::<SparseSet<$type>>()),+];
+
+                self.add_entity_among(all_storages, entity, &types);
+            }
+            fn add_entity_among(self, all_storages: &mut AllStorages, entity: EntityId, types: &[TypeId]) {
+                $({
+                    let sparse_set = all_storages
+                        .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::new);
+
+                    let was_broken = sparse_set.metadata.pack.is_broken();
+                    sparse_set.insert(entity, self.$index);
+                    sparse_set.sync_tight_pack_new(entity, types, was_broken);
+                })+
+                $(
+                    all_storages.run_global_callbacks(StorageId::of::<SparseSet<$type>>());
+                )+
+            }
+            fn push_types(types: &mut Vec<TypeId>) {
+                $(
+                    types.push(TypeId::of::<SparseSet<$type>>());
+                )+
             }
         }
     };
@@ -1,10 +1,23 @@
 use crate::reserve::BulkEntityIter;
 use crate::sparse_set::SparseSet;
 use crate::storage::{AllStorages, Entities, EntityId};
+use crate::type_id::TypeId;
+use alloc::vec::Vec;
 use core::iter::IntoIterator;
 
 pub trait BulkAddEntity {
     fn bulk_add_entity(self, all_storages: &mut AllStorages) -> BulkEntityIter<'_>;
+    /// Same as `bulk_add_entity` but the new entities also get components in other storages,
+    /// `types` lists all storages they will have a component in.
+    fn bulk_add_entity_among<'a>(
+        self,
+        all_storages: &'a mut AllStorages,
+        types: &[TypeId],
+    ) -> BulkEntityIter<'a>;
+    /// Pushes the storages the components are added to.
+    fn push_types(types: &mut Vec<TypeId>)
+    where
+        Self: Sized;
 }
 
 impl<I: IntoIterator> BulkAddEntity for I
@@ -14,6 +27,16 @@ where
     fn bulk_add_entity(self, all_storages: &mut AllStorages) -> BulkEntityIter<'_> {
         <I::Item as BulkInsert>::bulk_insert(all_storages, self)
     }
+    fn bulk_add_entity_among<'a>(
+        self,
+        all_storages: &'a mut AllStorages,
+        types: &[TypeId],
+    ) -> BulkEntityIter<'a> {
+        <I::Item as BulkInsert>::bulk_insert_among(all_storages, self, types)
+    }
+    fn push_types(types: &mut Vec<TypeId>) {
+        <I::Item as BulkInsert>::push_types(types);
+    }
 }
 
 pub trait BulkInsert {
@@ -23,6 +46,19 @@ pub trait BulkInsert {
     ) -> BulkEntityIter<'_>
     where
         Self: Sized;
+    /// Same as `bulk_insert` but the new entities also get components in other storages,
+    /// `types` lists all storages they will have a component in.
+    fn bulk_insert_among<'a, I: IntoIterator<Item = Self>>(
+        all_storages: &'a mut AllStorages,
+        iter: I,
+        types: &[TypeId],
+    ) -> BulkEntityIter<'a>
+    where
+        Self: Sized;
+    /// Pushes the storages the components are added to.
+    fn push_types(types: &mut Vec<TypeId>)
+    where
+        Self: Sized;
 }
 
 impl BulkInsert for () {
@@ -30,6 +66,16 @@ impl BulkInsert for () {
         all_storages: &mut AllStorages,
         iter: I,
     ) -> BulkEntityIter<'_>
+    where
+        Self: Sized,
+    {
+        Self::bulk_insert_among(all_storages, iter, &[])
+    }
+    fn bulk_insert_among<'a, I: IntoIterator<Item = Self>>(
+        all_storages: &'a mut AllStorages,
+        iter: I,
+        _: &[TypeId],
+    ) -> BulkEntityIter<'a>
     where
         Self: Sized,
     {
@@ -37,6 +83,7 @@ impl BulkInsert for () {
         let len = iter.size_hint().0;
 
         let entities = all_storages.exclusive_storage_mut::<Entities>().unwrap();
+        entities.flush();
         let entities_len = entities.data.len();
 
         entities.bulk_generate(len);
@@ -45,6 +92,7 @@ impl BulkInsert for () {
         }
         BulkEntityIter(entities.data[entities_len..].iter().copied())
     }
+    fn push_types(_: &mut Vec<TypeId>) {}
 }
 
 impl<T: 'static + Send + Sync> BulkInsert for (T,) {
@@ -52,6 +100,13 @@ impl<T: 'static + Send + Sync> BulkInsert for (T,) {
         all_storages: &mut AllStorages,
         iter: I,
     ) -> BulkEntityIter<'_> {
+        Self::bulk_insert_among(all_storages, iter, &[TypeId::of::<SparseSet<T>>()])
+    }
+    fn bulk_insert_among<'a, I: IntoIterator<Item = Self>>(
+        all_storages: &'a mut AllStorages,
+        iter: I,
+        types: &[TypeId],
+    ) -> BulkEntityIter<'a> {
         let iter = iter.into_iter();
         let len = iter.size_hint().0;
 
@@ -103,6 +158,14 @@ impl<T: 'static + Send + Sync> BulkInsert for (T,) {
             }
         }
 
+        let was_broken = sparse_set.metadata.pack.is_broken();
+        for i in old_len..sparse_set.dense.len() {
+            let entity = sparse_set.dense[i];
+            sparse_set.sync_tight_pack_new(entity, types, was_broken);
+        }
+
+        sparse_set.sync_loose_pack_new(&sparse_set.dense[old_len..]);
+
         drop((entities, sparse_set));
 
         BulkEntityIter(
@@ -114,13 +177,21 @@ impl<T: 'static + Send + Sync> BulkInsert for (T,) {
                 .copied(),
         )
     }
+    fn push_types(types: &mut Vec<TypeId>) {
+        types.push(TypeId::of::<SparseSet<T>>());
+    }
 }
 
 macro_rules! impl_bulk_insert {
     (($type1: ident, $sparse_set1: ident, $index1: tt) $(($type: ident, $sparse_set: ident, $index: tt))*) => {
         impl<$type1: 'static + Send + Sync, $($type: 'static + Send + Sync,)*> BulkInsert for ($type1, $($type,)*) {
-            #[allow(non_snake_case)]
             fn bulk_insert<Source: IntoIterator<Item = Self>>(all_storages: &mut AllStorages, iter: Source) -> BulkEntityIter<'_> {
+                let types = [TypeId::of::<SparseSet<$type1>>(), $(TypeId::of::<SparseSet<$type>>(),)*];
+
+                Self::bulk_insert_among(all_storages, iter, &types)
+            }
+            #[allow(non_snake_case)]
+            fn bulk_insert_among<'a, Source: IntoIterator<Item = Self>>(all_storages: &'a mut AllStorages, iter: Source, types: &[TypeId]) -> BulkEntityIter<'a> {
                 let iter = iter.into_iter();
                 let len = iter.size_hint().0;
 
@@ -133,6 +204,8 @@ macro_rules! impl_bulk_insert {
                     let mut $sparse_set = all_storages.custom_storage_or_insert_mut(SparseSet::<$type>::new).unwrap();
                 )*
 
+                let was_broken = [$sparse_set1.metadata.pack.is_broken(), $($sparse_set.metadata.pack.is_broken(),)*];
+
                 $sparse_set1.reserve(len);
                 $(
                     $sparse_set.reserve(len);
@@ -190,17 +263,37 @@ macro_rules! impl_bulk_insert {
                 }
 
                 $(
+                    let offset = $sparse_set.dense.len() - (data_len - old_len);
                     for (i, &entity) in dense[old_len..].iter().enumerate() {
                         unsafe {
-                            *$sparse_set.sparse.get_mut_unchecked(entity) = EntityId::new_from_parts((old_len + i) as u64, 0, 0);
+                            *$sparse_set.sparse.get_mut_unchecked(entity) = EntityId::new_from_parts((offset + i) as u64, 0, 0);
                         }
                     }
                 )*
 
+                let new_entities: Vec<EntityId> = dense[old_len..].to_vec();
+                for &entity in &new_entities {
+                    $sparse_set1.sync_tight_pack_new(entity, types, was_broken[$index1]);
+                    $(
+                        $sparse_set.sync_tight_pack_new(entity, types, was_broken[$index]);
+                    )*
+                }
+
+                $sparse_set1.sync_loose_pack_new(&new_entities);
+                $(
+                    $sparse_set.sync_loose_pack_new(&new_entities);
+                )*
+
                 drop((entities, $sparse_set1, $($sparse_set),*));
 
                 BulkEntityIter(all_storages.exclusive_storage_mut::<Entities>().unwrap().data[entities_len..].iter().copied())
             }
+            fn push_types(types: &mut Vec<TypeId>) {
+                types.push(TypeId::of::<SparseSet<$type1>>());
+                $(
+                    types.push(TypeId::of::<SparseSet<$type>>());
+                )*
+            }
         }
     };
 }
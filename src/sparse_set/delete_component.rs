@@ -1,5 +1,6 @@
 use crate::sparse_set::SparseSet;
 use crate::storage::{AllStorages, EntityId, StorageId};
+use crate::type_id::TypeId;
 
 pub trait DeleteComponent {
     fn delete_component(all_storages: &mut AllStorages, entity: EntityId);
@@ -11,6 +12,7 @@ impl<T: 'static + Send + Sync> DeleteComponent for (T,) {
         all_storages
             .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<T>>(), SparseSet::<T>::new)
             .delete(entity);
+        all_storages.run_global_callbacks(StorageId::of::<SparseSet<T>>());
     }
 }
 
@@ -18,11 +20,26 @@ macro_rules! impl_delete_component {
     ($(($type: ident, $index: tt))+) => {
         impl<$($type: 'static + Send + Sync,)+> DeleteComponent for ($($type,)+) {
             fn delete_component(all_storages: &mut AllStorages, entity: EntityId) {
+                let types = [$(TypeId::of::<SparseSet<$type>>()),+];
+                let was_broken = [$(
+                    all_storages
+                        .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::<$type>::new)
+                        .metadata.pack.is_broken()
+                ),+];
+
                 $(
                     all_storages
                         .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::<$type>::new)
                         .delete(entity);
                 )+
+                $(
+                    all_storages
+                        .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::<$type>::new)
+                        .sync_tight_pack(entity, &types, was_broken[$index]);
+                )+
+                $(
+                    all_storages.run_global_callbacks(StorageId::of::<SparseSet<$type>>());
+                )+
             }
         }
     };
@@ -1,12 +1,11 @@
-use super::{Metadata, SparseArray, SparseSet};
-use crate::serde_setup::{GlobalDeConfig, GlobalSerConfig};
+use super::SparseSet;
+use crate::serde_setup::EntitiesMap;
 use crate::storage::EntityId;
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
-#[allow(unused)]
 pub(crate) struct SparseSetSerializer<'a, T> {
     pub(crate) sparse_set: &'a SparseSet<T>,
-    pub(crate) ser_config: GlobalSerConfig,
 }
 
 impl<T> serde::Serialize for SparseSetSerializer<'_, T>
@@ -26,22 +25,26 @@ where
     }
 }
 
-pub(super) struct SparseSetDeserializer<T> {
-    pub(super) de_config: GlobalDeConfig,
-    pub(super) _phantom: PhantomData<T>,
+/// Inserts the deserialized components in an existing `SparseSet`.
+/// Ids are replaced by their value in `entities_map`.  
+/// `remap` updates the ids stored inside the components.
+pub(crate) struct SparseSetDeserializer<'a, T> {
+    pub(crate) sparse_set: &'a mut SparseSet<T>,
+    pub(crate) entities_map: &'a EntitiesMap,
+    pub(crate) remap: Option<fn(&mut T, &EntitiesMap)>,
 }
 
-impl<'de, T> serde::de::DeserializeSeed<'de> for SparseSetDeserializer<T>
+impl<'de, T> serde::de::DeserializeSeed<'de> for SparseSetDeserializer<'_, T>
 where
     T: serde::Deserialize<'de>,
 {
-    type Value = SparseSet<T>;
+    type Value = ();
 
     fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        const FIELDS: &'static [&'static str] = &["ids", "data"];
+        const FIELDS: &[&str] = &["ids", "data"];
 
         enum Field {
             Ids,
@@ -106,21 +109,51 @@ where
             }
         }
 
-        #[allow(unused)]
-        struct Visitor<'de, T>
-        where
-            T: serde::Deserialize<'de>,
-        {
-            marker: PhantomData<SparseSet<T>>,
+        struct Visitor<'a, 'de, T> {
+            sparse_set: &'a mut SparseSet<T>,
+            entities_map: &'a EntitiesMap,
+            remap: Option<fn(&mut T, &EntitiesMap)>,
             lifetime: PhantomData<&'de ()>,
-            de_config: GlobalDeConfig,
         }
 
-        impl<'de, T> serde::de::Visitor<'de> for Visitor<'de, T>
+        impl<'a, 'de, T> Visitor<'a, 'de, T> {
+            fn insert_all<E: serde::de::Error>(
+                self,
+                dense: Vec<EntityId>,
+                data: Vec<T>,
+            ) -> Result<(), E> {
+                if dense.len() != data.len() {
+                    return Err(serde::de::Error::invalid_length(
+                        data.len(),
+                        &"as many components as ids",
+                    ));
+                }
+
+                self.sparse_set.reserve(dense.len());
+
+                for (id, mut component) in dense.into_iter().zip(data) {
+                    let id = self.entities_map.load(id).ok_or_else(|| {
+                        serde::de::Error::custom(
+                            "Component belongs to an entity missing from the serialized entities.",
+                        )
+                    })?;
+
+                    if let Some(remap) = self.remap {
+                        (remap)(&mut component, self.entities_map);
+                    }
+
+                    self.sparse_set.insert(id, component);
+                }
+
+                Ok(())
+            }
+        }
+
+        impl<'a, 'de, T> serde::de::Visitor<'de> for Visitor<'a, 'de, T>
         where
             T: serde::Deserialize<'de>,
         {
-            type Value = SparseSet<T>;
+            type Value = ();
 
             fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                 formatter.write_str("struct SparseSet")
@@ -131,21 +164,14 @@ where
             where
                 A: serde::de::SeqAccess<'de>,
             {
-                let sparse = SparseArray::new();
-                let dense = seq.next_element()?.ok_or_else(|| {
+                let dense: Vec<EntityId> = seq.next_element()?.ok_or_else(|| {
                     serde::de::Error::invalid_length(0, &"struct SparseSet with 2 elements")
                 })?;
-                let data = seq.next_element()?.ok_or_else(|| {
+                let data: Vec<T> = seq.next_element()?.ok_or_else(|| {
                     serde::de::Error::invalid_length(1, &"struct SparseSet with 2 elements")
                 })?;
-                let metadata = Metadata::default();
-
-                Ok(SparseSet {
-                    sparse,
-                    dense,
-                    data,
-                    metadata,
-                })
+
+                self.insert_all(dense, data)
             }
 
             #[inline]
@@ -172,23 +198,10 @@ where
                         }
                     }
                 }
-                let dense = dense.ok_or_else(|| serde::de::Error::missing_field("dense"))?;
+                let dense = dense.ok_or_else(|| serde::de::Error::missing_field("ids"))?;
                 let data = data.ok_or_else(|| serde::de::Error::missing_field("data"))?;
 
-                let mut sparse: SparseArray<[usize; super::BUCKET_SIZE]> = SparseArray::new();
-                for (i, &id) in dense.iter().enumerate() {
-                    sparse.allocate_at(id);
-                    unsafe {
-                        sparse.set_sparse_index_unchecked(id, i);
-                    }
-                }
-
-                Ok(SparseSet {
-                    sparse,
-                    dense,
-                    data,
-                    metadata: Default::default(),
-                })
+                self.insert_all(dense, data)
             }
         }
 
@@ -196,9 +209,10 @@ where
             "SparseSet",
             FIELDS,
             Visitor {
-                marker: PhantomData::<SparseSet<T>>,
+                sparse_set: self.sparse_set,
+                entities_map: self.entities_map,
+                remap: self.remap,
                 lifetime: PhantomData,
-                de_config: self.de_config,
             },
         )
     }
@@ -1,46 +1,200 @@
 use super::SparseSet;
+#[cfg(feature = "serde1")]
+use crate::serde_setup::SerdeInfos;
 use crate::sparse_set::SparseArray;
 use crate::storage::{AllStorages, EntityId};
+#[cfg(feature = "serde1")]
+use crate::template::TemplateDeserialization;
+use crate::type_id::TypeId;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
-// #[cfg(feature = "serde1")]
-// use crate::storage::Storage;
-// #[cfg(feature = "serde1")]
-// use super::{SparseSet, SparseSetDeserializer};
-// #[cfg(feature = "serde1")]
-// use crate::atomic_refcell::AtomicRefCell;
-// #[cfg(feature = "serde1")]
-// use crate::serde_setup::{GlobalDeConfig, GlobalSerConfig, Identifier, SerConfig};
-// #[cfg(feature = "serde1")]
-// use hashbrown::HashMap;
+use hashbrown::hash_map::Entry;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+pub(crate) type LocalCallback<T> = Box<dyn FnMut(EntityId, &mut SparseSet<T>) + Send + Sync>;
+pub(crate) type GlobalCallback<T> =
+    Box<dyn FnMut(EntityId, &mut SparseSet<T>, &AllStorages) + Send + Sync>;
+pub(crate) type UnlinkFn<T> = fn(&mut SparseSet<T>, EntityId, &AllStorages);
+pub(crate) type DependentsFn<T> = fn(&SparseSet<T>, EntityId, &AllStorages) -> Vec<EntityId>;
+/// The callback is `None` while it runs.
+pub(crate) type Callback<F> = (CallbackId, Option<F>);
+
+/// Identifies a callback registered on a storage, used to unregister it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackId(pub(super) u64);
 
 pub struct Metadata<T> {
+    pub(crate) pack: Pack,
     pub(crate) update: Option<UpdatePack<T>>,
-    pub(super) local_on_insert: Vec<fn(EntityId, &mut SparseSet<T>)>,
-    pub(super) local_on_remove: Vec<fn(EntityId, &mut SparseSet<T>)>,
-    pub(crate) global_on_insert: Vec<fn(EntityId, &mut SparseSet<T>, &AllStorages)>,
+    pub(super) next_callback_id: u64,
+    pub(super) local_on_insert: Vec<Callback<LocalCallback<T>>>,
+    pub(super) local_on_remove: Vec<Callback<LocalCallback<T>>>,
+    pub(super) local_on_modify: Vec<Callback<LocalCallback<T>>>,
+    pub(crate) global_on_insert: Vec<Callback<GlobalCallback<T>>>,
     pub(crate) on_insert_ids_sparse: SparseArray<[EntityId; super::BUCKET_SIZE]>,
     pub(crate) on_insert_ids_dense: Vec<EntityId>,
-    pub(crate) global_on_remove: Vec<fn(EntityId, &mut SparseSet<T>, &AllStorages)>,
+    pub(crate) global_on_remove: Vec<Callback<GlobalCallback<T>>>,
     pub(crate) on_remove_ids_sparse: SparseArray<[EntityId; super::BUCKET_SIZE]>,
     pub(crate) on_remove_ids_dense: Vec<EntityId>,
-    // #[cfg(feature = "serde1")]
-    // pub(crate) serde: Option<SerdeInfos<T>>,
+    pub(super) cloning: Cloning<T>,
+    pub(crate) links: Option<Links<T>>,
+    #[cfg(feature = "serde1")]
+    pub(crate) serde: Option<SerdeInfos<SparseSet<T>>>,
+    #[cfg(feature = "serde1")]
+    pub(super) template_deserialization: Option<TemplateDeserialization>,
+}
+
+/// What `AllStorages::clone_entity` does with the components of a storage.
+pub(super) enum Cloning<T> {
+    /// Cloning an entity with a component in this storage is an error.
+    Unsupported,
+    Clone(fn(&T) -> T),
+    /// The clone doesn't get a component in this storage.
+    Skip,
+}
+
+/// Keeps the `EntityId`s stored in the components of a storage valid, used by the hierarchy.  
+/// Components linking entities aren't cloned nor moved to another `World`.
+pub(crate) struct Links<T> {
+    /// Updates the ids stored in a merged component, returns `false` if one of them wasn't merged.
+    pub(crate) remap: fn(&mut T, &[(EntityId, EntityId)]) -> bool,
+    /// Removes an entity from the links before it's stripped, other storages can be borrowed from `AllStorages`.
+    pub(crate) unlink: Option<UnlinkFn<T>>,
+    /// Returns the entities deleted alongside an entity.
+    pub(crate) dependents: Option<DependentsFn<T>>,
 }
 
+impl<T> Clone for Links<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Links<T> {}
+
 impl<T> Default for Metadata<T> {
     fn default() -> Self {
         Metadata {
+            pack: Pack::None,
             update: None,
+            next_callback_id: 0,
             local_on_insert: Vec::new(),
             local_on_remove: Vec::new(),
+            local_on_modify: Vec::new(),
             global_on_insert: Vec::new(),
             on_insert_ids_sparse: SparseArray::new(),
             on_insert_ids_dense: Vec::new(),
             global_on_remove: Vec::new(),
             on_remove_ids_sparse: SparseArray::new(),
             on_remove_ids_dense: Vec::new(),
-            // #[cfg(feature = "serde1")]
-            // serde: None,
+            cloning: Cloning::Unsupported,
+            links: None,
+            #[cfg(feature = "serde1")]
+            serde: None,
+            #[cfg(feature = "serde1")]
+            template_deserialization: None,
+        }
+    }
+}
+
+pub(crate) enum Pack {
+    Tight(TightPackInfo),
+    Loose(LoosePackInfo),
+    None,
+}
+
+impl Pack {
+    /// Returns `true` if the storage is tight packed but was modified without the rest of its pack.
+    #[inline]
+    pub(crate) fn is_broken(&self) -> bool {
+        match self {
+            Pack::Tight(pack) => pack.is_broken,
+            Pack::Loose(_) | Pack::None => false,
+        }
+    }
+}
+
+/// Entities owning a component in all storages of the pack are kept at the front of `dense` and `data`,
+/// in the same order in all storages.
+pub(crate) struct TightPackInfo {
+    /// Sorted `TypeId`s of all storages in the pack.
+    pub(crate) types: Box<[TypeId]>,
+    /// Number of packed entities.
+    pub(crate) len: usize,
+    /// Set when the storage was modified without the other storages of the pack.  
+    /// Iterators won't use the pack until it's rebuilt.
+    pub(crate) is_broken: bool,
+}
+
+impl TightPackInfo {
+    pub(crate) fn new(types: Box<[TypeId]>) -> Self {
+        TightPackInfo {
+            types,
+            len: 0,
+            is_broken: false,
+        }
+    }
+}
+
+/// Keeps a list of the entities owning a component in all storages of the pack, without moving any component.  
+/// The index is shared by all storages of the pack and updated each time one of them gains or loses a component.
+#[derive(Clone)]
+pub(crate) struct LoosePackInfo {
+    /// Sorted `TypeId`s of all storages in the pack.
+    pub(crate) types: Box<[TypeId]>,
+    pub(crate) index: Arc<Mutex<LoosePackIndex>>,
+}
+
+#[derive(Default)]
+pub(crate) struct LoosePackIndex {
+    /// Number of storages of the pack in which each entity has a component.
+    counts: HashMap<EntityId, usize>,
+    /// Entities with a component in all storages of the pack, sorted.
+    pub(crate) entities: Vec<EntityId>,
+}
+
+impl LoosePackInfo {
+    pub(crate) fn new(types: Box<[TypeId]>) -> Self {
+        LoosePackInfo {
+            types,
+            index: Arc::new(Mutex::new(LoosePackIndex::default())),
+        }
+    }
+    /// Registers a new component for `entity` in one of the storages of the pack.
+    pub(crate) fn insert(&self, mut entity: EntityId) {
+        let mut index = self.index.lock();
+        let LoosePackIndex { counts, entities } = &mut *index;
+
+        let count = counts.entry(entity).or_insert(0);
+        *count += 1;
+
+        if *count == self.types.len() {
+            entity.clear_meta();
+
+            if let Err(position) = entities.binary_search(&entity) {
+                entities.insert(position, entity);
+            }
+        }
+    }
+    /// Registers the removal of `entity`'s component from one of the storages of the pack.
+    pub(crate) fn remove(&self, entity: EntityId) {
+        let mut index = self.index.lock();
+        let LoosePackIndex { counts, entities } = &mut *index;
+
+        if let Entry::Occupied(mut count) = counts.entry(entity) {
+            if *count.get() == self.types.len() {
+                if let Ok(position) = entities.binary_search(&entity) {
+                    entities.remove(position);
+                }
+            }
+
+            if *count.get() == 1 {
+                count.remove();
+            } else {
+                *count.get_mut() -= 1;
+            }
         }
     }
 }
@@ -58,74 +212,3 @@ impl<T> Default for UpdatePack<T> {
         }
     }
 }
-
-// #[cfg(feature = "serde1")]
-// #[allow(unused)]
-// pub(crate) struct SerdeInfos<T> {
-//     pub(crate) serialization: fn(
-//         &SparseSet<T>,
-//         GlobalSerConfig,
-//         &mut dyn crate::erased_serde::Serializer,
-//     )
-//         -> Result<crate::erased_serde::Ok, crate::erased_serde::Error>,
-//     pub(crate) deserialization: fn(
-//         GlobalDeConfig,
-//         &HashMap<EntityId, EntityId>,
-//         &mut dyn crate::erased_serde::Deserializer<'_>,
-//     ) -> Result<Storage, crate::erased_serde::Error>,
-//     pub(crate) with_shared: bool,
-//     pub(crate) identifier: Option<Identifier>,
-// }
-
-// #[cfg(feature = "serde1")]
-// impl<T: serde::Serialize + for<'de> serde::Deserialize<'de> + 'static> SerdeInfos<T> {
-//     pub(super) fn new(ser_config: SerConfig) -> Self {
-//         SerdeInfos {
-//             serialization:
-//                 |sparse_set: &SparseSet<T>,
-//                  ser_config: GlobalSerConfig,
-//                  serializer: &mut dyn crate::erased_serde::Serializer| {
-//                     crate::erased_serde::Serialize::erased_serialize(
-//                         &super::SparseSetSerializer {
-//                             sparse_set: &sparse_set,
-//                             ser_config,
-//                         },
-//                         serializer,
-//                     )
-//                 },
-//             deserialization:
-//                 |de_config: GlobalDeConfig,
-//                  entities_map: &HashMap<EntityId, EntityId>,
-//                  deserializer: &mut dyn crate::erased_serde::Deserializer<'_>| {
-//                     #[cfg(feature = "std")]
-//                     {
-//                         Ok(Storage(Box::new(AtomicRefCell::new(
-//                             serde::de::DeserializeSeed::deserialize(
-//                                 SparseSetDeserializer::<T> {
-//                                     de_config,
-//                                     _phantom: core::marker::PhantomData,
-//                                 },
-//                                 deserializer,
-//                             )?,
-//                             None,
-//                             true,
-//                         ))))
-//                     }
-//                     #[cfg(not(feature = "std"))]
-//                     {
-//                         Ok(Storage(Box::new(AtomicRefCell::new(
-//                             serde::de::DeserializeSeed::deserialize(
-//                                 SparseSetDeserializer::<T> {
-//                                     de_config,
-//                                     _phantom: core::marker::PhantomData,
-//                                 },
-//                                 deserializer,
-//                             )?,
-//                         ))))
-//                     }
-//                 },
-//             with_shared: true,
-//             identifier: ser_config.identifier,
-//         }
-//     }
-// }
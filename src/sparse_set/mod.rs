@@ -1,41 +1,38 @@
 mod add_component;
 mod bulk_add_entity;
 mod delete_component;
+#[cfg(feature = "serde1")]
+mod deser;
 mod metadata;
 mod remove;
 pub mod sort;
 mod sparse_array;
 mod window;
-// #[cfg(feature = "serde1")]
-// mod deser;
 
 pub(crate) use add_component::AddComponent;
 pub(crate) use bulk_add_entity::BulkAddEntity;
 pub(crate) use delete_component::DeleteComponent;
-pub(crate) use metadata::Metadata;
+pub use metadata::CallbackId;
+pub(crate) use metadata::{Links, LoosePackInfo, Metadata, Pack, TightPackInfo};
 pub(crate) use remove::Remove;
 pub(crate) use sparse_array::SparseArray;
 pub(crate) use window::FullRawWindowMut;
-// #[cfg(feature = "serde1")]
-// pub(crate) use deser::SparseSetSerializer;
-// #[cfg(feature = "serde1")]
-// use hashbrown::HashMap;
-// #[cfg(feature = "serde1")]
-// pub(crate) use metadata::SerdeInfos;
 
+use crate::atomic_refcell::AtomicRefCell;
 use crate::error;
+#[cfg(feature = "serde1")]
+use crate::serde_setup::{EntitiesMap, Identifier, SerConfig, SerdeInfos, VisitEntityIds};
 use crate::storage::AllStorages;
 use crate::storage::EntityId;
+#[cfg(feature = "serde1")]
+use crate::template::Template;
+use crate::type_id::TypeId;
 use crate::unknown_storage::UnknownStorage;
-#[cfg(all(not(feature = "std"), feature = "serde1"))]
-use alloc::string::ToString;
+use alloc::boxed::Box;
 use alloc::vec::Vec;
-// #[cfg(feature = "serde1")]
-// use alloc::borrow::Cow;
-// #[cfg(feature = "serde1")]
-// use crate::serde_setup::{GlobalDeConfig, GlobalSerConfig, SerConfig};
-// #[cfg(feature = "serde1")]
-// use deser::SparseSetDeserializer;
+#[cfg(feature = "serde1")]
+use deser::{SparseSetDeserializer, SparseSetSerializer};
+use metadata::{Callback, Cloning};
 
 pub(crate) const BUCKET_SIZE: usize = 256 / core::mem::size_of::<usize>();
 
@@ -135,11 +132,17 @@ impl<T> SparseSet<T> {
         let index = self.index_of(entity)?;
 
         if self.metadata.update.is_some() {
-            unsafe {
-                let dense_entity = self.dense.get_unchecked_mut(index);
+            let dense_entity = unsafe { self.dense.get_unchecked_mut(index) };
+
+            if !dense_entity.is_inserted() && !dense_entity.is_modified() {
+                dense_entity.set_modified();
 
-                if !dense_entity.is_inserted() {
-                    dense_entity.set_modified();
+                if !self.metadata.local_on_modify.is_empty() {
+                    self.run_on_modify(entity);
+
+                    // callbacks can move or remove the component
+                    let index = self.index_of(entity)?;
+                    return Some(unsafe { self.data.get_unchecked_mut(index) });
                 }
             }
         }
@@ -164,8 +167,7 @@ impl<T> SparseSet<T> {
         let old_component;
 
         if sparse_entity.is_dead() {
-            *sparse_entity =
-                EntityId::new_from_parts(self.dense.len() as u64, entity.gen() as u16, 0);
+            *sparse_entity = EntityId::new_from_parts(self.dense.len() as u64, entity.gen(), 0);
 
             if self.metadata.update.is_some() {
                 entity.set_inserted();
@@ -176,6 +178,13 @@ impl<T> SparseSet<T> {
             self.dense.push(entity);
             self.data.push(value);
 
+            match &mut self.metadata.pack {
+                // the other storages of the pack might now have a component for this entity too
+                Pack::Tight(pack) => pack.is_broken = true,
+                Pack::Loose(pack) => pack.insert(entity),
+                Pack::None => {}
+            }
+
             self.run_on_insert(entity);
 
             old_component = None;
@@ -184,16 +193,22 @@ impl<T> SparseSet<T> {
                 core::mem::replace(self.data.get_unchecked_mut(sparse_entity.uindex()), value)
             };
 
+            let dense_entity = unsafe { self.dense.get_unchecked_mut(sparse_entity.uindex()) };
+
             if entity.gen() == sparse_entity.gen() {
                 old_component = Some(old_data);
             } else {
                 old_component = None;
+
+                // the component now belongs to a newer entity
+                if let Pack::Loose(pack) = &self.metadata.pack {
+                    pack.remove(*dense_entity);
+                    pack.insert(entity);
+                }
             }
 
             sparse_entity.copy_gen(entity);
 
-            let dense_entity = unsafe { self.dense.get_unchecked_mut(sparse_entity.uindex()) };
-
             if self.metadata.update.is_some() && !dense_entity.is_inserted() {
                 dense_entity.set_modified();
             }
@@ -231,20 +246,41 @@ impl<T> SparseSet<T> {
         if entity.gen() >= sparse_entity.gen() {
             self.run_on_remove(entity);
 
-            let sparse_entity = self.sparse.get(entity)?;
+            let mut sparse_entity = self.sparse.get(entity)?;
+
+            if let Pack::Tight(pack) = &mut self.metadata.pack {
+                if sparse_entity.uindex() < pack.len {
+                    pack.len -= 1;
+                    pack.is_broken = true;
+
+                    let last_packed = pack.len;
+                    // SAFE both indices are smaller than dense's length
+                    unsafe {
+                        self.swap_index_unchecked(sparse_entity.uindex(), last_packed);
+                    }
+                    sparse_entity.set_index(last_packed as u64);
+                }
+            }
 
             unsafe {
                 *self.sparse.get_mut_unchecked(entity) = EntityId::dead();
             }
 
-            self.dense.swap_remove(sparse_entity.uindex());
+            let removed = self.dense.swap_remove(sparse_entity.uindex());
+
+            if let Pack::Loose(pack) = &self.metadata.pack {
+                pack.remove(removed);
+            }
+
             let component = self.data.swap_remove(sparse_entity.uindex());
 
-            unsafe {
-                let last = *self.dense.get_unchecked(sparse_entity.uindex());
-                self.sparse
-                    .get_mut_unchecked(last)
-                    .copy_index(sparse_entity);
+            if sparse_entity.uindex() < self.dense.len() {
+                unsafe {
+                    let last = *self.dense.get_unchecked(sparse_entity.uindex());
+                    self.sparse
+                        .get_mut_unchecked(last)
+                        .copy_index(sparse_entity);
+                }
             }
 
             if entity.gen() == sparse_entity.gen() {
@@ -566,6 +602,21 @@ impl<T> SparseSet<T> {
     pub fn update_pack(&mut self) {
         self.metadata.update.get_or_insert_with(Default::default);
     }
+    /// Makes [`AllStorages::clone_entity`] clone the components of this storage.
+    ///
+    /// [`AllStorages::clone_entity`]: struct.AllStorages.html#method.clone_entity
+    pub fn enable_cloning(&mut self)
+    where
+        T: Clone,
+    {
+        self.metadata.cloning = Cloning::Clone(T::clone);
+    }
+    /// Makes [`AllStorages::clone_entity`] ignore this storage, clones won't have a component in it.
+    ///
+    /// [`AllStorages::clone_entity`]: struct.AllStorages.html#method.clone_entity
+    pub fn skip_cloning(&mut self) {
+        self.metadata.cloning = Cloning::Skip;
+    }
 }
 
 impl<T> SparseSet<T> {
@@ -577,12 +628,28 @@ impl<T> SparseSet<T> {
     }
     /// Deletes all components in this storage.
     pub fn clear(&mut self) {
+        if let Pack::Tight(pack) = &mut self.metadata.pack {
+            if pack.len != 0 {
+                pack.len = 0;
+                pack.is_broken = true;
+            }
+        }
+
+        self.private_clear();
+    }
+    fn private_clear(&mut self) {
         for &id in &self.dense {
             unsafe {
                 *self.sparse.get_mut_unchecked(id) = EntityId::dead();
             }
         }
 
+        if let Pack::Loose(pack) = &self.metadata.pack {
+            for &id in &self.dense {
+                pack.remove(id);
+            }
+        }
+
         if let Some(update) = &mut self.metadata.update {
             update
                 .deleted
@@ -711,81 +778,175 @@ impl<T> SparseSet<T> {
 }
 
 impl<T> SparseSet<T> {
-    #[inline]
-    fn run_on_insert(&mut self, entity: EntityId) {
-        self.schedule_insert_global(entity);
-        let mut i = 0;
-
-        if i < self.metadata.local_on_insert.len() {
-            let f = unsafe { self.metadata.local_on_insert.get_unchecked(i) };
-            (f)(entity, self);
-            i += 1;
+    /// Swaps the components at `a` and `b` and updates `sparse` accordingly.
+    ///
+    /// # Safety
+    ///
+    /// `a` and `b` have to be smaller than `dense`'s length.
+    unsafe fn swap_index_unchecked(&mut self, a: usize, b: usize) {
+        self.dense.swap(a, b);
+        self.data.swap(a, b);
+
+        let a_id = *self.dense.get_unchecked(a);
+        let b_id = *self.dense.get_unchecked(b);
+        self.sparse.get_mut_unchecked(a_id).set_index(a as u64);
+        self.sparse.get_mut_unchecked(b_id).set_index(b as u64);
+    }
+    /// Moves `entity` at the end of the packed entities.  
+    /// Does nothing if the storage isn't tight packed, if `entity` is already packed or doesn't have a component in this storage.
+    pub(crate) fn pack(&mut self, entity: EntityId) {
+        if let Some(index) = self.index_of(entity) {
+            if let Pack::Tight(pack) = &mut self.metadata.pack {
+                if index >= pack.len {
+                    let len = pack.len;
+                    pack.len += 1;
+
+                    // SAFE len <= index < dense.len()
+                    unsafe {
+                        self.swap_index_unchecked(index, len);
+                    }
+                }
+            }
         }
-
-        let _ = i;
     }
-    pub(crate) fn run_on_insert_global(&mut self, all_storages: &AllStorages) {
+    /// Has to be called after components were added to a new `entity` in all `types` storages.  
+    /// `entity` can't have components in other storages so the pack is still valid,
+    /// `entity` is packed if `types` contains all storages of the pack.
+    pub(crate) fn sync_tight_pack_new(
+        &mut self,
+        entity: EntityId,
+        types: &[TypeId],
+        was_broken: bool,
+    ) {
+        if let Pack::Tight(pack) = &mut self.metadata.pack {
+            pack.is_broken = was_broken;
+
+            if pack.types.iter().all(|type_id| types.contains(type_id)) {
+                self.pack(entity);
+            }
+        }
+    }
+    /// Has to be called after `entity`'s components were added or removed in all `types` storages.  
+    /// If `types` contains all storages of the pack, the pack is still valid and `entity` is packed if needed.
+    pub(crate) fn sync_tight_pack(&mut self, entity: EntityId, types: &[TypeId], was_broken: bool) {
+        if let Pack::Tight(pack) = &mut self.metadata.pack {
+            if pack.types.iter().all(|type_id| types.contains(type_id)) {
+                pack.is_broken = was_broken;
+                self.pack(entity);
+            }
+        }
+    }
+    /// Has to be called after `entities` were pushed to `dense` without going through `insert`.
+    pub(crate) fn sync_loose_pack_new(&self, entities: &[EntityId]) {
+        if let Pack::Loose(pack) = &self.metadata.pack {
+            for &entity in entities {
+                pack.insert(entity);
+            }
+        }
+    }
+}
+
+impl<T> SparseSet<T> {
+    /// Runs all callbacks of a list, `callbacks` selects the list.  
+    /// A callback is taken out of the list while it runs, callbacks can register and unregister callbacks.
+    fn run_callbacks<F>(
+        &mut self,
+        callbacks: fn(&mut Metadata<T>) -> &mut Vec<Callback<F>>,
+        mut run: impl FnMut(&mut F, &mut Self),
+    ) {
         let mut i = 0;
-        let mut current = 0;
-        let end = self.metadata.on_insert_ids_dense.len();
 
-        while i < self.metadata.global_on_insert.len() {
-            let f = unsafe { *self.metadata.global_on_insert.get_unchecked(i) };
+        while let Some((id, f)) = callbacks(&mut self.metadata).get_mut(i) {
+            let id = *id;
 
-            while current < end {
-                let entity = unsafe { *self.metadata.on_insert_ids_dense.get_unchecked(current) };
+            // the callback is already running higher in the stack
+            let mut f = match f.take() {
+                Some(f) => f,
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
 
-                f(entity, self, all_storages);
-                current += 1;
-            }
+            run(&mut f, self);
 
-            current = 0;
-            i += 1;
+            // the list might have been modified by the callback
+            if let Some(position) = callbacks(&mut self.metadata)
+                .iter()
+                .position(|(other, _)| *other == id)
+            {
+                callbacks(&mut self.metadata)[position].1 = Some(f);
+                i = position + 1;
+            }
         }
+    }
+    #[inline]
+    fn run_on_modify(&mut self, entity: EntityId) {
+        self.run_callbacks(
+            |metadata| &mut metadata.local_on_modify,
+            |f, sparse_set| f(entity, sparse_set),
+        );
+    }
+    #[inline]
+    fn run_on_insert(&mut self, entity: EntityId) {
+        self.schedule_insert_global(entity);
 
-        for entity in self.metadata.on_insert_ids_dense.drain(0..end) {
-            unsafe {
-                *self.metadata.on_insert_ids_sparse.get_mut_unchecked(entity) = EntityId::dead();
+        self.run_callbacks(
+            |metadata| &mut metadata.local_on_insert,
+            |f, sparse_set| f(entity, sparse_set),
+        );
+    }
+    pub(crate) fn run_on_insert_global(&mut self, all_storages: &AllStorages) {
+        // callbacks can insert components, these events are dispatched in the next iteration
+        while !self.metadata.on_insert_ids_dense.is_empty() {
+            let entities = core::mem::take(&mut self.metadata.on_insert_ids_dense);
+
+            for &entity in &entities {
+                unsafe {
+                    *self.metadata.on_insert_ids_sparse.get_mut_unchecked(entity) =
+                        EntityId::dead();
+                }
             }
+
+            self.run_callbacks(
+                |metadata| &mut metadata.global_on_insert,
+                |f, sparse_set| {
+                    for &entity in &entities {
+                        f(entity, sparse_set, all_storages);
+                    }
+                },
+            );
         }
     }
     #[inline]
     fn run_on_remove(&mut self, entity: EntityId) {
         self.schedule_remove_global(entity);
 
-        let mut i = 0;
-
-        if i < self.metadata.local_on_remove.len() {
-            let f = unsafe { self.metadata.local_on_remove.get_unchecked(i) };
-            (f)(entity, self);
-            i += 1;
-        }
-
-        let _ = i;
+        self.run_callbacks(
+            |metadata| &mut metadata.local_on_remove,
+            |f, sparse_set| f(entity, sparse_set),
+        );
     }
     pub(crate) fn run_on_remove_global(&mut self, all_storages: &AllStorages) {
-        let mut i = 0;
-        let mut current = 0;
-        let end = self.metadata.on_remove_ids_dense.len();
-
-        while i < self.metadata.global_on_remove.len() {
-            let f = unsafe { *self.metadata.global_on_remove.get_unchecked(i) };
+        // callbacks can remove components, these events are dispatched in the next iteration
+        while !self.metadata.on_remove_ids_dense.is_empty() {
+            let entities = core::mem::take(&mut self.metadata.on_remove_ids_dense);
 
-            while current < end {
-                let entity = unsafe { *self.metadata.on_remove_ids_dense.get_unchecked(current) };
-
-                f(entity, self, all_storages);
-                current += 1;
+            for &entity in &entities {
+                unsafe {
+                    *self.metadata.on_remove_ids_sparse.get_mut_unchecked(entity) =
+                        EntityId::dead();
+                }
             }
 
-            current = 0;
-            i += 1;
-        }
-
-        for entity in self.metadata.on_remove_ids_dense.drain(0..end) {
-            unsafe {
-                *self.metadata.on_remove_ids_sparse.get_mut_unchecked(entity) = EntityId::dead();
-            }
+            self.run_callbacks(
+                |metadata| &mut metadata.global_on_remove,
+                |f, sparse_set| {
+                    for &entity in &entities {
+                        f(entity, sparse_set, all_storages);
+                    }
+                },
+            );
         }
     }
     /// Registers a callback triggered when a component is inserted and run immediately.
@@ -793,36 +954,222 @@ impl<T> SparseSet<T> {
     /// Callbacks will run one after the other based on the order they were added.  
     /// They will run after the component is already in the `SparseSet`.  
     /// Inserting components to an entity that already owns a component in this storage will not trigger `on_insert` event.
+    /// Returns an id that can be used to unregister the callback with [`remove_callback`].
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{ViewMut, World};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let count = Arc::new(AtomicUsize::new(0));
+    /// let callback_count = count.clone();
+    ///
+    /// let callback = world
+    ///     .borrow::<ViewMut<u32>>()
+    ///     .on_insert(move |_, _| {
+    ///         callback_count.fetch_add(1, Ordering::Relaxed);
+    ///     });
+    ///
+    /// world.add_entity((0u32,));
+    /// world.borrow::<ViewMut<u32>>().remove_callback(callback);
+    /// world.add_entity((1u32,));
+    ///
+    /// assert_eq!(count.load(Ordering::Relaxed), 1);
+    /// ```
+    ///
+    /// [`remove_callback`]: struct.SparseSet.html#method.remove_callback
+    #[inline]
+    pub fn on_insert<F>(&mut self, f: F) -> CallbackId
+    where
+        F: FnMut(EntityId, &mut Self) + Send + Sync + 'static,
+    {
+        let id = self.next_callback_id();
+        self.metadata.local_on_insert.push((id, Some(Box::new(f))));
+        id
+    }
+    /// Registers a callback triggered when a component is inserted, with access to all other storages.
+    ///
+    /// Events are queued and the callbacks run:
+    /// - when the `ViewMut` used to insert the component is dropped
+    /// - at the end of `AllStorages::add_entity` and `AllStorages::add_component`, and `World`'s equivalents
+    ///
+    /// Callbacks will run one after the other based on the order they were added, each one for all queued entities.  
+    /// `on_insert_global` callbacks run before `on_remove_global`, the component might have been removed since it was inserted.  
+    /// Each entity is reported once per dispatch, even if it was inserted multiple times.  
+    /// The storage itself is passed as argument, trying to borrow it from `AllStorages` will fail.  
+    /// It is not possible to remove unique storages inside a global callback triggered by `AllStorages`.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{AllStorages, EntityId, SparseSet, UniqueViewMut, ViewMut, World};
+    ///
+    /// struct Collider;
+    ///
+    /// let mut world = World::new();
+    /// world.add_unique(Vec::<EntityId>::new());
+    ///
+    /// world
+    ///     .borrow::<ViewMut<Collider>>()
+    ///     .on_insert_global(|entity, _: &mut SparseSet<Collider>, all_storages: &AllStorages| {
+    ///         all_storages
+    ///             .borrow::<UniqueViewMut<Vec<EntityId>>>()
+    ///             .push(entity);
+    ///     });
+    ///
+    /// let entity = world.add_entity((Collider,));
+    ///
+    /// assert_eq!(*world.borrow::<UniqueViewMut<Vec<EntityId>>>(), vec![entity]);
+    /// ```
     #[inline]
-    pub fn on_insert(&mut self, f: fn(EntityId, &mut Self)) {
-        self.metadata.local_on_insert.push(f);
-    }
-    // /// Registers a callback triggered when a component is inserted and run when `ViewMut` is dropped.
-    // ///
-    // /// Callbacks will run one after the other based on the order they were added.
-    // /// `on_insert_global` callbacks run before `on_remove_global`.
-    // /// It is not possible to remove unique storages inside a global callback.
-    // #[inline]
-    // fn on_insert_global(&mut self, f: fn(EntityId, &mut Self, &AllStorages)) {
-    //     self.metadata.global_on_insert.push(f);
-    // }
+    pub fn on_insert_global<F>(&mut self, f: F) -> CallbackId
+    where
+        F: FnMut(EntityId, &mut Self, &AllStorages) + Send + Sync + 'static,
+    {
+        let id = self.next_callback_id();
+        self.metadata.global_on_insert.push((id, Some(Box::new(f))));
+        id
+    }
     /// Registers a callback triggered when a component is removed or deleted and run immediately.
     ///
     /// Callbacks will run one after the other based on the order they were added.  
     /// They will run before the component is removed from the `SparseSet`.  
+    /// Returns an id that can be used to unregister the callback with [`remove_callback`].
+    ///
+    /// [`remove_callback`]: struct.SparseSet.html#method.remove_callback
+    #[inline]
+    pub fn on_remove<F>(&mut self, f: F) -> CallbackId
+    where
+        F: FnMut(EntityId, &mut Self) + Send + Sync + 'static,
+    {
+        let id = self.next_callback_id();
+        self.metadata.local_on_remove.push((id, Some(Box::new(f))));
+        id
+    }
+    /// Registers a callback triggered when a component is modified and run immediately.  
+    /// Only storages tracking modification trigger this event, when a component is accessed with `IndexMut` or `fast_get`.
+    ///
+    /// Callbacks will run one after the other based on the order they were added.  
+    /// They will run before the component is modified, the first time it's accessed mutably since modification tracking was cleared.  
+    /// Inserted components are not considered modified.  
+    /// Returns an id that can be used to unregister the callback with [`remove_callback`].
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{EntityId, ViewMut, World};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut world = World::new();
+    /// world.borrow::<ViewMut<u32>>().update_pack();
+    ///
+    /// let modified = Arc::new(Mutex::new(Vec::<EntityId>::new()));
+    /// let callback_modified = modified.clone();
+    ///
+    /// world.borrow::<ViewMut<u32>>().on_modify(move |entity, _| {
+    ///     callback_modified.lock().unwrap().push(entity);
+    /// });
+    ///
+    /// let entity = world.add_entity((0u32,));
+    /// world.borrow::<ViewMut<u32>>().clear_inserted();
+    ///
+    /// world.run(|mut u32s: ViewMut<u32>| {
+    ///     u32s[entity] += 1;
+    ///     u32s[entity] += 1;
+    /// });
+    ///
+    /// assert_eq!(*modified.lock().unwrap(), vec![entity]);
+    /// ```
+    ///
+    /// [`remove_callback`]: struct.SparseSet.html#method.remove_callback
+    #[inline]
+    pub fn on_modify<F>(&mut self, f: F) -> CallbackId
+    where
+        F: FnMut(EntityId, &mut Self) + Send + Sync + 'static,
+    {
+        let id = self.next_callback_id();
+        self.metadata.local_on_modify.push((id, Some(Box::new(f))));
+        id
+    }
+    /// Registers a callback triggered when a component is removed or deleted, with access to all other storages.
+    ///
+    /// Events are queued and the callbacks run:
+    /// - when the `ViewMut` used to remove the component is dropped
+    /// - at the end of `AllStorages::remove` and `AllStorages::delete_component`, and `World`'s equivalents
+    /// - during `AllStorages::delete_entity`, `strip`, `retain` and `clear`, right after this storage is processed
+    ///
+    /// Callbacks will run one after the other based on the order they were added, each one for all queued entities.  
+    /// `on_remove_global` callbacks run after `on_insert_global`.  
+    /// The component is no longer in the storage when the callback runs.  
+    /// The storage itself is passed as argument, trying to borrow it from `AllStorages` will fail.  
+    /// It is not possible to remove unique storages inside a global callback triggered by `AllStorages`.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{AllStorages, EntityId, SparseSet, UniqueViewMut, ViewMut, World};
+    ///
+    /// struct Collider;
+    ///
+    /// #[derive(Default)]
+    /// struct Physics {
+    ///     bodies: Vec<EntityId>,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.add_unique(Physics::default());
+    ///
+    /// world
+    ///     .borrow::<ViewMut<Collider>>()
+    ///     .on_remove_global(|entity, _: &mut SparseSet<Collider>, all_storages: &AllStorages| {
+    ///         all_storages
+    ///             .borrow::<UniqueViewMut<Physics>>()
+    ///             .bodies
+    ///             .retain(|&body| body != entity);
+    ///     });
+    ///
+    /// let entity = world.add_entity((Collider,));
+    /// world.borrow::<UniqueViewMut<Physics>>().bodies.push(entity);
+    ///
+    /// world.delete_entity(entity);
+    ///
+    /// assert!(world.borrow::<UniqueViewMut<Physics>>().bodies.is_empty());
+    /// ```
     #[inline]
-    pub fn on_remove(&mut self, f: fn(EntityId, &mut Self)) {
-        self.metadata.local_on_remove.push(f);
-    }
-    // /// Registers a callback triggered when a component is removed or deleted and run when `ViewMut` is dropped or when deleting components using `AllStorages`.
-    // ///
-    // /// Callbacks will run one after the other based on the order they were added.
-    // /// `on_remove_global` callbacks run after `on_insert_global`.
-    // /// It is not possible to remove unique storages inside a global callback.
-    // #[inline]
-    // fn on_remove_global(&mut self, f: fn(EntityId, &mut Self, &AllStorages)) {
-    //     self.metadata.global_on_remove.push(f);
-    // }
+    pub fn on_remove_global<F>(&mut self, f: F) -> CallbackId
+    where
+        F: FnMut(EntityId, &mut Self, &AllStorages) + Send + Sync + 'static,
+    {
+        let id = self.next_callback_id();
+        self.metadata.global_on_remove.push((id, Some(Box::new(f))));
+        id
+    }
+    /// Unregisters the callback identified by `id`.  
+    /// Returns `false` if no callback with this id is registered on this storage.
+    pub fn remove_callback(&mut self, id: CallbackId) -> bool {
+        fn remove<F>(callbacks: &mut Vec<Callback<F>>, id: CallbackId) -> bool {
+            match callbacks.iter().position(|(other, _)| *other == id) {
+                Some(position) => {
+                    callbacks.remove(position);
+                    true
+                }
+                None => false,
+            }
+        }
+
+        let metadata = &mut self.metadata;
+
+        remove(&mut metadata.local_on_insert, id)
+            || remove(&mut metadata.local_on_remove, id)
+            || remove(&mut metadata.local_on_modify, id)
+            || remove(&mut metadata.global_on_insert, id)
+            || remove(&mut metadata.global_on_remove, id)
+    }
+    fn next_callback_id(&mut self) -> CallbackId {
+        let id = CallbackId(self.metadata.next_callback_id);
+        self.metadata.next_callback_id += 1;
+        id
+    }
     /// Schedules a `on_insert_global` event for `entity`.
     #[inline]
     fn schedule_insert_global(&mut self, entity: EntityId) {
@@ -834,7 +1181,7 @@ impl<T> SparseSet<T> {
             if id.is_dead() || entity.gen() > id.gen() {
                 *id = EntityId::new_from_parts(
                     self.metadata.on_insert_ids_dense.len() as u64,
-                    entity.gen() as u16,
+                    entity.gen(),
                     0,
                 );
                 self.metadata.on_insert_ids_dense.push(entity);
@@ -852,7 +1199,7 @@ impl<T> SparseSet<T> {
             if id.is_dead() || entity.gen() > id.gen() {
                 *id = EntityId::new_from_parts(
                     self.metadata.on_remove_ids_dense.len() as u64,
-                    entity.gen() as u16,
+                    entity.gen(),
                     0,
                 );
                 self.metadata.on_remove_ids_dense.push(entity);
@@ -861,15 +1208,126 @@ impl<T> SparseSet<T> {
     }
 }
 
-// #[cfg(feature = "serde1")]
-// impl<T: serde::Serialize + for<'de> serde::Deserialize<'de> + 'static> SparseSet<T> {
-//     /// Setup serialization for this storage.
-//     /// Needs to be called for a storage to be serialized.
-//     #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
-//     pub fn setup_serde(&mut self, ser_config: SerConfig) {
-//         self.metadata.serde = Some(SerdeInfos::new(ser_config));
-//     }
-// }
+#[cfg(feature = "serde1")]
+impl<T: serde::Serialize + for<'de> serde::Deserialize<'de> + 'static> SparseSet<T> {
+    /// Setup serialization for this storage.  
+    /// Needs to be called for a storage to be serialized or deserialized with the `World`.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{Identifier, SerConfig, ViewMut, World};
+    ///
+    /// let world = World::new();
+    ///
+    /// world.borrow::<ViewMut<u32>>().setup_serde(SerConfig {
+    ///     identifier: Some(Identifier::new("u32")),
+    /// });
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn setup_serde(&mut self, ser_config: SerConfig) {
+        self.metadata.serde = Some(SerdeInfos::new::<T>(
+            ser_config,
+            |sparse_set, serializer| {
+                crate::erased_serde::Serialize::erased_serialize(
+                    &SparseSetSerializer { sparse_set },
+                    serializer,
+                )
+            },
+            |sparse_set, entities_map, deserializer| {
+                serde::de::DeserializeSeed::deserialize(
+                    SparseSetDeserializer {
+                        sparse_set,
+                        entities_map,
+                        remap: None,
+                    },
+                    deserializer,
+                )
+            },
+        ));
+    }
+}
+
+#[cfg(feature = "serde1")]
+impl<T: serde::Serialize + for<'de> serde::Deserialize<'de> + VisitEntityIds + 'static>
+    SparseSet<T>
+{
+    /// Setup serialization for this storage.  
+    /// When loaded into a `World` with entities, the `EntityId`s inside the components are also updated.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use serde_derive::{Deserialize, Serialize};
+    /// use shipyard::{EntityId, SerConfig, ViewMut, VisitEntityIds, World};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Parent(EntityId);
+    ///
+    /// impl VisitEntityIds for Parent {
+    ///     fn visit_entity_ids(&mut self, visitor: &mut dyn FnMut(&mut EntityId)) {
+    ///         self.0.visit_entity_ids(visitor);
+    ///     }
+    /// }
+    ///
+    /// let world = World::new();
+    ///
+    /// world
+    ///     .borrow::<ViewMut<Parent>>()
+    ///     .setup_serde_remap(SerConfig::default());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn setup_serde_remap(&mut self, ser_config: SerConfig) {
+        self.metadata.serde = Some(SerdeInfos::new::<T>(
+            ser_config,
+            |sparse_set, serializer| {
+                crate::erased_serde::Serialize::erased_serialize(
+                    &SparseSetSerializer { sparse_set },
+                    serializer,
+                )
+            },
+            |sparse_set, entities_map, deserializer| {
+                serde::de::DeserializeSeed::deserialize(
+                    SparseSetDeserializer {
+                        sparse_set,
+                        entities_map,
+                        remap: Some(crate::serde_setup::remap::<T>),
+                    },
+                    deserializer,
+                )
+            },
+        ));
+    }
+}
+
+#[cfg(feature = "serde1")]
+impl<T: Clone + Send + Sync + for<'de> serde::Deserialize<'de> + 'static> SparseSet<T> {
+    /// Allows components of this storage to be part of templates loaded with [`World::load_templates`].  
+    /// The storage is found with the identifier given to [`setup_serde`] or [`setup_serde_remap`].
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{SerConfig, ViewMut, World};
+    ///
+    /// let world = World::new();
+    ///
+    /// let mut u32s = world.borrow::<ViewMut<u32>>();
+    /// u32s.setup_serde(SerConfig::default());
+    /// u32s.setup_serde_template();
+    /// ```
+    ///
+    /// [`World::load_templates`]: struct.World.html#method.load_templates
+    /// [`setup_serde`]: struct.SparseSet.html#method.setup_serde
+    /// [`setup_serde_remap`]: struct.SparseSet.html#method.setup_serde_remap
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn setup_serde_template(&mut self) {
+        self.metadata.template_deserialization = Some(|template, deserializer| {
+            template.insert(crate::erased_serde::deserialize::<T>(deserializer)?);
+            Ok(())
+        });
+    }
+}
 
 impl<T> core::ops::Index<EntityId> for SparseSet<T> {
     type Output = T;
@@ -892,8 +1350,183 @@ impl<T: 'static> UnknownStorage for SparseSet<T> {
         SparseSet::delete(self, entity);
     }
     #[inline]
+    fn strip(&mut self, entity: EntityId) {
+        // all storages lose this entity, packs stay valid
+        let was_broken = self.metadata.pack.is_broken();
+
+        SparseSet::delete(self, entity);
+
+        if let Pack::Tight(pack) = &mut self.metadata.pack {
+            pack.is_broken = was_broken;
+        }
+    }
+    #[inline]
     fn clear(&mut self) {
-        <Self>::clear(self);
+        // all storages are cleared, packs stay valid
+        if let Pack::Tight(pack) = &mut self.metadata.pack {
+            pack.len = 0;
+        }
+
+        self.private_clear();
+    }
+    #[inline]
+    fn can_clone(&self, entity: EntityId) -> Result<(), &'static str> {
+        match self.metadata.cloning {
+            Cloning::Unsupported if self.metadata.links.is_none() && self.contains(entity) => {
+                Err(core::any::type_name::<T>())
+            }
+            _ => Ok(()),
+        }
+    }
+    #[inline]
+    fn clone_component(&mut self, entity: EntityId, new_entity: EntityId) {
+        // the clone isn't linked to the entities `entity` is linked to
+        if self.metadata.links.is_some() {
+            return;
+        }
+
+        if let Cloning::Clone(clone) = self.metadata.cloning {
+            if let Some(component) = self.private_get(entity) {
+                let component = clone(component);
+                self.insert(new_entity, component);
+            }
+        }
+    }
+    #[inline]
+    fn new_empty(&self) -> Option<Box<AtomicRefCell<dyn UnknownStorage>>> {
+        // SAFE the caller copies this storage's thread restrictions
+        Some(Box::new(unsafe {
+            AtomicRefCell::new_unrestricted(SparseSet::<T>::new())
+        }))
+    }
+    #[inline]
+    fn move_component(
+        &mut self,
+        entity: EntityId,
+        other: &mut dyn UnknownStorage,
+        new_entity: EntityId,
+    ) {
+        // the entity leaves its links, they're removed when it's stripped
+        if self.metadata.links.is_some() {
+            return;
+        }
+
+        if let Some(other) = other.any_mut().downcast_mut::<SparseSet<T>>() {
+            // the entity leaves all storages, packs stay valid
+            let was_broken = self.metadata.pack.is_broken();
+
+            if let Some(component) = self.actual_remove(entity) {
+                if let Some(update) = &mut self.metadata.update {
+                    update.removed.push(entity);
+                }
+
+                other.insert(new_entity, component);
+            }
+
+            if let Pack::Tight(pack) = &mut self.metadata.pack {
+                pack.is_broken = was_broken;
+            }
+        }
+    }
+    fn merge(&mut self, other: &mut dyn UnknownStorage, entities_map: &[(EntityId, EntityId)]) {
+        if let Some(other) = other.any_mut().downcast_mut::<SparseSet<T>>() {
+            let dense = core::mem::take(&mut other.dense);
+            let data = core::mem::take(&mut other.data);
+
+            other.sparse = SparseArray::new();
+            match &mut other.metadata.pack {
+                Pack::Tight(pack) => pack.len = 0,
+                Pack::Loose(pack) => {
+                    for &entity in &dense {
+                        pack.remove(entity);
+                    }
+                }
+                Pack::None => {}
+            }
+
+            let links = other.metadata.links;
+            if self.metadata.links.is_none() {
+                self.metadata.links = links;
+            }
+
+            let old_len = self.dense.len();
+            self.reserve(data.len());
+
+            for (entity, mut component) in dense.into_iter().zip(data) {
+                if let Some(mut new_entity) = crate::storage::merged_id(entities_map, entity) {
+                    if let Some(links) = links {
+                        if !(links.remap)(&mut component, entities_map) {
+                            continue;
+                        }
+                    }
+
+                    if self.metadata.update.is_some() {
+                        new_entity.set_inserted();
+                    }
+
+                    self.dense.push(new_entity);
+                    self.data.push(component);
+                }
+            }
+
+            if self.dense.len() == old_len {
+                return;
+            }
+
+            // merged entities are new, they can't have a component in this storage yet
+            let SparseSet { sparse, dense, .. } = self;
+            let first = dense[old_len..].iter().min_by_key(|entity| entity.uindex());
+            let last = dense[old_len..].iter().max_by_key(|entity| entity.uindex());
+
+            sparse.bulk_allocate(*first.unwrap(), *last.unwrap());
+            for (i, &entity) in dense[old_len..].iter().enumerate() {
+                unsafe {
+                    *sparse.get_mut_unchecked(entity) =
+                        EntityId::new_from_parts((old_len + i) as u64, entity.gen(), 0);
+                }
+            }
+
+            if let Pack::Tight(pack) = &mut self.metadata.pack {
+                pack.is_broken = true;
+            }
+            self.sync_loose_pack_new(&self.dense[old_len..]);
+
+            for i in old_len..self.dense.len() {
+                let entity = self.dense[i];
+                self.run_on_insert(entity);
+            }
+        }
+    }
+    #[inline]
+    fn has_links(&self) -> bool {
+        matches!(
+            self.metadata.links,
+            Some(Links {
+                unlink: Some(_),
+                ..
+            })
+        )
+    }
+    #[inline]
+    fn unlink(&mut self, entity: EntityId, all_storages: &AllStorages) {
+        if let Some(unlink) = self.metadata.links.and_then(|links| links.unlink) {
+            unlink(self, entity, all_storages);
+        }
+    }
+    #[inline]
+    fn dependents(&self, entity: EntityId, all_storages: &AllStorages) -> Vec<EntityId> {
+        match self.metadata.links.and_then(|links| links.dependents) {
+            Some(dependents) => dependents(self, entity, all_storages),
+            None => Vec::new(),
+        }
+    }
+    #[inline]
+    fn has_insert_event_to_dispatch(&self) -> bool {
+        !self.metadata.on_insert_ids_dense.is_empty()
+    }
+    #[inline]
+    fn run_on_insert_global(&mut self, all_storages: &AllStorages) {
+        self.run_on_insert_global(all_storages);
     }
     #[inline]
     fn has_remove_event_to_dispatch(&self) -> bool {
@@ -903,39 +1536,48 @@ impl<T: 'static> UnknownStorage for SparseSet<T> {
     fn run_on_remove_global(&mut self, all_storages: &AllStorages) {
         self.run_on_remove_global(all_storages);
     }
-    //     #[cfg(feature = "serde1")]
-    //     fn should_serialize(&self, _: GlobalSerConfig) -> bool {
-    //         self.metadata.serde.is_some()
-    //     }
-    //     #[cfg(feature = "serde1")]
-    //     fn serialize_identifier(&self) -> Cow<'static, str> {
-    //         self.metadata
-    //             .serde
-    //             .as_ref()
-    //             .and_then(|serde| serde.identifier.as_ref())
-    //             .map(|identifier| identifier.0.clone())
-    //             .unwrap_or("".into())
-    //     }
-    //     #[cfg(feature = "serde1")]
-    //     fn serialize(
-    //         &self,
-    //         ser_config: GlobalSerConfig,
-    //         serializer: &mut dyn crate::erased_serde::Serializer,
-    //     ) -> crate::erased_serde::Result<crate::erased_serde::Ok> {
-    //         (self.metadata.serde.as_ref().unwrap().serialization)(self, ser_config, serializer)
-    //     }
-    //     #[cfg(feature = "serde1")]
-    //     fn deserialize(
-    //         &self,
-    //     ) -> Option<
-    //         fn(
-    //             GlobalDeConfig,
-    //             &HashMap<EntityId, EntityId>,
-    //             &mut dyn crate::erased_serde::Deserializer<'_>,
-    //         ) -> Result<crate::storage::Storage, crate::erased_serde::Error>,
-    //     > {
-    //         Some(self.metadata.serde.as_ref()?.deserialization)
-    //     }
+    #[cfg(feature = "serde1")]
+    fn serde_identifier(&self) -> Option<&Identifier> {
+        self.metadata.serde.as_ref().map(|serde| &serde.identifier)
+    }
+    #[cfg(feature = "serde1")]
+    fn serialize(
+        &self,
+        serializer: &mut dyn crate::erased_serde::Serializer,
+    ) -> crate::erased_serde::Result<crate::erased_serde::Ok> {
+        match &self.metadata.serde {
+            Some(serde) => (serde.serialization)(self, serializer),
+            None => Err(serde::ser::Error::custom(
+                "This storage wasn't setup for serialization.",
+            )),
+        }
+    }
+    #[cfg(feature = "serde1")]
+    fn deserialize(
+        &mut self,
+        entities_map: &EntitiesMap,
+        deserializer: &mut dyn crate::erased_serde::Deserializer<'_>,
+    ) -> crate::erased_serde::Result<()> {
+        match &self.metadata.serde {
+            Some(serde) => (serde.deserialization)(self, entities_map, deserializer),
+            None => Err(serde::de::Error::custom(
+                "This storage wasn't setup for deserialization.",
+            )),
+        }
+    }
+    #[cfg(feature = "serde1")]
+    fn deserialize_template_component(
+        &self,
+        template: &mut Template,
+        deserializer: &mut dyn crate::erased_serde::Deserializer<'_>,
+    ) -> crate::erased_serde::Result<()> {
+        match self.metadata.template_deserialization {
+            Some(template_deserialization) => (template_deserialization)(template, deserializer),
+            None => Err(serde::de::Error::custom(
+                "This storage wasn't setup to be part of templates.",
+            )),
+        }
+    }
 }
 
 #[test]
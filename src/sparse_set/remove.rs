@@ -1,5 +1,6 @@
 use crate::sparse_set::SparseSet;
 use crate::storage::{AllStorages, EntityId, StorageId};
+use crate::type_id::TypeId;
 
 pub trait Remove {
     type Out;
@@ -11,9 +12,12 @@ impl<T: 'static + Send + Sync> Remove for (T,) {
 
     #[inline]
     fn remove(all_storages: &mut AllStorages, entity: EntityId) -> Self::Out {
-        (all_storages
+        let component = all_storages
             .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<T>>(), SparseSet::new)
-            .remove(entity),)
+            .remove(entity);
+        all_storages.run_global_callbacks(StorageId::of::<SparseSet<T>>());
+
+        (component,)
     }
 }
 
@@ -23,11 +27,29 @@ macro_rules! impl_remove_component {
             type Out = ($(Option<$type>,)+);
 
             fn remove(all_storages: &mut AllStorages, entity: EntityId) -> Self::Out {
-                ($(
+                let types = [$(TypeId::of::<SparseSet<$type>>()),+];
+                let was_broken = [$(
+                    all_storages
+                        .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::<$type>::new)
+                        .metadata.pack.is_broken()
+                ),+];
+
+                let out = ($(
                     all_storages
                         .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::new)
                         .remove(entity),
-                )+)
+                )+);
+
+                $(
+                    all_storages
+                        .exclusive_storage_or_insert_mut(StorageId::of::<SparseSet<$type>>(), SparseSet::<$type>::new)
+                        .sync_tight_pack(entity, &types, was_broken[$index]);
+                )+
+                $(
+                    all_storages.run_global_callbacks(StorageId::of::<SparseSet<$type>>());
+                )+
+
+                out
             }
         }
     };
@@ -1,4 +1,5 @@
 use super::{IntoSortable, SparseSet};
+use crate::sparse_set::Pack;
 use alloc::vec::Vec;
 use core::cmp::Ordering;
 
@@ -13,8 +14,13 @@ impl<'tmp, T> IntoSortable for &'tmp mut SparseSet<T> {
 }
 
 impl<'tmp, T> Sort1<'tmp, T> {
-    /// Sorts the storage(s) using an unstable algorithm, it may reorder equal components.
+    /// Sorts the storage(s) using an unstable algorithm, it may reorder equal components.  
+    /// Sorting a tight packed storage breaks its pack until it is packed again.
     pub fn unstable(self, mut cmp: impl FnMut(&T, &T) -> Ordering) {
+        if let Pack::Tight(pack) = &mut self.0.metadata.pack {
+            pack.is_broken = true;
+        }
+
         let mut transform: Vec<usize> = (0..self.0.dense.len()).collect();
 
         transform.sort_unstable_by(|&i, &j| {
@@ -9,7 +9,7 @@ pub struct SparseArray<T>(Vec<Option<Box<T>>>);
 
 impl<T> SparseArray<T> {
     #[inline]
-    pub(super) fn new() -> Self {
+    pub(crate) fn new() -> Self {
         SparseArray(Vec::new())
     }
     #[inline]
@@ -24,7 +24,7 @@ impl<T> SparseArray<T> {
 
 impl SparseArray<[EntityId; crate::sparse_set::BUCKET_SIZE]> {
     #[inline]
-    pub(super) fn allocate_at(&mut self, entity: EntityId) {
+    pub(crate) fn allocate_at(&mut self, entity: EntityId) {
         if entity.bucket() >= self.0.len() {
             self.0.resize(entity.bucket() + 1, None);
         }
@@ -50,7 +50,7 @@ impl SparseArray<[EntityId; crate::sparse_set::BUCKET_SIZE]> {
         }
     }
     #[inline]
-    pub(super) fn get(&self, entity: EntityId) -> Option<EntityId> {
+    pub(crate) fn get(&self, entity: EntityId) -> Option<EntityId> {
         self.0
             .get(entity.bucket())?
             .as_ref()
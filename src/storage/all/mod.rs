@@ -1,18 +1,32 @@
 mod delete_any;
 mod retain;
+#[cfg(feature = "serde1")]
+mod serde;
 
 pub use delete_any::{CustomDeleteAny, DeleteAny};
 pub use retain::Retain;
+#[cfg(feature = "serde1")]
+use serde::TemplatesDeserializer;
+#[cfg(feature = "serde1")]
+pub(crate) use serde::{AllStoragesDeserializer, AllStoragesSerializer};
 
-use super::{Entities, EntityId, Storage, StorageId, Unique};
+use super::{Entities, EntityId, GenerationExhausted, Storage, StorageId, Unique};
 use crate::atomic_refcell::{AtomicRefCell, Ref, RefMut};
 use crate::borrow::AllStoragesBorrow;
+use crate::commands::CommandQueue;
+use crate::dynamic::{ComponentDescriptor, DynamicSparseSet, DynamicView, DynamicViewMut};
 use crate::error;
+use crate::events::{EventRuns, EventWriter};
 use crate::reserve::BulkEntityIter;
 use crate::sparse_set::{AddComponent, BulkAddEntity, DeleteComponent, Remove};
+use crate::template::Template;
 use crate::unknown_storage::UnknownStorage;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::any::type_name;
 use core::cell::UnsafeCell;
+use hashbrown::HashMap;
 use indexmap::{map::Entry, IndexMap};
 use parking_lot::{lock_api::RawRwLock as _, RawRwLock};
 
@@ -30,6 +44,10 @@ pub struct AllStorages {
     #[cfg(feature = "non_send")]
     thread_id: std::thread::ThreadId,
     inside_callback: UnsafeCell<bool>,
+    pub(crate) commands: CommandQueue,
+    /// Number of runs started by each workload, events are kept for two runs.
+    pub(crate) events_runs: EventRuns,
+    pub(crate) templates: HashMap<Cow<'static, str>, Template>,
 }
 
 #[cfg(not(feature = "non_send"))]
@@ -49,6 +67,9 @@ impl AllStorages {
             #[cfg(feature = "non_send")]
             thread_id: std::thread::current().id(),
             inside_callback: UnsafeCell::new(false),
+            commands: CommandQueue::default(),
+            events_runs: EventRuns::default(),
+            templates: HashMap::new(),
         }
     }
     /// Removes a unique storage.  
@@ -231,7 +252,10 @@ impl AllStorages {
         unsafe { self.lock.unlock_exclusive() };
     }
     /// Delete an entity and all its components.
-    /// Returns `true` if `entity` was alive.
+    /// Returns `true` if `entity` was alive.  
+    /// If `entity` is part of a [`hierarchy`], all its descendants are deleted too.  
+    /// All [`Relations`] `entity` is part of are removed.  
+    /// If `entity`'s generation is exhausted, a [`GenerationExhausted`] event is sent.
     ///
     /// ### Example
     ///
@@ -253,6 +277,10 @@ impl AllStorages {
     ///     assert_eq!(u32s.get(entity2), Ok(&3));
     /// });
     /// ```
+    ///
+    /// [`hierarchy`]: hierarchy/index.html
+    /// [`Relations`]: struct.Relations.html
+    /// [`GenerationExhausted`]: struct.GenerationExhausted.html
     pub fn delete_entity(&mut self, entity: EntityId) -> bool {
         // no need to lock here since we have a unique access
         let mut entities = self.entities_mut().unwrap();
@@ -260,14 +288,26 @@ impl AllStorages {
         if entities.delete_unchecked(entity) {
             drop(entities);
 
+            let dependents = self.dependents(entity);
+
             self.strip(entity);
 
+            for dependent in dependents {
+                if self.entities_mut().unwrap().delete_unchecked(dependent) {
+                    self.strip(dependent);
+                }
+            }
+
+            self.send_exhausted_events();
+
             true
         } else {
             false
         }
     }
-    /// Deletes all components from an entity without deleting it.
+    /// Deletes all components from an entity without deleting it.  
+    /// If `entity` is part of a [`hierarchy`], it's detached from its parent and its children become roots.  
+    /// All [`Relations`] `entity` is part of are removed.
     ///
     /// ### Example
     ///
@@ -281,7 +321,12 @@ impl AllStorages {
     ///
     /// all_storages.strip(entity);
     /// ```
+    ///
+    /// [`hierarchy`]: hierarchy/index.html
+    /// [`Relations`]: struct.Relations.html
     pub fn strip(&mut self, entity: EntityId) {
+        self.unlink(entity);
+
         let mut i = 0;
         let mut has_event = false;
 
@@ -293,7 +338,7 @@ impl AllStorages {
                     let storage =
                         unsafe { (&mut *(storages.get_index_mut(i).unwrap().1).0).get_mut() };
 
-                    storage.delete(entity);
+                    storage.strip(entity);
 
                     if storage.has_remove_event_to_dispatch() {
                         has_event = true;
@@ -313,6 +358,7 @@ impl AllStorages {
                     .try_borrow_mut()
                     .unwrap();
                 storage.run_on_remove_global(self);
+                unsafe { *self.inside_callback.get() = false };
 
                 i += 1;
             } else {
@@ -320,6 +366,323 @@ impl AllStorages {
             }
         }
     }
+    /// Creates a new entity with a clone of all components of `entity`.  
+    /// Storages have to opt in with [`SparseSet::enable_cloning`] or be ignored with [`SparseSet::skip_cloning`].  
+    /// Dynamic components can't be cloned, their storages have to be ignored with [`DynamicSparseSet::skip_cloning`].  
+    /// The clone isn't part of `entity`'s [`hierarchy`] and doesn't have its [`Relations`].
+    ///
+    /// ### Errors
+    ///
+    /// - `entity` is not alive.
+    /// - `entity` has components in storages that didn't opt in, nothing is cloned.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{AllStoragesViewMut, Get, View, ViewMut, World};
+    ///
+    /// struct Name(&'static str);
+    ///
+    /// let world = World::new();
+    /// let mut all_storages = world.borrow::<AllStoragesViewMut>();
+    ///
+    /// all_storages.borrow::<ViewMut<u32>>().enable_cloning();
+    /// all_storages.borrow::<ViewMut<Name>>().skip_cloning();
+    ///
+    /// let entity = all_storages.add_entity((0u32, Name("original")));
+    /// let clone = all_storages.try_clone_entity(entity).unwrap();
+    ///
+    /// all_storages.run(|u32s: View<u32>, names: View<Name>| {
+    ///     assert_eq!(u32s.get(clone), Ok(&0));
+    ///     assert!(names.get(clone).is_err());
+    /// });
+    /// ```
+    ///
+    /// [`SparseSet::enable_cloning`]: struct.SparseSet.html#method.enable_cloning
+    /// [`SparseSet::skip_cloning`]: struct.SparseSet.html#method.skip_cloning
+    /// [`DynamicSparseSet::skip_cloning`]: struct.DynamicSparseSet.html#method.skip_cloning
+    /// [`hierarchy`]: hierarchy/index.html
+    /// [`Relations`]: struct.Relations.html
+    pub fn try_clone_entity(&mut self, entity: EntityId) -> Result<EntityId, error::CloneEntity> {
+        if !self.entities_mut().unwrap().is_alive(entity) {
+            return Err(error::CloneEntity::EntityIsNotAlive);
+        }
+
+        let not_cloneable = unsafe { &mut *self.storages.get() }
+            .values_mut()
+            .filter_map(|storage| {
+                unsafe { (&mut *storage.0).get_mut() }
+                    .can_clone(entity)
+                    .err()
+            })
+            .collect::<Vec<_>>();
+
+        if !not_cloneable.is_empty() {
+            return Err(error::CloneEntity::NotCloneable(not_cloneable));
+        }
+
+        let new_entity = self.entities_mut().unwrap().generate();
+
+        let mut i = 0;
+        let mut has_event = false;
+
+        loop {
+            {
+                let storages = unsafe { &mut *self.storages.get() };
+
+                while i < storages.len() {
+                    let storage =
+                        unsafe { (&mut *(storages.get_index_mut(i).unwrap().1).0).get_mut() };
+
+                    storage.clone_component(entity, new_entity);
+
+                    if storage.has_insert_event_to_dispatch() {
+                        has_event = true;
+                        break;
+                    }
+
+                    i += 1;
+                }
+            }
+
+            if has_event {
+                has_event = false;
+                let storages = unsafe { &*self.storages.get() };
+
+                unsafe { *self.inside_callback.get() = true };
+                let mut storage = unsafe { &*(storages.get_index(i).unwrap().1).0 }
+                    .try_borrow_mut()
+                    .unwrap();
+                storage.run_on_insert_global(self);
+                unsafe { *self.inside_callback.get() = false };
+
+                i += 1;
+            } else {
+                return Ok(new_entity);
+            }
+        }
+    }
+    /// Creates a new entity with a clone of all components of `entity`.  
+    /// Storages have to opt in with [`SparseSet::enable_cloning`] or be ignored with [`SparseSet::skip_cloning`].  
+    /// Dynamic components can't be cloned, their storages have to be ignored with [`DynamicSparseSet::skip_cloning`].  
+    /// The clone isn't part of `entity`'s [`hierarchy`] and doesn't have its [`Relations`].  
+    /// Unwraps errors.
+    ///
+    /// ### Errors
+    ///
+    /// - `entity` is not alive.
+    /// - `entity` has components in storages that didn't opt in, nothing is cloned.
+    ///
+    /// [`SparseSet::enable_cloning`]: struct.SparseSet.html#method.enable_cloning
+    /// [`SparseSet::skip_cloning`]: struct.SparseSet.html#method.skip_cloning
+    /// [`DynamicSparseSet::skip_cloning`]: struct.DynamicSparseSet.html#method.skip_cloning
+    /// [`hierarchy`]: hierarchy/index.html
+    /// [`Relations`]: struct.Relations.html
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn clone_entity(&mut self, entity: EntityId) -> EntityId {
+        match self.try_clone_entity(entity) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Moves `entities` and all their components to `other`.
+    /// Moved entities get a new `EntityId` in `other` and are deleted from this `AllStorages`.
+    /// Returns the moved entities as `(old_id, new_id)` pairs, entities that aren't alive are skipped.
+    ///
+    /// Storages missing from `other` are created, without any of this storage's setup like packs or callbacks.
+    /// `Unique` storages are left untouched.
+    /// Moved entities are removed from their [`hierarchy`] and lose their [`Relations`].
+    /// `EntityId`s stored inside components aren't updated.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{AllStoragesViewMut, View, World};
+    ///
+    /// let staging = World::new();
+    /// let world = World::new();
+    ///
+    /// let mut staging = staging.borrow::<AllStoragesViewMut>();
+    /// let mut all_storages = world.borrow::<AllStoragesViewMut>();
+    ///
+    /// let entity = staging.add_entity((0u32, 1usize));
+    ///
+    /// let moved = staging.move_entities_to(&mut all_storages, &[entity]);
+    /// let (_, new_entity) = moved[0];
+    ///
+    /// all_storages.run(|u32s: View<u32>, usizes: View<usize>| {
+    ///     assert_eq!(u32s[new_entity], 0);
+    ///     assert_eq!(usizes[new_entity], 1);
+    /// });
+    /// ```
+    ///
+    /// [`hierarchy`]: hierarchy/index.html
+    /// [`Relations`]: struct.Relations.html
+    pub fn move_entities_to(
+        &mut self,
+        other: &mut AllStorages,
+        entities: &[EntityId],
+    ) -> Vec<(EntityId, EntityId)> {
+        let mut moved = Vec::with_capacity(entities.len());
+
+        {
+            let mut self_entities = self.entities_mut().unwrap();
+            let mut other_entities = other.entities_mut().unwrap();
+
+            for &entity in entities {
+                if self_entities.delete_unchecked(entity) {
+                    moved.push((entity, other_entities.generate()));
+                }
+            }
+        }
+
+        let mut inserted = Vec::new();
+
+        for (storage_id, storage) in self.storages.get_mut() {
+            if *storage_id == StorageId::of::<Entities>() {
+                continue;
+            }
+
+            // SAFE this is not `AllStorages` and we have exclusive access
+            let storage = unsafe { &mut *storage.0 };
+
+            if let Some(other_storage) = other.storage_or_insert_like(*storage_id, storage) {
+                let storage = storage.get_mut();
+
+                for &(entity, new_entity) in &moved {
+                    storage.move_component(entity, other_storage, new_entity);
+                }
+
+                if other_storage.has_insert_event_to_dispatch() {
+                    inserted.push(*storage_id);
+                }
+            }
+        }
+
+        for storage_id in inserted {
+            other.run_global_callbacks(storage_id);
+        }
+
+        // removes what's left of the moved entities and dispatches remove events
+        for &(entity, _) in &moved {
+            self.strip(entity);
+        }
+
+        self.send_exhausted_events();
+
+        moved
+    }
+    /// Moves all entities of `other` and their components to this `AllStorages`, `other` is left without any entity.
+    /// Merged entities get a new `EntityId`, components are appended in bulk for each storage.
+    /// Returns the merged entities as `(old_id, new_id)` pairs.
+    ///
+    /// Storages missing from this `AllStorages` are created, without any of `other`'s setup like packs or callbacks.
+    /// `Unique` storages are left untouched.
+    /// [`hierarchy`] and [`Relations`] are merged with their ids updated,
+    /// `EntityId`s stored inside other components aren't.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{AllStoragesViewMut, View, World};
+    ///
+    /// let world = World::new();
+    /// let chunk = World::new();
+    ///
+    /// let mut all_storages = world.borrow::<AllStoragesViewMut>();
+    /// let mut chunk = chunk.borrow::<AllStoragesViewMut>();
+    ///
+    /// all_storages.add_entity((0u32,));
+    /// chunk.bulk_add_entity((1..10).map(|i| (i as u32,)));
+    ///
+    /// let merged = all_storages.merge(&mut chunk);
+    /// assert_eq!(merged.len(), 9);
+    ///
+    /// all_storages.run(|u32s: View<u32>| {
+    ///     assert_eq!(u32s.len(), 10);
+    ///     assert_eq!(u32s[merged[0].1], 1);
+    /// });
+    /// ```
+    ///
+    /// [`hierarchy`]: hierarchy/index.html
+    /// [`Relations`]: struct.Relations.html
+    pub fn merge(&mut self, other: &mut AllStorages) -> Vec<(EntityId, EntityId)> {
+        let (merged, other_len) = {
+            let other_entities = other.entities_mut().unwrap();
+            let mut entities = self.entities_mut().unwrap();
+
+            let alive = other_entities.iter().collect::<Vec<_>>();
+            let new_entities = entities.bulk_generate(alive.len());
+
+            (
+                alive
+                    .into_iter()
+                    .zip(new_entities.iter().copied())
+                    .collect::<Vec<_>>(),
+                other_entities.data.len(),
+            )
+        };
+
+        // indexed by the index of the entities in `other`
+        let mut entities_map = alloc::vec![(EntityId::dead(), EntityId::dead()); other_len];
+        for &(entity, new_entity) in &merged {
+            entities_map[entity.uindex()] = (entity, new_entity);
+        }
+
+        for (storage_id, storage) in other.storages.get_mut() {
+            if *storage_id == StorageId::of::<Entities>() {
+                continue;
+            }
+
+            // SAFE this is not `AllStorages` and we have exclusive access
+            let storage = unsafe { &mut *storage.0 };
+
+            if let Some(self_storage) = self.storage_or_insert_like(*storage_id, storage) {
+                self_storage.merge(storage.get_mut(), &entities_map);
+            }
+        }
+
+        let storage_ids = other.storages.get_mut().keys().copied().collect::<Vec<_>>();
+        for storage_id in storage_ids {
+            self.run_global_callbacks(storage_id);
+        }
+
+        other.clear();
+
+        merged
+    }
+    /// Removes `entity` from the links storages keep between entities, like the hierarchy.
+    fn unlink(&mut self, entity: EntityId) {
+        for storage in unsafe { &*self.storages.get() }.values() {
+            // SAFE we have exclusive access, the storage is borrowed before `self` is shared with its hook
+            if unsafe { (&mut *storage.0).get_mut() }.has_links() {
+                unsafe { &*storage.0 }
+                    .try_borrow_mut()
+                    .unwrap()
+                    .unlink(entity, self);
+            }
+        }
+    }
+    /// Returns the entities deleted alongside `entity`, like its descendants in the hierarchy.
+    fn dependents(&mut self, entity: EntityId) -> Vec<EntityId> {
+        let mut dependents = Vec::new();
+
+        for storage in unsafe { &*self.storages.get() }.values() {
+            // SAFE we have exclusive access, the storage is borrowed before `self` is shared with its hook
+            if unsafe { (&mut *storage.0).get_mut() }.has_links() {
+                dependents.extend(
+                    unsafe { &*storage.0 }
+                        .try_borrow()
+                        .unwrap()
+                        .dependents(entity, self),
+                );
+            }
+        }
+
+        dependents
+    }
     /// Deletes all components of an entity except the ones passed in `S`.  
     /// The storage's type has to be used and not the component.  
     /// `SparseSet` is the default storage.
@@ -377,6 +740,7 @@ impl AllStorages {
                     .try_borrow_mut()
                     .unwrap();
                 storage.run_on_remove_global(self);
+                unsafe { *self.inside_callback.get() = false };
 
                 i += 1;
             } else {
@@ -428,12 +792,15 @@ impl AllStorages {
                     .try_borrow_mut()
                     .unwrap();
                 storage.run_on_remove_global(self);
+                unsafe { *self.inside_callback.get() = false };
 
                 i += 1;
             } else {
-                return;
+                break;
             }
         }
+
+        self.send_exhausted_events();
     }
     /// Creates a new entity with the components passed as argument and returns its `EntityId`.  
     /// `component` must always be a tuple, even for a single component.
@@ -452,7 +819,7 @@ impl AllStorages {
     #[inline]
     pub fn add_entity<T: AddComponent>(&mut self, component: T) -> EntityId {
         let entity = self.exclusive_storage_mut::<Entities>().unwrap().generate();
-        component.add_component(self, entity);
+        component.add_entity(self, entity);
 
         entity
     }
@@ -475,6 +842,229 @@ impl AllStorages {
     pub fn bulk_add_entity<T: BulkAddEntity>(&mut self, source: T) -> BulkEntityIter<'_> {
         source.bulk_add_entity(self)
     }
+    /// Registers `template` under `name`, replacing the template previously registered with this name.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{AllStoragesViewMut, Template, World};
+    ///
+    /// let world = World::new();
+    /// let mut all_storages = world.borrow::<AllStoragesViewMut>();
+    ///
+    /// all_storages.add_template("orc", Template::new().with(50u32));
+    /// ```
+    pub fn add_template(&mut self, name: impl Into<Cow<'static, str>>, template: Template) {
+        self.templates.insert(name.into(), template);
+    }
+    /// Returns the template registered under `name`.
+    pub fn template(&self, name: &str) -> Option<&Template> {
+        self.templates.get(name)
+    }
+    /// Removes the template registered under `name` and returns it.
+    pub fn remove_template(&mut self, name: &str) -> Option<Template> {
+        self.templates.remove(name)
+    }
+    /// Registers the templates serialized in `deserializer`, replacing the templates previously registered with the same names.
+    /// Templates are a map of names to templates, each template is a map of storage identifiers to components.
+    /// Storages have to be setup with [`SparseSet::setup_serde_template`].
+    ///
+    /// ### Errors
+    ///
+    /// - No storage was setup with a serialized identifier.
+    /// - A storage wasn't setup to be part of templates.
+    /// - Deserialization error.
+    ///
+    /// [`SparseSet::setup_serde_template`]: struct.SparseSet.html#method.setup_serde_template
+    #[cfg(feature = "serde1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn load_templates<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        ::serde::de::DeserializeSeed::deserialize(
+            TemplatesDeserializer { all_storages: self },
+            deserializer,
+        )
+    }
+    /// Creates a new entity with the components of the template registered under `name` and returns its `EntityId`.
+    /// Components in `overrides` are added instead of the template's ones of the same type.
+    /// `overrides` must always be a tuple, even for a single component.
+    ///
+    /// ### Errors
+    ///
+    /// - No template is registered under `name`.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{AllStoragesViewMut, Template, View, World};
+    ///
+    /// let world = World::new();
+    /// let mut all_storages = world.borrow::<AllStoragesViewMut>();
+    ///
+    /// all_storages.add_template("orc", Template::new().with(50u32).with(1usize));
+    ///
+    /// let entity = all_storages.instantiate("orc", (100u32,)).unwrap();
+    ///
+    /// all_storages.run(|u32s: View<u32>, usizes: View<usize>| {
+    ///     assert_eq!(u32s[entity], 100);
+    ///     assert_eq!(usizes[entity], 1);
+    /// });
+    /// ```
+    pub fn instantiate<C: AddComponent>(
+        &mut self,
+        name: &str,
+        overrides: C,
+    ) -> Result<EntityId, error::MissingTemplate> {
+        if !self.templates.contains_key(name) {
+            return Err(error::MissingTemplate);
+        }
+
+        let mut types = Vec::new();
+        self.templates[name].push_types(&mut types);
+        C::push_types(&mut types);
+
+        let entity = self.exclusive_storage_mut::<Entities>().unwrap().generate();
+        overrides.add_entity_among(self, entity, &types);
+        self.templates[name].add_to(self, &[entity], &types);
+
+        Ok(entity)
+    }
+    /// Creates multiple new entities with the components of the template registered under `name`
+    /// and returns an iterator yielding the new `EntityId`s.
+    /// There is one new entity for each item of `overrides`, its components are added instead of the template's ones of the same type.
+    /// `overrides` must always yield a tuple, even for a single component.
+    ///
+    /// ### Errors
+    ///
+    /// - No template is registered under `name`.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{AllStoragesViewMut, Template, View, World};
+    ///
+    /// let world = World::new();
+    /// let mut all_storages = world.borrow::<AllStoragesViewMut>();
+    ///
+    /// all_storages.add_template("orc", Template::new().with(50u32).with(1usize));
+    ///
+    /// let orcs = all_storages
+    ///     .bulk_instantiate("orc", (0..10).map(|_| ()))
+    ///     .unwrap()
+    ///     .collect::<Vec<_>>();
+    /// let bosses = all_storages
+    ///     .bulk_instantiate("orc", (0..2).map(|i| (500u32 + i,)))
+    ///     .unwrap()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// all_storages.run(|u32s: View<u32>| {
+    ///     assert_eq!(u32s[orcs[0]], 50);
+    ///     assert_eq!(u32s[bosses[1]], 501);
+    /// });
+    /// ```
+    pub fn bulk_instantiate<T: BulkAddEntity>(
+        &mut self,
+        name: &str,
+        overrides: T,
+    ) -> Result<alloc::vec::IntoIter<EntityId>, error::MissingTemplate> {
+        if !self.templates.contains_key(name) {
+            return Err(error::MissingTemplate);
+        }
+
+        let mut types = Vec::new();
+        self.templates[name].push_types(&mut types);
+        T::push_types(&mut types);
+
+        let new_entities = overrides
+            .bulk_add_entity_among(self, &types)
+            .collect::<Vec<_>>();
+        self.templates[name].add_to(self, &new_entities, &types);
+
+        Ok(new_entities.into_iter())
+    }
+    /// Registers a storage of dynamic components described by `descriptor` under `StorageId::Custom(id)`.  
+    /// Does nothing if a storage is already registered with this id.  
+    /// Moving or merging entities to another `World` panics if `id` is registered there with a different layout.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{AllStoragesViewMut, ComponentDescriptor, World};
+    ///
+    /// let world = World::new();
+    /// let mut all_storages = world.borrow::<AllStoragesViewMut>();
+    ///
+    /// all_storages.register_dynamic_component(0, ComponentDescriptor::of::<u32>("health"));
+    ///
+    /// let entity = all_storages.add_entity(());
+    /// let mut health = 10u32;
+    /// let mut healths = all_storages.borrow_dynamic_mut(0);
+    /// unsafe { healths.insert(entity, &mut health as *mut u32 as *mut u8) };
+    ///
+    /// assert!(healths.contains(entity));
+    /// ```
+    pub fn register_dynamic_component(&self, id: u64, descriptor: ComponentDescriptor) {
+        let _ = self.custom_storage_or_insert_by_id(StorageId::Custom(id), || {
+            DynamicSparseSet::new(descriptor)
+        });
+    }
+    /// Borrows the dynamic storage registered under `StorageId::Custom(id)`.
+    ///
+    /// ### Errors
+    ///
+    /// - No storage was registered with this id.
+    /// - The storage is already borrowed exclusively.
+    pub fn try_borrow_dynamic(&self, id: u64) -> Result<DynamicView<'_>, error::GetStorage> {
+        Ok(DynamicView {
+            storage: self.custom_storage_by_id(StorageId::Custom(id))?,
+            _all_borrow: None,
+        })
+    }
+    /// Borrows the dynamic storage registered under `StorageId::Custom(id)`.  
+    /// Unwraps errors.
+    ///
+    /// ### Errors
+    ///
+    /// - No storage was registered with this id.
+    /// - The storage is already borrowed exclusively.
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn borrow_dynamic(&self, id: u64) -> DynamicView<'_> {
+        match self.try_borrow_dynamic(id) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Borrows exclusively the dynamic storage registered under `StorageId::Custom(id)`.
+    ///
+    /// ### Errors
+    ///
+    /// - No storage was registered with this id.
+    /// - The storage is already borrowed.
+    pub fn try_borrow_dynamic_mut(&self, id: u64) -> Result<DynamicViewMut<'_>, error::GetStorage> {
+        Ok(DynamicViewMut {
+            storage: self.custom_storage_mut_by_id(StorageId::Custom(id))?,
+            _all_borrow: None,
+        })
+    }
+    /// Borrows exclusively the dynamic storage registered under `StorageId::Custom(id)`.  
+    /// Unwraps errors.
+    ///
+    /// ### Errors
+    ///
+    /// - No storage was registered with this id.
+    /// - The storage is already borrowed.
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn borrow_dynamic_mut(&self, id: u64) -> DynamicViewMut<'_> {
+        match self.try_borrow_dynamic_mut(id) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
     /// Adds components to an existing entity.  
     /// If the entity already owned a component it will be replaced.  
     /// `component` must always be a tuple, even for a single component.
@@ -504,11 +1094,7 @@ impl AllStorages {
         entity: EntityId,
         component: T,
     ) -> Result<(), error::AddComponent> {
-        if self
-            .exclusive_storage_mut::<Entities>()
-            .unwrap()
-            .is_alive(entity)
-        {
+        if self.entities_mut().unwrap().is_alive(entity) {
             component.add_component(self, entity);
 
             Ok(())
@@ -555,6 +1141,33 @@ impl AllStorages {
     pub fn delete_component<C: DeleteComponent>(&mut self, entity: EntityId) {
         C::delete_component(self, entity);
     }
+    /// Applies the commands recorded by [`Commands`] views and not applied yet.  
+    /// Then sends a [`GenerationExhausted`] event for each entity deleted with an exhausted generation and not reported yet,
+    /// like the ones deleted with [`EntitiesViewMut::delete_unchecked`].
+    ///
+    /// [`Commands`]: struct.Commands.html
+    /// [`GenerationExhausted`]: struct.GenerationExhausted.html
+    /// [`EntitiesViewMut::delete_unchecked`]: struct.Entities.html#method.delete_unchecked
+    pub fn apply_commands(&mut self) {
+        // commands can add components to reserved entities
+        self.exclusive_storage_mut::<Entities>().unwrap().flush();
+
+        for (_, commands) in self.commands.take() {
+            for command in commands {
+                command(self);
+            }
+        }
+
+        self.send_exhausted_events();
+    }
+    /// Returns `true` if there are commands to apply or exhausted generations to report.
+    pub(crate) fn has_pending_commands(&self) -> bool {
+        !self.commands.is_empty()
+            || self
+                .entities()
+                .map(|entities| !entities.exhausted.is_empty())
+                .unwrap_or(false)
+    }
     #[doc = "Borrows the requested storage(s), if it doesn't exist it'll get created.  
 You can use a tuple to get multiple storages at once.
 
@@ -1250,6 +1863,18 @@ let i = all_storages.run(sys1);
     pub fn delete_any<T: DeleteAny>(&mut self) {
         T::delete_any(self);
     }
+    /// Sends a [`GenerationExhausted`] event for each entity deleted with an exhausted generation.
+    ///
+    /// [`GenerationExhausted`]: struct.GenerationExhausted.html
+    fn send_exhausted_events(&mut self) {
+        let exhausted = core::mem::take(&mut self.entities_mut().unwrap().exhausted);
+
+        if !exhausted.is_empty() {
+            self.try_borrow::<EventWriter<'_, GenerationExhausted>>()
+                .unwrap()
+                .send_batch(exhausted.into_iter().map(GenerationExhausted));
+        }
+    }
     pub(crate) fn entities(&self) -> Result<Ref<'_, &'_ Entities>, error::GetStorage> {
         let storage_id = StorageId::of::<Entities>();
 
@@ -1268,7 +1893,13 @@ let i = all_storages.run(sys1);
         let storage = storages.get(&storage_id).unwrap();
         let storage = storage.get_mut::<Entities>();
         unsafe { self.lock.unlock_shared() };
-        storage.map_err(error::GetStorage::Entities)
+        // reserved entities become alive as soon as entities are borrowed exclusively
+        storage
+            .map(|mut entities| {
+                entities.flush();
+                entities
+            })
+            .map_err(error::GetStorage::Entities)
     }
     pub fn custom_storage<T: 'static>(&self) -> Result<Ref<'_, &'_ T>, error::GetStorage> {
         self.custom_storage_by_id(StorageId::of::<T>())
@@ -1645,6 +2276,30 @@ let i = all_storages.run(sys1);
             Err(error::GetStorage::MissingStorage(type_name::<T>()))
         }
     }
+    /// Returns the storage with `storage_id`, it's created empty with the same type and thread restrictions as `like` if missing.
+    /// Returns `None` if the storage is missing and `like` can't be created in another `World`.
+    fn storage_or_insert_like(
+        &mut self,
+        storage_id: StorageId,
+        like: &mut AtomicRefCell<dyn UnknownStorage>,
+    ) -> Option<&mut dyn UnknownStorage> {
+        let storage = match self.storages.get_mut().entry(storage_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut new_storage = like.get_mut().new_empty()?;
+                new_storage.copy_thread_restrictions(
+                    like,
+                    #[cfg(feature = "non_send")]
+                    self.thread_id,
+                );
+
+                entry.insert(Storage(Box::into_raw(new_storage)))
+            }
+        };
+
+        // SAFE this is not `AllStorages` and we have exclusive access
+        Some(unsafe { &mut *storage.0 }.get_mut())
+    }
     pub(crate) fn exclusive_storage_or_insert_mut<T, F>(
         &mut self,
         storage_id: StorageId,
@@ -1661,75 +2316,32 @@ let i = all_storages.run(sys1);
             .or_insert_with(|| Storage::new(f()))
             .get_mut_exclusive()
     }
+    /// Runs the global callbacks of the storage with `storage_id` if it has events to dispatch.
+    pub(crate) fn run_global_callbacks(&mut self, storage_id: StorageId) {
+        let storages = unsafe { &*self.storages.get() };
+
+        if let Some(storage) = storages.get(&storage_id) {
+            let mut storage = unsafe { &*storage.0 }.try_borrow_mut().unwrap();
+
+            if storage.has_insert_event_to_dispatch() || storage.has_remove_event_to_dispatch() {
+                unsafe { *self.inside_callback.get() = true };
+                storage.run_on_insert_global(self);
+                storage.run_on_remove_global(self);
+                unsafe { *self.inside_callback.get() = false };
+            }
+        }
+    }
 }
 
-// #[cfg(feature = "serde1")]
-// pub(crate) struct AllStoragesSerializer<'a> {
-//     pub(crate) all_storages: RefMut<'a, AllStorages>,
-//     pub(crate) ser_config: crate::serde_setup::GlobalSerConfig,
-// }
-
-// #[cfg(feature = "serde1")]
-// impl serde::Serialize for AllStoragesSerializer<'_> {
-//     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-//     where
-//         S: serde::Serializer,
-//     {
-//         use serde::ser::SerializeStruct;
-
-//         let all_storages = unsafe { &*self.all_storages.storages.get() };
-
-//         let mut storages = Vec::with_capacity(all_storages.len());
-
-//         let ser_infos = crate::serde_setup::SerInfos {
-//             same_binary: self.ser_config.same_binary,
-//             with_entities: self.ser_config.with_entities,
-//         };
-
-//         let mut state = serializer.serialize_struct("AllStorages", 3)?;
-
-//         if ser_infos.same_binary {
-//             let metadata = all_storages
-//                 .iter()
-//                 .filter_map(|(type_id, storage)| {
-//                     let storage = storage.0.try_borrow().unwrap();
-
-//                     if storage.should_serialize(self.ser_config) {
-//                         let result = match storage.deserialize() {
-//                             Some(deserialize) => Ok((
-//                                 type_id,
-//                                 crate::unknown_storage::deserialize_ptr(deserialize),
-//                             )),
-//                             None => Err(serde::ser::Error::custom(
-//                                 "Unknown storage's implementation is incorrect.",
-//                             )),
-//                         };
-
-//                         storages.push(storage);
-
-//                         Some(result)
-//                     } else {
-//                         None
-//                     }
-//                 })
-//                 .collect::<Result<Vec<_>, S::Error>>()?;
-
-//             state.serialize_field("ser_infos", &ser_infos)?;
-//             state.serialize_field("metadata", &metadata)?;
-//             state.serialize_field(
-//                 "storages",
-//                 &storages
-//                     .iter()
-//                     .map(|storage| crate::unknown_storage::StorageSerializer {
-//                         unknown_storage: &**storage,
-//                         ser_config: self.ser_config,
-//                     })
-//                     .collect::<Vec<_>>(),
-//             )?;
-//         } else {
-//             todo!()
-//         }
-
-//         state.end()
-//     }
-// }
+/// Returns the id `entity` of a merged `AllStorages` has once merged, `entities_map` comes from `AllStorages::merge`.
+pub(crate) fn merged_id(
+    entities_map: &[(EntityId, EntityId)],
+    mut entity: EntityId,
+) -> Option<EntityId> {
+    entity.clear_meta();
+
+    match entities_map.get(entity.uindex()) {
+        Some(&(old_entity, new_entity)) if old_entity == entity => Some(new_entity),
+        _ => None,
+    }
+}
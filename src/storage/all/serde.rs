@@ -0,0 +1,499 @@
+use super::AllStorages;
+use crate::serde_setup::{EntitiesMap, GlobalDeConfig, GlobalSerConfig, Loading};
+use crate::storage::{Entities, Storage, StorageId};
+use crate::template::Template;
+use crate::unknown_storage::{
+    StorageDeserializer, StorageSerializer, TemplateComponentDeserializer,
+};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use serde::ser::{SerializeMap, SerializeStruct};
+
+pub(crate) struct AllStoragesSerializer<'a> {
+    pub(crate) all_storages: &'a AllStorages,
+    pub(crate) ser_config: GlobalSerConfig,
+}
+
+impl serde::Serialize for AllStoragesSerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let entities = if self.ser_config.with_entities {
+            Some(
+                self.all_storages
+                    .entities()
+                    .map_err(serde::ser::Error::custom)?,
+            )
+        } else {
+            None
+        };
+
+        let mut state = serializer.serialize_struct("AllStorages", 2)?;
+        state.serialize_field("entities", &entities.as_deref())?;
+        state.serialize_field(
+            "storages",
+            &StoragesSerializer {
+                all_storages: self.all_storages,
+            },
+        )?;
+        state.end()
+    }
+}
+
+struct StoragesSerializer<'a> {
+    all_storages: &'a AllStorages,
+}
+
+impl serde::Serialize for StoragesSerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // SAFE the caller has exclusive access to AllStorages
+        let storages = unsafe { &*self.all_storages.storages.get() };
+
+        let mut borrows = Vec::new();
+        for storage in storages.values() {
+            // SAFE this is not `AllStorages`
+            let storage = unsafe { &*storage.0 }
+                .try_borrow()
+                .map_err(serde::ser::Error::custom)?;
+
+            if let Some(identifier) = storage.serde_identifier().cloned() {
+                if borrows.iter().any(|(other, _)| *other == identifier) {
+                    return Err(serde::ser::Error::custom(format!(
+                        "Multiple storages use the identifier `{}`.",
+                        identifier.0
+                    )));
+                }
+
+                borrows.push((identifier, storage));
+            }
+        }
+
+        let mut map = serializer.serialize_map(Some(borrows.len()))?;
+        for (identifier, storage) in &borrows {
+            map.serialize_entry(
+                &*identifier.0,
+                &StorageSerializer {
+                    unknown_storage: &**storage,
+                },
+            )?;
+        }
+        map.end()
+    }
+}
+
+/// Loads a serialized `AllStorages` into an existing one.
+pub(crate) struct AllStoragesDeserializer<'a> {
+    pub(crate) all_storages: &'a mut AllStorages,
+    pub(crate) de_config: GlobalDeConfig,
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for AllStoragesDeserializer<'_> {
+    type Value = EntitiesMap;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &["entities", "storages"];
+
+        enum Field {
+            Entities,
+            Storages,
+        }
+
+        struct FieldVisitor;
+        impl<'de> serde::de::Visitor<'de> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                formatter.write_str("field identifier")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    0u64 => Ok(Field::Entities),
+                    1u64 => Ok(Field::Storages),
+                    _ => Err(serde::de::Error::invalid_value(
+                        serde::de::Unexpected::Unsigned(value),
+                        &"field index 0 <= i < 2",
+                    )),
+                }
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "entities" => Ok(Field::Entities),
+                    "storages" => Ok(Field::Storages),
+                    _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                }
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    b"entities" => Ok(Field::Entities),
+                    b"storages" => Ok(Field::Storages),
+                    _ => Err(serde::de::Error::invalid_value(
+                        serde::de::Unexpected::Bytes(value),
+                        &"field are `entities` and `storages`",
+                    )),
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for Field {
+            #[inline]
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_identifier(FieldVisitor)
+            }
+        }
+
+        struct Visitor<'a, 'de> {
+            all_storages: &'a mut AllStorages,
+            de_config: GlobalDeConfig,
+            lifetime: PhantomData<&'de ()>,
+        }
+
+        impl<'a, 'de> serde::de::Visitor<'de> for Visitor<'a, 'de> {
+            type Value = EntitiesMap;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                formatter.write_str("struct AllStorages")
+            }
+
+            #[inline]
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let entities: Option<Entities> = seq.next_element()?.ok_or_else(|| {
+                    serde::de::Error::invalid_length(0, &"struct AllStorages with 2 elements")
+                })?;
+
+                let mut entities_map = load_entities(self.all_storages, self.de_config, entities)?;
+
+                seq.next_element_seed(StoragesDeserializer {
+                    all_storages: self.all_storages,
+                    entities_map: &entities_map,
+                })?
+                .ok_or_else(|| {
+                    serde::de::Error::invalid_length(1, &"struct AllStorages with 2 elements")
+                })?;
+
+                spawn_loaded_entities(self.all_storages, &mut entities_map)?;
+
+                Ok(entities_map)
+            }
+
+            #[inline]
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut entities_map = None;
+                let mut storages = false;
+
+                while let Some(key) = map.next_key::<Field>()? {
+                    match key {
+                        Field::Entities => {
+                            if storages {
+                                return Err(serde::de::Error::custom(
+                                    "`entities` has to come before `storages`.",
+                                ));
+                            }
+                            if entities_map.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entities"));
+                            }
+
+                            let entities: Option<Entities> = map.next_value()?;
+                            entities_map =
+                                Some(load_entities(self.all_storages, self.de_config, entities)?);
+                        }
+                        Field::Storages => {
+                            if storages {
+                                return Err(serde::de::Error::duplicate_field("storages"));
+                            }
+                            storages = true;
+
+                            if entities_map.is_none() {
+                                entities_map =
+                                    Some(load_entities(self.all_storages, self.de_config, None)?);
+                            }
+
+                            map.next_value_seed(StoragesDeserializer {
+                                all_storages: self.all_storages,
+                                entities_map: entities_map.as_ref().unwrap(),
+                            })?;
+                        }
+                    }
+                }
+
+                if !storages {
+                    return Err(serde::de::Error::missing_field("storages"));
+                }
+
+                let mut entities_map = entities_map.unwrap();
+                spawn_loaded_entities(self.all_storages, &mut entities_map)?;
+
+                Ok(entities_map)
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "AllStorages",
+            FIELDS,
+            Visitor {
+                all_storages: self.all_storages,
+                de_config: self.de_config,
+                lifetime: PhantomData,
+            },
+        )
+    }
+}
+
+/// Adds the deserialized entities to `all_storages`.
+/// If `all_storages` never had any entity and `de_config` doesn't ask for it,
+/// `Entities` is replaced and ids are kept.
+/// Otherwise each entity gets a new id, the returned map goes from the serialized id to the new one.
+///
+/// Without serialized entities, components keep their ids and `all_storages` must never have had any entity.
+/// Their entities are made alive by `spawn_loaded_entities` once all storages are loaded.
+fn load_entities<E: serde::de::Error>(
+    all_storages: &mut AllStorages,
+    de_config: GlobalDeConfig,
+    new_entities: Option<Entities>,
+) -> Result<EntitiesMap, E> {
+    let entities = all_storages
+        .storages
+        .get_mut()
+        .get_mut(&StorageId::of::<Entities>())
+        .unwrap()
+        .get_mut_exclusive::<Entities>();
+
+    entities.flush();
+
+    match new_entities {
+        Some(new_entities) => {
+            if entities.data.is_empty() && !de_config.remap_entities {
+                let entities_map = EntitiesMap::new(Loading::Kept(new_entities.data.clone()));
+                *entities = new_entities;
+
+                Ok(entities_map)
+            } else {
+                let mut entities_map = EntitiesMap::new(Loading::Remapped);
+                entities_map.map.reserve(new_entities.iter().count());
+
+                for entity in &new_entities {
+                    entities_map.map.insert(entity, entities.generate());
+                }
+
+                Ok(entities_map)
+            }
+        }
+        None if de_config.remap_entities => Err(serde::de::Error::custom(
+            "Entities have to be serialized to be remapped.",
+        )),
+        // the components' ids could belong to existing entities
+        None if !entities.data.is_empty() => Err(serde::de::Error::custom(
+            "Entities have to be serialized to be loaded in a World that already had entities.",
+        )),
+        None => Ok(EntitiesMap::new(Loading::Collected(Mutex::new(Vec::new())))),
+    }
+}
+
+/// Makes alive the entities owning the components loaded without serialized entities.
+fn spawn_loaded_entities<E: serde::de::Error>(
+    all_storages: &mut AllStorages,
+    entities_map: &mut EntitiesMap,
+) -> Result<(), E> {
+    if let Loading::Collected(ids) = &mut entities_map.loading {
+        let ids = core::mem::take(ids.get_mut());
+
+        all_storages
+            .storages
+            .get_mut()
+            .get_mut(&StorageId::of::<Entities>())
+            .unwrap()
+            .get_mut_exclusive::<Entities>()
+            .spawn(ids)
+            .map_err(|entity| {
+                serde::de::Error::custom(format!(
+                    "Components belong to multiple generations of the entity at index {} or to a dead entity.",
+                    entity.index()
+                ))
+            })?;
+    }
+
+    Ok(())
+}
+
+struct StoragesDeserializer<'a> {
+    all_storages: &'a mut AllStorages,
+    entities_map: &'a EntitiesMap,
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for StoragesDeserializer<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for StoragesDeserializer<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("a map of storages")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let storages = self.all_storages.storages.get_mut();
+
+        while let Some(identifier) = map.next_key::<String>()? {
+            let storage = storages
+                .values_mut()
+                // SAFE this is not `AllStorages` and we have exclusive access
+                .map(|storage| unsafe { &mut *storage.0 }.get_mut())
+                .find(|storage| {
+                    matches!(storage.serde_identifier(), Some(other) if other.0 == identifier)
+                })
+                .ok_or_else(|| {
+                    serde::de::Error::custom(format!(
+                        "No storage with the identifier `{}` was setup for deserialization.",
+                        identifier
+                    ))
+                })?;
+
+            map.next_value_seed(StorageDeserializer {
+                unknown_storage: storage,
+                entities_map: self.entities_map,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads a map of template names to templates into `all_storages`.
+/// Each template is a map of storage identifiers to components.
+pub(crate) struct TemplatesDeserializer<'a> {
+    pub(crate) all_storages: &'a mut AllStorages,
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for TemplatesDeserializer<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for TemplatesDeserializer<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("a map of templates")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let AllStorages {
+            storages,
+            templates,
+            ..
+        } = self.all_storages;
+        let storages = storages.get_mut();
+
+        while let Some(name) = map.next_key::<String>()? {
+            let template = map.next_value_seed(TemplateDeserializer { storages })?;
+            templates.insert(name.into(), template);
+        }
+
+        Ok(())
+    }
+}
+
+struct TemplateDeserializer<'a> {
+    storages: &'a mut IndexMap<StorageId, Storage>,
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for TemplateDeserializer<'_> {
+    type Value = Template;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for TemplateDeserializer<'_> {
+    type Value = Template;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("a map of components")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut template = Template::new();
+
+        while let Some(identifier) = map.next_key::<String>()? {
+            let storage = self
+                .storages
+                .values_mut()
+                // SAFE this is not `AllStorages` and we have exclusive access
+                .map(|storage| &*unsafe { &mut *storage.0 }.get_mut())
+                .find(|storage| {
+                    matches!(storage.serde_identifier(), Some(other) if other.0 == identifier)
+                })
+                .ok_or_else(|| {
+                    serde::de::Error::custom(format!(
+                        "No storage with the identifier `{}` was setup for deserialization.",
+                        identifier
+                    ))
+                })?;
+
+            map.next_value_seed(TemplateComponentDeserializer {
+                unknown_storage: storage,
+                template: &mut template,
+            })?;
+        }
+
+        Ok(template)
+    }
+}
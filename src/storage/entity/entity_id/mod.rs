@@ -6,8 +6,11 @@ use core::num::NonZeroU64;
 /// Handle to an entity.
 // the id is 64 bits long
 // <- 46 index -> <- 16 gen -> <- 2 meta ->
+// with the gen32 feature
+// <- 30 index -> <- 32 gen -> <- 2 meta ->
 // a generation of !0 is used as a dead entity
 // dead entities don't have any component
+// binary formats serialize the raw id with both meta bits set for gen32
 //
 // inserted and modified component are flagged using metadata
 #[derive(Clone, Copy, Eq)]
@@ -16,7 +19,10 @@ pub struct EntityId(pub(super) NonZeroU64);
 
 impl EntityId {
     // Number of bits used by the generation
+    #[cfg(not(feature = "gen32"))]
     const GEN_LEN: u64 = 16;
+    #[cfg(feature = "gen32")]
+    const GEN_LEN: u64 = 32;
     const META_LEN: u64 = 2;
     const INDEX_LEN: u64 = 64 - (Self::GEN_LEN + Self::META_LEN);
     const INDEX_MASK: u64 = !(!0 << Self::INDEX_LEN);
@@ -95,9 +101,9 @@ impl EntityId {
     }
 
     #[inline]
-    pub(crate) fn new_from_parts(index: u64, gen: u16, meta: u8) -> Self {
+    pub(crate) fn new_from_parts(index: u64, gen: u64, meta: u8) -> Self {
         assert!(index < Self::INDEX_MASK);
-        assert!(gen as u64 <= Self::max_gen());
+        assert!(gen <= Self::max_gen());
         assert!(
             meta == 0
                 || meta as u64 == (Self::MODIFIED >> (Self::INDEX_LEN + Self::GEN_LEN))
@@ -107,7 +113,7 @@ impl EntityId {
         EntityId(unsafe {
             NonZeroU64::new_unchecked(
                 (index + 1)
-                    | (gen as u64) << Self::INDEX_LEN
+                    | gen << Self::INDEX_LEN
                     | (meta as u64) << (Self::INDEX_LEN + Self::GEN_LEN),
             )
         })
@@ -1,6 +1,19 @@
 use super::EntityId;
+use core::num::NonZeroU64;
+use serde::de::{Error, Unexpected};
 use serde::{ser::SerializeTupleStruct, Deserialize, Deserializer, Serialize, Serializer};
 
+// Binary formats store the raw id, its meta bits record the index/generation split
+// to refuse ids serialized with a different `gen32` feature.
+#[cfg(not(feature = "gen32"))]
+const LAYOUT: u64 = 0;
+#[cfg(feature = "gen32")]
+const LAYOUT: u64 = EntityId::META_MASK;
+#[cfg(not(feature = "gen32"))]
+const EXPECTED_LAYOUT: &str = "an EntityId serialized without the gen32 feature";
+#[cfg(feature = "gen32")]
+const EXPECTED_LAYOUT: &str = "an EntityId serialized with the gen32 feature";
+
 impl Serialize for EntityId {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -9,10 +22,10 @@ impl Serialize for EntityId {
         if serializer.is_human_readable() {
             let mut tup = serializer.serialize_tuple_struct("EntityId", 2)?;
             tup.serialize_field(&self.index())?;
-            tup.serialize_field(&(self.gen() as u16))?;
+            tup.serialize_field(&self.gen())?;
             tup.end()
         } else {
-            self.0.serialize(serializer)
+            ((self.0.get() & !EntityId::META_MASK) | LAYOUT).serialize(serializer)
         }
     }
 }
@@ -23,10 +36,41 @@ impl<'de> Deserialize<'de> for EntityId {
         D: Deserializer<'de>,
     {
         if deserializer.is_human_readable() {
-            let (index, gen): (u64, u16) = Deserialize::deserialize(deserializer)?;
+            let (index, gen): (u64, u64) = Deserialize::deserialize(deserializer)?;
+
+            if index > EntityId::max_index() {
+                return Err(D::Error::invalid_value(
+                    Unexpected::Unsigned(index),
+                    &"an index that fits in an EntityId",
+                ));
+            }
+            if gen > EntityId::max_gen() {
+                return Err(D::Error::invalid_value(
+                    Unexpected::Unsigned(gen),
+                    &"a generation that fits in an EntityId",
+                ));
+            }
+
             Ok(EntityId::new_from_parts(index, gen, 0))
         } else {
-            Ok(EntityId(Deserialize::deserialize(deserializer)?))
+            let id: u64 = Deserialize::deserialize(deserializer)?;
+
+            // `gen32` ids have both meta bits set, the others can have at most one
+            let layout = if cfg!(feature = "gen32") {
+                id & EntityId::META_MASK == LAYOUT
+            } else {
+                id & EntityId::META_MASK != EntityId::META_MASK
+            };
+            if !layout {
+                return Err(D::Error::invalid_value(
+                    Unexpected::Unsigned(id),
+                    &EXPECTED_LAYOUT,
+                ));
+            }
+
+            NonZeroU64::new(id & !EntityId::META_MASK)
+                .map(EntityId)
+                .ok_or_else(|| D::Error::invalid_value(Unexpected::Unsigned(id), &"a non zero id"))
         }
     }
 }
@@ -1,5 +1,7 @@
 mod entity_id;
 mod iterator;
+#[cfg(feature = "serde1")]
+mod serde;
 
 pub use entity_id::EntityId;
 pub use iterator::EntitiesIter;
@@ -11,16 +13,7 @@ use crate::reserve::{BulkEntityIter, BulkReserve};
 use crate::unknown_storage::UnknownStorage;
 use alloc::vec::Vec;
 use core::iter::repeat_with;
-// #[cfg(feature = "serde1")]
-// use crate::atomic_refcell::AtomicRefCell;
-// #[cfg(feature = "serde1")]
-// use crate::serde_setup::{GlobalDeConfig, GlobalSerConfig};
-// #[cfg(feature = "serde1")]
-// use crate::storage::Storage;
-// #[cfg(feature = "serde1")]
-// use alloc::borrow::Cow;
-// #[cfg(feature = "serde1")]
-// use hashbrown::HashMap;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 /// Entities holds the EntityIds to all entities: living, removed and dead.
 ///
@@ -40,17 +33,48 @@ use core::iter::repeat_with;
 // Removed entities form a linked list inside the vector, using their index part to point to the next.
 // Removed entities are added to one end and removed from the other.
 // Dead entities are simply never added to the linked list.
+//
+// Reserved entities are taken from the list by moving a cursor along it, then past the end of the vector.
+// They're only added to `data` and removed from the list when flushed.
 pub struct Entities {
     pub(crate) data: Vec<EntityId>,
     list: Option<(usize, usize)>,
+    /// Next removed entity to reserve: 0 when nothing was reserved from the list,
+    /// `usize::MAX` when the whole list is reserved, the entity's index + 1 otherwise.
+    list_cursor: AtomicUsize,
+    /// Number of entities reserved past the end of `data`.
+    reserved_past_end: AtomicUsize,
+    /// Entities deleted with an exhausted generation and not reported yet.
+    pub(crate) exhausted: Vec<EntityId>,
 }
 
+/// Event sent when an entity is deleted and its generation can't be incremented anymore.  
+/// Its index won't be reused, the `gen32` feature makes it a lot less likely.
+///
+/// It's sent by [`AllStorages::delete_entity`], [`AllStorages::clear`] and [`AllStorages::move_entities_to`],
+/// read it with [`EventReader`].  
+/// Entities deleted with [`delete_unchecked`] are reported when commands are applied:
+/// at the end of each batch inside workloads, with [`World::apply_commands`] outside of workloads,
+/// or by the next of the methods above.
+///
+/// [`AllStorages::delete_entity`]: struct.AllStorages.html#method.delete_entity
+/// [`AllStorages::clear`]: struct.AllStorages.html#method.clear
+/// [`AllStorages::move_entities_to`]: struct.AllStorages.html#method.move_entities_to
+/// [`EventReader`]: struct.EventReader.html
+/// [`delete_unchecked`]: struct.Entities.html#method.delete_unchecked
+/// [`World::apply_commands`]: struct.World.html#method.apply_commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenerationExhausted(pub EntityId);
+
 impl Entities {
     #[inline]
     pub(crate) fn new() -> Self {
         Entities {
             data: Vec::new(),
             list: None,
+            list_cursor: AtomicUsize::new(0),
+            reserved_past_end: AtomicUsize::new(0),
+            exhausted: Vec::new(),
         }
     }
     /// Returns true if `entity` matches a living entity.
@@ -122,7 +146,92 @@ impl Entities {
             panic!("{:?}", error::AddComponent::EntityIsNotAlive);
         }
     }
+    /// Returns a new `EntityId` without borrowing `Entities` exclusively, removed entities are reused.  
+    /// The entity is only alive once `Entities` is borrowed exclusively, before that components can't be added to it.  
+    /// [`Commands`] are applied with an exclusive borrow, they can be used to add components to reserved entities.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{EntitiesView, EntitiesViewMut, World};
+    ///
+    /// let world = World::new();
+    ///
+    /// let entity = world.run(|entities: EntitiesView| {
+    ///     let entity = entities.reserve_entity();
+    ///     assert!(!entities.is_alive(entity));
+    ///     entity
+    /// });
+    ///
+    /// world.run(|entities: EntitiesViewMut| assert!(entities.is_alive(entity)));
+    /// ```
+    ///
+    /// [`Commands`]: struct.Commands.html
+    pub fn reserve_entity(&self) -> EntityId {
+        if let Some((new, old)) = self.list {
+            let mut cursor = self.list_cursor.load(Ordering::Acquire);
+
+            loop {
+                let index = match cursor {
+                    0 => old,
+                    usize::MAX => break,
+                    cursor => cursor - 1,
+                };
+                // `data` can't be modified while `Entities` is shared
+                let next = if index == new {
+                    usize::MAX
+                } else {
+                    self.data[index].uindex() + 1
+                };
+
+                match self.list_cursor.compare_exchange_weak(
+                    cursor,
+                    next,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => {
+                        return EntityId::new_from_parts(index as u64, self.data[index].gen(), 0)
+                    }
+                    Err(current) => cursor = current,
+                }
+            }
+        }
+
+        let offset = self.reserved_past_end.fetch_add(1, Ordering::Relaxed);
+        EntityId::new((self.data.len() + offset) as u64)
+    }
+    /// Makes all reserved entities alive.
+    pub(crate) fn flush(&mut self) {
+        let cursor = core::mem::replace(self.list_cursor.get_mut(), 0);
+
+        if cursor != 0 {
+            let (new, mut index) = self.list.unwrap();
+
+            loop {
+                let next = self.data[index].uindex();
+                self.data[index].set_index(index as u64);
+
+                if index == new {
+                    self.list = None;
+                    break;
+                } else if next + 1 == cursor {
+                    self.list = Some((new, next));
+                    break;
+                }
+
+                index = next;
+            }
+        }
+
+        let reserved = core::mem::replace(self.reserved_past_end.get_mut(), 0);
+        let len = self.data.len();
+
+        self.data
+            .extend((len as u64..(len + reserved) as u64).map(EntityId::new));
+    }
     pub(crate) fn generate(&mut self) -> EntityId {
+        self.flush();
+
         if let Some((new, ref mut old)) = self.list {
             let old_index = *old;
 
@@ -146,14 +255,60 @@ impl Entities {
         }
     }
     pub(crate) fn bulk_generate(&mut self, count: usize) -> &[EntityId] {
+        self.flush();
+
         self.data
             .extend((self.data.len() as u64..(self.data.len() + count) as u64).map(EntityId::new));
 
         &self.data[self.data.len() - count..self.data.len()]
     }
-    /// Delete an entity, returns true if the entity was alive.  
-    /// If the entity has components, they will not be deleted and still be accessible using this id.
+    /// Makes `ids` alive in an `Entities` that never had any entity, indices in between are added to the removed entities.
+    /// Returns an id sharing its index with another one or a dead id if there is any.
+    #[cfg(feature = "serde1")]
+    pub(crate) fn spawn(&mut self, mut ids: Vec<EntityId>) -> Result<(), EntityId> {
+        debug_assert!(self.data.is_empty());
+
+        ids.sort_unstable_by_key(|id| (id.index(), id.gen()));
+        ids.dedup();
+
+        if let Some(&id) = ids.iter().find(|id| id.is_dead()) {
+            return Err(id);
+        }
+        if let Some(ids) = ids.windows(2).find(|ids| ids[0].index() == ids[1].index()) {
+            return Err(ids[1]);
+        }
+
+        self.data
+            .reserve(ids.last().map_or(0, |last| last.uindex() + 1));
+
+        for mut id in ids {
+            for index in self.data.len()..id.uindex() {
+                self.data
+                    .push(EntityId::new_from_parts(EntityId::max_index(), 0, 0));
+
+                if let Some((ref mut new, _)) = self.list {
+                    self.data[*new].set_index(index as u64);
+                    *new = index;
+                } else {
+                    self.list = Some((index, index));
+                }
+            }
+
+            id.clear_meta();
+            self.data.push(id);
+        }
+
+        Ok(())
+    }
+    /// Delete an entity, returns true if the entity was alive.
+    /// If the entity has components, they will not be deleted and still be accessible using this id.  
+    /// If the entity's generation can't be incremented anymore, its index won't be reused
+    /// and it'll be reported by a [`GenerationExhausted`] event the next time commands are applied.
+    ///
+    /// [`GenerationExhausted`]: struct.GenerationExhausted.html
     pub fn delete_unchecked(&mut self, entity_id: EntityId) -> bool {
+        self.flush();
+
         if self.is_alive(entity_id) {
             // SAFE we checked for OOB
             if unsafe {
@@ -183,6 +338,14 @@ impl Entities {
                     };
                     self.list = Some((entity_id.uindex(), entity_id.uindex()));
                 }
+            } else {
+                // SAFE we checked for OOB
+                unsafe {
+                    self.data
+                        .get_unchecked_mut(entity_id.uindex())
+                        .copy_gen(EntityId::dead())
+                };
+                self.exhausted.push(entity_id);
             }
 
             true
@@ -240,6 +403,8 @@ impl Entities {
         mut storages: T,
         component: I,
     ) -> BulkEntityIter<'_> {
+        self.flush();
+
         let mut iter = component.into_iter();
         let len = iter.size_hint().0;
 
@@ -267,6 +432,8 @@ impl Entities {
 
 impl UnknownStorage for Entities {
     fn clear(&mut self) {
+        self.flush();
+
         if self.data.is_empty() {
             return;
         }
@@ -277,6 +444,10 @@ impl UnknownStorage for Entities {
 
             if id.bump_gen().is_ok() {
                 last_alive = i as u64;
+            } else if !id.is_dead() && id.uindex() == i {
+                // alive entity with an exhausted generation
+                self.exhausted.push(*id);
+                id.copy_gen(EntityId::dead());
             }
 
             id.set_index(target);
@@ -285,204 +456,18 @@ impl UnknownStorage for Entities {
         let begin = self
             .data
             .iter()
-            .position(|id| id.gen() < EntityId::max_gen())
-            .unwrap();
+            .position(|id| id.gen() < EntityId::max_gen());
         let end = self
             .data
             .iter()
-            .rev()
-            .position(|id| id.gen() < EntityId::max_gen())
-            .unwrap();
-        self.list = Some((self.data.len() - end - 1, begin));
+            .rposition(|id| id.gen() < EntityId::max_gen());
+        self.list = match (begin, end) {
+            (Some(begin), Some(end)) => Some((end, begin)),
+            _ => None,
+        };
     }
-    // #[cfg(feature = "serde1")]
-    // fn should_serialize(&self, ser_config: GlobalSerConfig) -> bool {
-    //     ser_config.with_entities
-    // }
-    // #[cfg(feature = "serde1")]
-    // fn serialize(
-    //     &self,
-    //     _: GlobalSerConfig,
-    //     serializer: &mut dyn crate::erased_serde::Serializer,
-    // ) -> crate::erased_serde::Result<crate::erased_serde::Ok> {
-    //     crate::erased_serde::Serialize::erased_serialize(self, serializer)
-    // }
-    // #[cfg(feature = "serde1")]
-    // fn serialize_identifier(&self) -> Cow<'static, str> {
-    //     // use @ to avoid conflict
-    //     "Entities @@@".into()
-    // }
-    // #[cfg(feature = "serde1")]
-    // #[allow(unused)]
-    // fn deserialize(
-    //     &self,
-    // ) -> Option<
-    //     fn(
-    //         GlobalDeConfig,
-    //         &HashMap<EntityId, EntityId>,
-    //         &mut dyn crate::erased_serde::Deserializer<'_>,
-    //     ) -> Result<Storage, crate::erased_serde::Error>,
-    // > {
-    //     Some(
-    //         |de_config: GlobalDeConfig,
-    //          entities_map: &HashMap<EntityId, EntityId>,
-    //          deserializer: &mut dyn crate::erased_serde::Deserializer<'_>| {
-    //             #[cfg(feature = "std")]
-    //             {
-    //                 Ok(Storage(Box::new(AtomicRefCell::new(
-    //                     <Entities as serde::Deserialize>::deserialize(deserializer)?,
-    //                     None,
-    //                     true,
-    //                 ))))
-    //             }
-    //             #[cfg(not(feature = "std"))]
-    //             {
-    //                 Ok(Storage(Box::new(AtomicRefCell::new(
-    //                     <Entities as serde::Deserialize>::deserialize(deserializer)?,
-    //                 ))))
-    //             }
-    //         },
-    //     )
-    // }
 }
 
-// #[cfg(feature = "serde1")]
-// impl serde::Serialize for Entities {
-//     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-//     where
-//         S: serde::Serializer,
-//     {
-//         use serde::ser::SerializeStruct;
-
-//         let mut state = serializer.serialize_struct("Entities", 2)?;
-
-//         state.serialize_field("data", &self.data)?;
-//         state.serialize_field("list", &self.list)?;
-//         state.end()
-//     }
-// }
-
-// #[cfg(feature = "serde1")]
-// impl<'de> serde::Deserialize<'de> for Entities {
-//     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-//     where
-//         D: serde::Deserializer<'de>,
-//     {
-//         const FIELDS: &'static [&'static str] = &["data", "list"];
-
-//         enum Field {
-//             Data,
-//             List,
-//         }
-
-//         struct FieldVisitor;
-//         impl<'de> serde::de::Visitor<'de> for FieldVisitor {
-//             type Value = Field;
-//             fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-//                 formatter.write_str("field identifier")
-//             }
-//             fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
-//             where
-//                 E: serde::de::Error,
-//             {
-//                 match value {
-//                     0u64 => Ok(Field::Data),
-//                     1u64 => Ok(Field::List),
-//                     _ => Err(serde::de::Error::invalid_value(
-//                         serde::de::Unexpected::Unsigned(value),
-//                         &"field index 0 <= i < 2",
-//                     )),
-//                 }
-//             }
-//             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-//             where
-//                 E: serde::de::Error,
-//             {
-//                 match value {
-//                     "data" => Ok(Field::Data),
-//                     "list" => Ok(Field::List),
-//                     _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
-//                 }
-//             }
-//             fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
-//             where
-//                 E: serde::de::Error,
-//             {
-//                 match value {
-//                     b"data" => Ok(Field::Data),
-//                     b"list" => Ok(Field::List),
-//                     _ => Err(serde::de::Error::invalid_value(
-//                         serde::de::Unexpected::Bytes(value),
-//                         &"field bytes `data` or `list`",
-//                     )),
-//                 }
-//             }
-//         }
-//         impl<'de> serde::Deserialize<'de> for Field {
-//             #[inline]
-//             fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-//             where
-//                 D: serde::Deserializer<'de>,
-//             {
-//                 deserializer.deserialize_identifier(FieldVisitor)
-//             }
-//         }
-
-//         struct Visitor;
-//         impl<'de> serde::de::Visitor<'de> for Visitor {
-//             type Value = Entities;
-//             fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-//                 formatter.write_str("struct Entities")
-//             }
-//             #[inline]
-//             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-//             where
-//                 A: serde::de::SeqAccess<'de>,
-//             {
-//                 let data = seq.next_element::<Vec<EntityId>>()?.ok_or_else(|| {
-//                     serde::de::Error::invalid_length(0, &"struct Entities with 2 elements")
-//                 })?;
-//                 let list = seq
-//                     .next_element::<Option<(usize, usize)>>()?
-//                     .ok_or_else(|| {
-//                         serde::de::Error::invalid_length(1, &"struct Entities with 2 elements")
-//                     })?;
-//                 Ok(Entities { data, list })
-//             }
-//             #[inline]
-//             fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-//             where
-//                 A: serde::de::MapAccess<'de>,
-//             {
-//                 let mut data: Option<Vec<EntityId>> = None;
-//                 let mut list: Option<Option<(usize, usize)>> = None;
-//                 while let Some(key) = map.next_key::<Field>()? {
-//                     match key {
-//                         Field::Data => {
-//                             if data.is_some() {
-//                                 return Err(serde::de::Error::duplicate_field("data"));
-//                             }
-//                             data = Some(map.next_value::<Vec<EntityId>>()?);
-//                         }
-//                         Field::List => {
-//                             if list.is_some() {
-//                                 return Err(serde::de::Error::duplicate_field("list"));
-//                             }
-//                             list = Some(map.next_value::<Option<(usize, usize)>>()?);
-//                         }
-//                     }
-//                 }
-//                 let data = data.ok_or_else(|| serde::de::Error::missing_field("data"))?;
-//                 let list = list.ok_or_else(|| serde::de::Error::missing_field("list"))?;
-
-//                 Ok(Entities { data, list })
-//             }
-//         }
-
-//         deserializer.deserialize_struct("Entities", FIELDS, Visitor)
-//     }
-// }
-
 #[test]
 fn entities() {
     let mut entities = Entities::new();
@@ -512,15 +497,53 @@ fn entities() {
     assert_eq!(key02.index(), 0);
     assert_eq!(key02.gen(), 2);
 
-    let last_key = EntityId::new_from_parts(0, EntityId::max_gen() as u16 - 1, 0);
+    let last_key = EntityId::new_from_parts(0, EntityId::max_gen() - 1, 0);
     entities.data[0] = last_key;
     assert!(entities.delete_unchecked(last_key));
+    assert!(!entities.is_alive(last_key));
+    assert_eq!(entities.exhausted, &[last_key]);
     assert_eq!(entities.list, None);
     let dead = entities.generate();
     assert_eq!(dead.index(), 2);
     assert_eq!(dead.gen(), 0);
 }
 
+#[test]
+fn reserve() {
+    let mut entities = Entities::new();
+
+    let key00 = entities.generate();
+    let key10 = entities.generate();
+    let key20 = entities.generate();
+    entities.delete_unchecked(key00);
+    entities.delete_unchecked(key10);
+    entities.delete_unchecked(key20);
+
+    let key01 = entities.reserve_entity();
+    let key11 = entities.reserve_entity();
+    assert_eq!((key01.index(), key01.gen()), (0, 1));
+    assert_eq!((key11.index(), key11.gen()), (1, 1));
+    assert!(!entities.is_alive(key01));
+
+    entities.flush();
+    assert!(entities.is_alive(key01));
+    assert!(entities.is_alive(key11));
+    assert_eq!(entities.list, Some((2, 2)));
+
+    let key21 = entities.reserve_entity();
+    let key30 = entities.reserve_entity();
+    let key40 = entities.reserve_entity();
+    assert_eq!((key21.index(), key21.gen()), (2, 1));
+    assert_eq!((key30.index(), key30.gen()), (3, 0));
+    assert_eq!((key40.index(), key40.gen()), (4, 0));
+
+    let key50 = entities.generate();
+    assert_eq!((key50.index(), key50.gen()), (5, 0));
+    assert!(entities.is_alive(key21));
+    assert!(entities.is_alive(key40));
+    assert_eq!(entities.list, None);
+}
+
 #[test]
 fn iterator() {
     let mut entities = Entities::new();
@@ -0,0 +1,183 @@
+use super::{Entities, EntityId};
+use alloc::vec::Vec;
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for Entities {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Entities", 2)?;
+
+        state.serialize_field("data", &self.data)?;
+        state.serialize_field("list", &self.list)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Entities {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &["data", "list"];
+
+        enum Field {
+            Data,
+            List,
+        }
+
+        struct FieldVisitor;
+        impl<'de> serde::de::Visitor<'de> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                formatter.write_str("field identifier")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    0u64 => Ok(Field::Data),
+                    1u64 => Ok(Field::List),
+                    _ => Err(serde::de::Error::invalid_value(
+                        serde::de::Unexpected::Unsigned(value),
+                        &"field index 0 <= i < 2",
+                    )),
+                }
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "data" => Ok(Field::Data),
+                    "list" => Ok(Field::List),
+                    _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                }
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    b"data" => Ok(Field::Data),
+                    b"list" => Ok(Field::List),
+                    _ => Err(serde::de::Error::invalid_value(
+                        serde::de::Unexpected::Bytes(value),
+                        &"field are `data` and `list`",
+                    )),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Field {
+            #[inline]
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_identifier(FieldVisitor)
+            }
+        }
+
+        struct Visitor;
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Entities;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                formatter.write_str("struct Entities")
+            }
+
+            #[inline]
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let data = seq.next_element::<Vec<EntityId>>()?.ok_or_else(|| {
+                    serde::de::Error::invalid_length(0, &"struct Entities with 2 elements")
+                })?;
+                let list = seq
+                    .next_element::<Option<(usize, usize)>>()?
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_length(1, &"struct Entities with 2 elements")
+                    })?;
+
+                validate(data, list)
+            }
+
+            #[inline]
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut data: Option<Vec<EntityId>> = None;
+                let mut list: Option<Option<(usize, usize)>> = None;
+
+                while let Some(key) = map.next_key::<Field>()? {
+                    match key {
+                        Field::Data => {
+                            if data.is_some() {
+                                return Err(serde::de::Error::duplicate_field("data"));
+                            }
+                            data = Some(map.next_value::<Vec<EntityId>>()?);
+                        }
+                        Field::List => {
+                            if list.is_some() {
+                                return Err(serde::de::Error::duplicate_field("list"));
+                            }
+                            list = Some(map.next_value::<Option<(usize, usize)>>()?);
+                        }
+                    }
+                }
+
+                let data = data.ok_or_else(|| serde::de::Error::missing_field("data"))?;
+                let list = list.ok_or_else(|| serde::de::Error::missing_field("list"))?;
+
+                validate(data, list)
+            }
+        }
+
+        deserializer.deserialize_struct("Entities", FIELDS, Visitor)
+    }
+}
+
+/// `Entities` uses unchecked accesses to walk its list of removed entities.
+/// We have to make sure the list is well formed before accepting it.
+fn validate<E: serde::de::Error>(
+    data: Vec<EntityId>,
+    list: Option<(usize, usize)>,
+) -> Result<Entities, E> {
+    if let Some((new, old)) = list {
+        let mut current = old;
+        let mut steps = 0;
+
+        while current != new {
+            current = match data.get(current) {
+                Some(id) if steps < data.len() => id.uindex(),
+                _ => {
+                    return Err(serde::de::Error::custom(
+                        "Entities' list of removed entities is corrupted.",
+                    ))
+                }
+            };
+            steps += 1;
+        }
+
+        if new >= data.len() {
+            return Err(serde::de::Error::custom(
+                "Entities' list of removed entities is corrupted.",
+            ));
+        }
+    }
+
+    Ok(Entities {
+        data,
+        list,
+        ..Entities::new()
+    })
+}
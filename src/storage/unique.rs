@@ -1,3 +1,5 @@
+#[cfg(feature = "serde1")]
+use crate::serde_setup::{EntitiesMap, Identifier, SerConfig, SerdeInfos, VisitEntityIds};
 use crate::unknown_storage::UnknownStorage;
 
 /// Type used to [`FakeBorrow`] unique storages.
@@ -6,15 +8,86 @@ use crate::unknown_storage::UnknownStorage;
 pub struct Unique<T> {
     pub(crate) value: T,
     pub(crate) is_modified: bool,
+    #[cfg(feature = "serde1")]
+    pub(crate) serde: Option<SerdeInfos<Unique<T>>>,
 }
 
-impl<T: 'static> UnknownStorage for Unique<T> {}
+impl<T: 'static> UnknownStorage for Unique<T> {
+    #[cfg(feature = "serde1")]
+    fn serde_identifier(&self) -> Option<&Identifier> {
+        self.serde.as_ref().map(|serde| &serde.identifier)
+    }
+    #[cfg(feature = "serde1")]
+    fn serialize(
+        &self,
+        serializer: &mut dyn crate::erased_serde::Serializer,
+    ) -> crate::erased_serde::Result<crate::erased_serde::Ok> {
+        match &self.serde {
+            Some(serde) => (serde.serialization)(self, serializer),
+            None => Err(serde::ser::Error::custom(
+                "This storage wasn't setup for serialization.",
+            )),
+        }
+    }
+    #[cfg(feature = "serde1")]
+    fn deserialize(
+        &mut self,
+        entities_map: &EntitiesMap,
+        deserializer: &mut dyn crate::erased_serde::Deserializer<'_>,
+    ) -> crate::erased_serde::Result<()> {
+        match &self.serde {
+            Some(serde) => (serde.deserialization)(self, entities_map, deserializer),
+            None => Err(serde::de::Error::custom(
+                "This storage wasn't setup for deserialization.",
+            )),
+        }
+    }
+}
 
 impl<T> Unique<T> {
     pub(crate) fn new(value: T) -> Self {
         Unique {
             value,
             is_modified: false,
+            #[cfg(feature = "serde1")]
+            serde: None,
         }
     }
 }
+
+#[cfg(feature = "serde1")]
+impl<T: serde::Serialize + for<'de> serde::Deserialize<'de> + 'static> Unique<T> {
+    pub(crate) fn setup_serde(&mut self, ser_config: SerConfig) {
+        self.serde = Some(SerdeInfos::new::<T>(
+            ser_config,
+            |unique, serializer| {
+                crate::erased_serde::Serialize::erased_serialize(&unique.value, serializer)
+            },
+            |unique, _, deserializer| {
+                unique.value = crate::erased_serde::deserialize(deserializer)?;
+                unique.is_modified = true;
+
+                Ok(())
+            },
+        ));
+    }
+}
+
+#[cfg(feature = "serde1")]
+impl<T: serde::Serialize + for<'de> serde::Deserialize<'de> + VisitEntityIds + 'static> Unique<T> {
+    pub(crate) fn setup_serde_remap(&mut self, ser_config: SerConfig) {
+        self.serde = Some(SerdeInfos::new::<T>(
+            ser_config,
+            |unique, serializer| {
+                crate::erased_serde::Serialize::erased_serialize(&unique.value, serializer)
+            },
+            |unique, entities_map, deserializer| {
+                unique.value = crate::erased_serde::deserialize(deserializer)?;
+                crate::serde_setup::remap(&mut unique.value, entities_map);
+                unique.is_modified = true;
+
+                Ok(())
+            },
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::reserve::BulkReserve;
+use crate::sparse_set::SparseSet;
+use crate::storage::{AllStorages, EntityId, StorageId};
+use crate::type_id::TypeId;
+use crate::view::ViewMut;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// Deserializes a component and adds it to a template.
+#[cfg(feature = "serde1")]
+pub(crate) type TemplateDeserialization = fn(
+    &mut Template,
+    &mut dyn crate::erased_serde::Deserializer<'_>,
+) -> crate::erased_serde::Result<()>;
+
+/// Named bundle of components new entities can be created from.
+/// Registered with [`World::add_template`] and instantiated with [`World::instantiate`] or [`World::bulk_instantiate`].
+///
+/// Templates can also be loaded with [`World::load_templates`] when the `serde1` feature is enabled.
+///
+/// ### Example
+/// ```
+/// use shipyard::{Template, View, World};
+///
+/// #[derive(Clone)]
+/// struct Health(u32);
+/// #[derive(Clone)]
+/// struct Name(&'static str);
+///
+/// let mut world = World::new();
+///
+/// world.add_template("orc", Template::new().with(Health(50)).with(Name("orc")));
+///
+/// let orc = world.instantiate("orc", ()).unwrap();
+/// let boss = world.instantiate("orc", (Health(500),)).unwrap();
+///
+/// world.run(|healths: View<Health>, names: View<Name>| {
+///     assert_eq!(healths[orc].0, 50);
+///     assert_eq!(healths[boss].0, 500);
+///     assert_eq!(names[boss].0, "orc");
+/// });
+/// ```
+///
+/// [`World::add_template`]: struct.World.html#method.add_template
+/// [`World::instantiate`]: struct.World.html#method.instantiate
+/// [`World::bulk_instantiate`]: struct.World.html#method.bulk_instantiate
+/// [`World::load_templates`]: struct.World.html#method.load_templates
+pub struct Template {
+    components: Vec<Box<dyn TemplateComponent>>,
+}
+
+impl Template {
+    /// Creates an empty template.
+    pub fn new() -> Self {
+        Template {
+            components: Vec::new(),
+        }
+    }
+    /// Adds `component` to the template, replacing the component of the same type if there was one.
+    pub fn with<T: 'static + Clone + Send + Sync>(mut self, component: T) -> Self {
+        self.insert(component);
+        self
+    }
+    /// Adds `component` to the template, replacing the component of the same type if there was one.
+    pub fn insert<T: 'static + Clone + Send + Sync>(&mut self, component: T) {
+        let component: Box<dyn TemplateComponent> = Box::new(Component(component));
+        let storage_id = component.storage_id();
+
+        match self
+            .components
+            .iter_mut()
+            .find(|other| other.storage_id() == storage_id)
+        {
+            Some(other) => *other = component,
+            None => self.components.push(component),
+        }
+    }
+    /// Returns the number of components in the template.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+    /// Returns `true` if the template doesn't have any component.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+    /// Pushes the storages of the template's components.
+    pub(crate) fn push_types(&self, types: &mut Vec<TypeId>) {
+        for component in &self.components {
+            types.push(component.type_id());
+        }
+    }
+    /// Adds a clone of the template's components to `entities`.
+    /// Components an entity already has are kept.  
+    /// `entities` are new, `types` lists all storages they have a component in once the template is added.
+    pub(crate) fn add_to(
+        &self,
+        all_storages: &AllStorages,
+        entities: &[EntityId],
+        types: &[TypeId],
+    ) {
+        for component in &self.components {
+            component.add_to(all_storages, entities, types);
+        }
+    }
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Template::new()
+    }
+}
+
+impl Clone for Template {
+    fn clone(&self) -> Self {
+        Template {
+            components: self
+                .components
+                .iter()
+                .map(|component| component.clone_box())
+                .collect(),
+        }
+    }
+}
+
+/// Type erased component of a template.
+trait TemplateComponent: Send + Sync {
+    fn storage_id(&self) -> StorageId;
+    fn type_id(&self) -> TypeId;
+    fn add_to(&self, all_storages: &AllStorages, entities: &[EntityId], types: &[TypeId]);
+    fn clone_box(&self) -> Box<dyn TemplateComponent>;
+}
+
+struct Component<T>(T);
+
+impl<T: 'static + Clone + Send + Sync> TemplateComponent for Component<T> {
+    fn storage_id(&self) -> StorageId {
+        StorageId::of::<SparseSet<T>>()
+    }
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<SparseSet<T>>()
+    }
+    fn add_to(&self, all_storages: &AllStorages, entities: &[EntityId], types: &[TypeId]) {
+        let mut sparse_set = all_storages.try_borrow::<ViewMut<'_, T>>().unwrap();
+
+        let was_broken = sparse_set.metadata.pack.is_broken();
+        sparse_set.bulk_reserve(entities);
+        for &entity in entities {
+            if !sparse_set.contains(entity) {
+                sparse_set.insert(entity, self.0.clone());
+            }
+
+            // the entities are new, they can only complete the pack
+            sparse_set.sync_tight_pack_new(entity, types, was_broken);
+        }
+    }
+    fn clone_box(&self) -> Box<dyn TemplateComponent> {
+        Box::new(Component(self.0.clone()))
+    }
+}
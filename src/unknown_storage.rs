@@ -1,14 +1,13 @@
+use crate::atomic_refcell::AtomicRefCell;
+#[cfg(feature = "serde1")]
+use crate::serde_setup::{EntitiesMap, Identifier};
 use crate::storage::AllStorages;
 use crate::storage::EntityId;
+#[cfg(feature = "serde1")]
+use crate::template::Template;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::any::Any;
-// #[cfg(feature = "serde1")]
-// use alloc::borrow::Cow;
-// #[cfg(feature = "serde1")]
-// use crate::serde_setup::{GlobalDeConfig, GlobalSerConfig, ANCHOR};
-// #[cfg(feature = "serde1")]
-// use crate::storage::Storage;
-// #[cfg(feature = "serde1")]
-// use hashbrown::HashMap;
 
 pub trait SizedAny {
     fn as_any(&self) -> &dyn Any;
@@ -35,90 +34,172 @@ pub trait UnknownStorage: SizedAny {
     }
     #[inline]
     fn delete(&mut self, _entity: EntityId) {}
+    /// Deletes `entity`'s component, called when `entity` is deleted from all storages at once.
+    #[inline]
+    fn strip(&mut self, entity: EntityId) {
+        self.delete(entity);
+    }
     #[inline]
     fn clear(&mut self) {}
+    /// Returns the component's name if `entity` has a component in this storage that can't be cloned.
+    #[inline]
+    fn can_clone(&self, _entity: EntityId) -> Result<(), &'static str> {
+        Ok(())
+    }
+    /// Clones `entity`'s component to `new_entity`, called when `entity` is cloned in all storages at once.
+    #[inline]
+    fn clone_component(&mut self, _entity: EntityId, _new_entity: EntityId) {}
+    /// Returns an empty storage of the same type, used to create this storage in another `World` when entities are moved to it.  
+    /// The returned storage doesn't have any thread restriction, they're copied from this storage by the caller.  
+    /// Storages returning `None` aren't created and keep no component of the moved entities.
+    #[inline]
+    fn new_empty(&self) -> Option<Box<AtomicRefCell<dyn UnknownStorage>>> {
+        None
+    }
+    /// Moves `entity`'s component to `other`, a storage of the same type in another `World`, where it belongs to `new_entity`.
+    #[inline]
+    fn move_component(
+        &mut self,
+        _entity: EntityId,
+        _other: &mut dyn UnknownStorage,
+        _new_entity: EntityId,
+    ) {
+    }
+    /// Moves all components of `other`, a storage of the same type in another `World`, to this storage.  
+    /// `entities_map` is indexed by the index of the entities in `other`'s `World` and holds their `(old_id, new_id)`,
+    /// components of entities not in `entities_map` are dropped.
+    #[inline]
+    fn merge(&mut self, _other: &mut dyn UnknownStorage, _entities_map: &[(EntityId, EntityId)]) {}
+    /// Returns `true` if this storage keeps links between entities and has to be unlinked before an entity is stripped.
+    #[inline]
+    fn has_links(&self) -> bool {
+        false
+    }
+    /// Removes `entity` from the links this storage keeps between entities, like the hierarchy.  
+    /// Called before `entity` is stripped, other storages can be borrowed from `all_storages`.
+    #[inline]
+    fn unlink(&mut self, _entity: EntityId, _all_storages: &AllStorages) {}
+    /// Returns the entities deleted alongside `entity` when it's deleted from all storages, in deletion order.
+    #[inline]
+    fn dependents(&self, _entity: EntityId, _all_storages: &AllStorages) -> Vec<EntityId> {
+        Vec::new()
+    }
+    /// Returns `true` if components were inserted since the last time global insert callbacks ran.
+    #[inline]
+    fn has_insert_event_to_dispatch(&self) -> bool {
+        false
+    }
+    /// Runs global insert callbacks for all components inserted since the last time they ran.
+    #[inline]
+    fn run_on_insert_global(&mut self, _all_storages: &AllStorages) {}
+    /// Returns `true` if components were removed or deleted since the last time global remove callbacks ran.
     #[inline]
     fn has_remove_event_to_dispatch(&self) -> bool {
         false
     }
+    /// Runs global remove callbacks for all components removed or deleted since the last time they ran.
     #[inline]
     fn run_on_remove_global(&mut self, _all_storages: &AllStorages) {}
-    // #[cfg(feature = "serde1")]
-    // fn should_serialize(&self, _: GlobalSerConfig) -> bool {
-    //     false
-    // }
-    // #[cfg(feature = "serde1")]
-    // fn serialize(
-    //     &self,
-    //     ser_config: GlobalSerConfig,
-    //     _: &mut dyn crate::erased_serde::Serializer,
-    // ) -> crate::erased_serde::Result<crate::erased_serde::Ok> {
-    //     assert!(
-    //         self.should_serialize(ser_config),
-    //         "UnknownStorage's (de)ser impl is incorrect."
-    //     );
-    //     Err(serde::ser::Error::custom("This type isn't serializable."))
-    // }
-    // #[cfg(feature = "serde1")]
-    // fn serialize_identifier(&self) -> Cow<'static, str> {
-    //     "".into()
-    // }
-    // #[cfg(feature = "serde1")]
-    // fn deserialize(
-    //     &self,
-    // ) -> Option<
-    //     fn(
-    //         GlobalDeConfig,
-    //         &HashMap<EntityId, EntityId>,
-    //         &mut dyn crate::erased_serde::Deserializer<'_>,
-    //     ) -> Result<Storage, crate::erased_serde::Error>,
-    // > {
-    //     None
-    // }
+    /// Returns the storage's identifier if it was setup for (de)serialization.
+    #[cfg(feature = "serde1")]
+    fn serde_identifier(&self) -> Option<&Identifier> {
+        None
+    }
+    #[cfg(feature = "serde1")]
+    fn serialize(
+        &self,
+        _: &mut dyn crate::erased_serde::Serializer,
+    ) -> crate::erased_serde::Result<crate::erased_serde::Ok> {
+        Err(serde::ser::Error::custom(
+            "This storage isn't serializable.",
+        ))
+    }
+    /// Deserializes components into this storage, `entities_map` holds the ids they have once loaded.
+    #[cfg(feature = "serde1")]
+    fn deserialize(
+        &mut self,
+        _entities_map: &EntitiesMap,
+        _: &mut dyn crate::erased_serde::Deserializer<'_>,
+    ) -> crate::erased_serde::Result<()> {
+        Err(serde::de::Error::custom(
+            "This storage isn't deserializable.",
+        ))
+    }
+    /// Deserializes a single component and adds it to `template`.
+    #[cfg(feature = "serde1")]
+    fn deserialize_template_component(
+        &self,
+        _template: &mut Template,
+        _: &mut dyn crate::erased_serde::Deserializer<'_>,
+    ) -> crate::erased_serde::Result<()> {
+        Err(serde::de::Error::custom(
+            "This storage can't be part of a template.",
+        ))
+    }
+}
+
+#[cfg(feature = "serde1")]
+pub(crate) struct TemplateComponentDeserializer<'a> {
+    pub(crate) unknown_storage: &'a dyn UnknownStorage,
+    pub(crate) template: &'a mut Template,
+}
+
+#[cfg(feature = "serde1")]
+impl<'de> serde::de::DeserializeSeed<'de> for TemplateComponentDeserializer<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.unknown_storage
+            .deserialize_template_component(
+                self.template,
+                &mut <dyn crate::erased_serde::Deserializer<'de>>::erase(deserializer),
+            )
+            .map_err(serde::de::Error::custom)
+    }
 }
 
-// #[cfg(feature = "serde1")]
-// pub(crate) fn deserialize_ptr(
-//     func: fn(
-//         GlobalDeConfig,
-//         &mut dyn crate::erased_serde::Deserializer<'_>,
-//     ) -> Result<Storage, crate::erased_serde::Error>,
-// ) -> usize {
-//     let anchor: *const () = &ANCHOR;
-//     anchor as usize - func as usize
-// }
+#[cfg(feature = "serde1")]
+pub(crate) struct StorageSerializer<'a> {
+    pub(crate) unknown_storage: &'a dyn UnknownStorage,
+}
 
-// #[cfg(feature = "serde1")]
-// pub(crate) unsafe fn deserialize_fn(
-//     ptr: usize,
-// ) -> fn(
-//     GlobalDeConfig,
-//     &mut dyn crate::erased_serde::Deserializer<'_>,
-// ) -> Result<Storage, crate::erased_serde::Error> {
-//     let anchor: *const () = &ANCHOR;
-//     let deserialize_ptr = anchor as usize - ptr;
-//     let deserialize: *const () = deserialize_ptr as *const _;
-//     core::mem::transmute(deserialize)
-// }
+#[cfg(feature = "serde1")]
+impl serde::Serialize for StorageSerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.unknown_storage
+            .serialize(&mut <dyn crate::erased_serde::Serializer>::erase(
+                serializer,
+            ))
+            .map(crate::erased_serde::Ok::take)
+            .map_err(serde::ser::Error::custom)
+    }
+}
 
-// #[cfg(feature = "serde1")]
-// pub(crate) struct StorageSerializer<'a> {
-//     pub(crate) unknown_storage: &'a dyn UnknownStorage,
-//     pub(crate) ser_config: GlobalSerConfig,
-// }
+#[cfg(feature = "serde1")]
+pub(crate) struct StorageDeserializer<'a> {
+    pub(crate) unknown_storage: &'a mut dyn UnknownStorage,
+    pub(crate) entities_map: &'a EntitiesMap,
+}
+
+#[cfg(feature = "serde1")]
+impl<'de> serde::de::DeserializeSeed<'de> for StorageDeserializer<'_> {
+    type Value = ();
 
-// #[cfg(feature = "serde1")]
-// impl serde::Serialize for StorageSerializer<'_> {
-//     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-//     where
-//         S: serde::Serializer,
-//     {
-//         self.unknown_storage
-//             .serialize(
-//                 self.ser_config,
-//                 &mut crate::erased_serde::Serializer::erase(serializer),
-//             )
-//             .map(crate::erased_serde::Ok::take)
-//             .map_err(serde::ser::Error::custom)
-//     }
-// }
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.unknown_storage
+            .deserialize(
+                self.entities_map,
+                &mut <dyn crate::erased_serde::Deserializer<'de>>::erase(deserializer),
+            )
+            .map_err(serde::de::Error::custom)
+    }
+}
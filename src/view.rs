@@ -1,5 +1,7 @@
 use crate::atomic_refcell::{Ref, RefMut, SharedBorrow};
 use crate::pack::update::{Inserted, InsertedOrModified, Modified};
+#[cfg(feature = "serde1")]
+use crate::serde_setup::{SerConfig, VisitEntityIds};
 use crate::sparse_set::SparseSet;
 use crate::storage::{AllStorages, Entities, Unique};
 use core::ops::{Deref, DerefMut};
@@ -253,6 +255,28 @@ impl<T> UniqueViewMut<'_, T> {
     }
 }
 
+#[cfg(feature = "serde1")]
+impl<T: serde::Serialize + for<'de> serde::Deserialize<'de> + 'static> UniqueViewMut<'_, T> {
+    /// Setup serialization for this unique storage.  
+    /// Needs to be called for a unique storage to be serialized or deserialized with the `World`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn setup_serde(unique: &mut Self, ser_config: SerConfig) {
+        unique.unique.setup_serde(ser_config);
+    }
+}
+
+#[cfg(feature = "serde1")]
+impl<T: serde::Serialize + for<'de> serde::Deserialize<'de> + VisitEntityIds + 'static>
+    UniqueViewMut<'_, T>
+{
+    /// Setup serialization for this unique storage.  
+    /// When loaded into a `World` with entities, the `EntityId`s inside the component are also updated.
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn setup_serde_remap(unique: &mut Self, ser_config: SerConfig) {
+        unique.unique.setup_serde_remap(ser_config);
+    }
+}
+
 impl<T> Deref for UniqueViewMut<'_, T> {
     type Target = T;
 
@@ -1,24 +1,24 @@
 pub mod scheduler;
 
-pub use scheduler::{Workload, WorkloadBuilder};
+pub use scheduler::{FixedTimestep, FixedTimesteps, Label, Workload, WorkloadBuilder};
 
-pub(crate) use scheduler::TypeInfo;
+pub(crate) use scheduler::{SystemContext, TypeInfo};
 
 use crate::atomic_refcell::{AtomicRefCell, Ref, RefMut};
 use crate::borrow::Borrow;
+use crate::dynamic::{ComponentDescriptor, DynamicView, DynamicViewMut};
 use crate::error;
 use crate::reserve::BulkEntityIter;
+#[cfg(feature = "serde1")]
+use crate::serde_setup::{EntitiesMap, GlobalDeConfig, GlobalSerConfig};
 use crate::sparse_set::{AddComponent, BulkAddEntity, DeleteComponent, Remove};
 use crate::storage::{AllStorages, DeleteAny, EntityId, Retain, StorageId};
+use crate::template::Template;
 use crate::unknown_storage::UnknownStorage;
+use crate::view::UniqueViewMut;
 use alloc::borrow::Cow;
+use alloc::vec::Vec;
 use scheduler::{Batches, Scheduler};
-// #[cfg(feature = "serde1")]
-// use crate::atomic_refcell::RefMut;
-// #[cfg(feature = "serde1")]
-// use crate::serde_setup::{ExistingEntities, GlobalDeConfig, GlobalSerConfig, WithShared};
-// #[cfg(feature = "serde1")]
-// use crate::storage::{Storage, StorageId};
 
 /// `World` contains all data this library will manipulate.
 pub struct World {
@@ -1224,7 +1224,7 @@ let i = world.run(sys1);
 
         let batches = scheduler.workload(name.as_ref())?;
 
-        self.try_run_workload_index(&scheduler, batches)
+        self.try_run_workload_index(&scheduler, name.as_ref(), batches)
     }
     /// Runs the `name` workload.  
     /// Unwraps error.
@@ -1252,23 +1252,99 @@ let i = world.run(sys1);
     fn try_run_workload_index(
         &self,
         scheduler: &Scheduler,
+        name: &str,
         batches: &Batches,
     ) -> Result<(), error::RunWorkload> {
+        let workload_id = scheduler.workload_id(name);
+
+        // events sent two runs ago can't be read anymore
+        if scheduler.has_system_context(workload_id) {
+            self.all_storages
+                .try_borrow()
+                .map_err(error::RunWorkload::UpdateEvents)?
+                .events_runs
+                .start(workload_id);
+        }
+
+        self.try_run_batches(scheduler, name, workload_id, batches, &[], true)
+    }
+    /// Runs the `name` workload as part of another workload.  
+    /// Commands are applied by the outer workload, other systems might still be running.
+    pub(crate) fn try_run_nested_workload(&self, name: &str) -> Result<(), error::RunWorkload> {
+        let scheduler = self
+            .scheduler
+            .try_borrow()
+            .map_err(|_| error::RunWorkload::Scheduler)?;
+
+        let batches = scheduler.workload(name)?;
+
+        // nested systems belong to the outer workload
+        #[cfg(feature = "std")]
+        let (workload_id, key) = if scheduler.has_system_context(scheduler.workload_id(name)) {
+            (
+                scheduler::current_system::current_workload()
+                    .unwrap_or_else(|| scheduler.workload_id(name)),
+                scheduler::current_system::current_key(),
+            )
+        } else {
+            (scheduler.workload_id(name), alloc::vec::Vec::new())
+        };
+        #[cfg(not(feature = "std"))]
+        let (workload_id, key) = (scheduler.workload_id(name), alloc::vec::Vec::new());
+
+        self.try_run_batches(&scheduler, name, workload_id, batches, &key, false)
+    }
+    /// `workload_id` is the id of the top-level workload.  
+    /// `key` is the position of the system running this workload when it's nested in another workload.
+    #[allow(clippy::too_many_arguments)]
+    fn try_run_batches(
+        &self,
+        scheduler: &Scheduler,
+        name: &str,
+        workload_id: usize,
+        batches: &Batches,
+        key: &[usize],
+        apply_commands: bool,
+    ) -> Result<(), error::RunWorkload> {
+        for &index in &batches.run_if {
+            let should_run = (scheduler.conditions[index])(self)
+                .map_err(|err| error::RunWorkload::Run((scheduler.condition_names[index], err)))?;
+
+            if !should_run {
+                return Ok(());
+            }
+        }
+
         #[cfg(feature = "parallel")]
         {
-            for batch in &batches.parallel {
+            for (batch_index, batch) in batches.parallel.iter().enumerate() {
+                #[cfg(feature = "std")]
+                self.profile_batch(scheduler, name, batch_index);
+
                 if batch.len() == 1 {
-                    scheduler.systems[batch[0]](self).map_err(|err| {
-                        error::RunWorkload::Run((scheduler.system_names[batch[0]], err))
-                    })?;
+                    self.run_system(scheduler, name, workload_id, key, batch_index, 0, batch[0])?;
                 } else {
                     use rayon::prelude::*;
 
-                    batch.into_par_iter().try_for_each(|&index| {
-                        (scheduler.systems[index])(self).map_err(|err| {
-                            error::RunWorkload::Run((scheduler.system_names[index], err))
-                        })
-                    })?;
+                    batch
+                        .into_par_iter()
+                        .enumerate()
+                        .try_for_each(|(position, &index)| {
+                            self.run_system(
+                                scheduler,
+                                name,
+                                workload_id,
+                                key,
+                                batch_index,
+                                position,
+                                index,
+                            )
+                        })?;
+                }
+
+                if apply_commands {
+                    self.try_apply_commands()
+                        .map_err(error::RunWorkload::ApplyCommands)?;
                 }
             }
 
@@ -1276,10 +1352,86 @@ let i = world.run(sys1);
         }
         #[cfg(not(feature = "parallel"))]
         {
-            batches.sequential.iter().try_for_each(|&index| {
-                (scheduler.systems[index])(self)
-                    .map_err(|err| error::RunWorkload::Run((scheduler.system_names[index], err)))
-            })
+            batches
+                .sequential
+                .iter()
+                .enumerate()
+                .try_for_each(|(position, &index)| {
+                    #[cfg(feature = "std")]
+                    self.profile_batch(scheduler, name, position);
+
+                    self.run_system(scheduler, name, workload_id, key, position, 0, index)?;
+
+                    if apply_commands {
+                        self.try_apply_commands()
+                            .map_err(error::RunWorkload::ApplyCommands)?;
+                    }
+
+                    Ok(())
+                })
+        }
+    }
+    /// Notifies the profiler, if there is one, that a batch is starting.
+    #[cfg(feature = "std")]
+    fn profile_batch(&self, scheduler: &Scheduler, workload: &str, batch: usize) {
+        if let Some(profiler) = &scheduler.profiler {
+            profiler.batch(&scheduler::profiling::BatchEvent {
+                workload,
+                batch,
+                thread: std::thread::current().id(),
+            });
+        }
+    }
+    /// Runs the system at `index`, calling the profiler around it if there is one.  
+    /// `position` is the position of the system in its batch, commands are applied following this order.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    #[allow(clippy::too_many_arguments)]
+    fn run_system(
+        &self,
+        scheduler: &Scheduler,
+        workload: &str,
+        workload_id: usize,
+        key: &[usize],
+        batch: usize,
+        position: usize,
+        index: usize,
+    ) -> Result<(), error::RunWorkload> {
+        #[cfg(feature = "std")]
+        {
+            let run = || {
+                if let Some(profiler) = &scheduler.profiler {
+                    let event = scheduler::profiling::SystemEvent {
+                        workload,
+                        system: scheduler.system_names[index],
+                        batch,
+                        thread: std::thread::current().id(),
+                    };
+
+                    profiler.begin(&event);
+                    let result = (scheduler.systems[index])(self);
+                    profiler.end(&event);
+
+                    result
+                } else {
+                    (scheduler.systems[index])(self)
+                }
+            };
+
+            if scheduler.has_system_context(workload_id) {
+                scheduler::current_system::with_current_system(
+                    workload_id,
+                    key,
+                    batch,
+                    position,
+                    run,
+                )
+            } else {
+                run()
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            (scheduler.systems[index])(self)
         }
     }
     /// Run the default workload if there is one.
@@ -1301,7 +1453,11 @@ let i = world.run(sys1);
             .map_err(|_| error::RunWorkload::Scheduler)?;
 
         if !scheduler.is_empty() {
-            self.try_run_workload_index(&scheduler, scheduler.default_workload())?
+            self.try_run_workload_index(
+                &scheduler,
+                &scheduler.default,
+                scheduler.default_workload(),
+            )?
         }
         Ok(())
     }
@@ -1327,6 +1483,398 @@ let i = world.run(sys1);
             Err(err) => panic!("{:?}", err),
         }
     }
+    /// Runs `fixed_workload` as many times as `delta` allows with the [`FixedTimestep`] unique storage's step, then runs `workload` once.  
+    /// Time that doesn't make a full step is kept for the next call and
+    /// [`FixedTimestep::alpha`] is updated before `workload` runs to let its systems interpolate.  
+    /// [`FixedTimestep::with_max_steps`] limits the number of steps, the time that would need more is dropped.
+    ///
+    /// `delta` is the time elapsed since the last call, usually measured by the caller's game loop.
+    ///
+    /// ### Example
+    /// ```
+    /// use core::time::Duration;
+    /// use shipyard::{system, FixedTimestep, UniqueView, UniqueViewMut, Workload, World};
+    ///
+    /// fn physics(mut steps: UniqueViewMut<u32>) {
+    ///     *steps += 1;
+    /// }
+    ///
+    /// fn render(fixed_timestep: UniqueView<FixedTimestep>) {
+    ///     assert_eq!(fixed_timestep.alpha(), 0.5);
+    /// }
+    ///
+    /// let world = World::new();
+    /// world.add_unique(0u32);
+    /// world.add_unique(FixedTimestep::new(Duration::from_millis(500)));
+    ///
+    /// Workload::builder("Physics")
+    ///     .with_system(system!(physics))
+    ///     .add_to_world(&world)
+    ///     .unwrap();
+    /// Workload::builder("Render")
+    ///     .with_system(system!(render))
+    ///     .add_to_world(&world)
+    ///     .unwrap();
+    ///
+    /// world.run_fixed_timestep("Physics", "Render", Duration::from_millis(1250));
+    ///
+    /// world.run(|steps: UniqueView<u32>| assert_eq!(*steps, 2));
+    /// ```
+    ///
+    /// ### Borrows
+    ///
+    /// - Scheduler (shared)
+    /// - FixedTimestep (exclusive) between fixed steps
+    /// - Systems' borrow as they are executed
+    ///
+    /// ### Errors
+    ///
+    /// - Scheduler borrow failed.
+    /// - One of the workloads did not exist.
+    /// - FixedTimestep borrow failed.
+    /// - Storage borrow failed.
+    /// - User error returned by system.
+    ///
+    /// [`FixedTimestep`]: struct.FixedTimestep.html
+    /// [`FixedTimestep::alpha`]: struct.FixedTimestep.html#method.alpha
+    /// [`FixedTimestep::with_max_steps`]: struct.FixedTimestep.html#method.with_max_steps
+    pub fn try_run_fixed_timestep(
+        &self,
+        fixed_workload: impl AsRef<str>,
+        workload: impl AsRef<str>,
+        delta: core::time::Duration,
+    ) -> Result<(), error::RunWorkload> {
+        let scheduler = self
+            .scheduler
+            .try_borrow()
+            .map_err(|_| error::RunWorkload::Scheduler)?;
+
+        let fixed_batches = scheduler.workload(fixed_workload.as_ref())?;
+        let batches = scheduler.workload(workload.as_ref())?;
+
+        self.try_borrow::<UniqueViewMut<'_, FixedTimestep>>()
+            .map_err(error::RunWorkload::FixedTimestep)?
+            .accumulate(delta);
+
+        // the storage isn't borrowed while the fixed workload runs, its systems can borrow it
+        while self
+            .try_borrow::<UniqueViewMut<'_, FixedTimestep>>()
+            .map_err(error::RunWorkload::FixedTimestep)?
+            .consume_step()
+        {
+            self.try_run_workload_index(&scheduler, fixed_workload.as_ref(), fixed_batches)?;
+        }
+
+        self.try_run_workload_index(&scheduler, workload.as_ref(), batches)
+    }
+    /// Runs `fixed_workload` as many times as `delta` allows with the [`FixedTimestep`] unique storage's step, then runs `workload` once.  
+    /// Time that doesn't make a full step is kept for the next call and
+    /// [`FixedTimestep::alpha`] is updated before `workload` runs to let its systems interpolate.  
+    /// [`FixedTimestep::with_max_steps`] limits the number of steps, the time that would need more is dropped.  
+    /// Unwraps errors.
+    ///
+    /// `delta` is the time elapsed since the last call, usually measured by the caller's game loop.
+    ///
+    /// ### Borrows
+    ///
+    /// - Scheduler (shared)
+    /// - FixedTimestep (exclusive) between fixed steps
+    /// - Systems' borrow as they are executed
+    ///
+    /// ### Errors
+    ///
+    /// - Scheduler borrow failed.
+    /// - One of the workloads did not exist.
+    /// - FixedTimestep borrow failed.
+    /// - Storage borrow failed.
+    /// - User error returned by system.
+    ///
+    /// [`FixedTimestep`]: struct.FixedTimestep.html
+    /// [`FixedTimestep::alpha`]: struct.FixedTimestep.html#method.alpha
+    /// [`FixedTimestep::with_max_steps`]: struct.FixedTimestep.html#method.with_max_steps
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn run_fixed_timestep(
+        &self,
+        fixed_workload: impl AsRef<str>,
+        workload: impl AsRef<str>,
+        delta: core::time::Duration,
+    ) {
+        match self.try_run_fixed_timestep(fixed_workload, workload, delta) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Runs each fixed workload of the [`FixedTimesteps`] unique storage as many times as `delta` allows with its own step,
+    /// then runs `workload` once.  
+    /// Fixed workloads run in the order they were added to [`FixedTimesteps`], each one catching up before the next.  
+    /// Every [`FixedTimestep`] keeps its own accumulator and alpha, they behave the same as with [`run_fixed_timestep`].
+    ///
+    /// `delta` is the time elapsed since the last call, usually measured by the caller's game loop.
+    ///
+    /// ### Example
+    /// ```
+    /// use core::time::Duration;
+    /// use shipyard::{system, FixedTimestep, FixedTimesteps, UniqueView, UniqueViewMut, Workload, World};
+    ///
+    /// fn physics(mut steps: UniqueViewMut<u32>) {
+    ///     *steps += 1;
+    /// }
+    ///
+    /// fn ai(mut steps: UniqueViewMut<u64>) {
+    ///     *steps += 1;
+    /// }
+    ///
+    /// fn render(fixed_timesteps: UniqueView<FixedTimesteps>) {
+    ///     assert_eq!(fixed_timesteps.get("Physics").unwrap().alpha(), 0.5);
+    ///     assert_eq!(fixed_timesteps.get("AI").unwrap().alpha(), 0.25);
+    /// }
+    ///
+    /// let world = World::new();
+    /// world.add_unique(0u32);
+    /// world.add_unique(0u64);
+    /// world.add_unique(
+    ///     FixedTimesteps::new()
+    ///         .with_workload("Physics", FixedTimestep::new(Duration::from_millis(500)))
+    ///         .with_workload("AI", FixedTimestep::new(Duration::from_millis(1000))),
+    /// );
+    ///
+    /// Workload::builder("Physics")
+    ///     .with_system(system!(physics))
+    ///     .add_to_world(&world)
+    ///     .unwrap();
+    /// Workload::builder("AI")
+    ///     .with_system(system!(ai))
+    ///     .add_to_world(&world)
+    ///     .unwrap();
+    /// Workload::builder("Render")
+    ///     .with_system(system!(render))
+    ///     .add_to_world(&world)
+    ///     .unwrap();
+    ///
+    /// world.run_multi_rate("Render", Duration::from_millis(1250));
+    ///
+    /// world.run(|physics_steps: UniqueView<u32>, ai_steps: UniqueView<u64>| {
+    ///     assert_eq!(*physics_steps, 2);
+    ///     assert_eq!(*ai_steps, 1);
+    /// });
+    /// ```
+    ///
+    /// ### Borrows
+    ///
+    /// - Scheduler (shared)
+    /// - FixedTimesteps (exclusive) between fixed steps
+    /// - Systems' borrow as they are executed
+    ///
+    /// ### Errors
+    ///
+    /// - Scheduler borrow failed.
+    /// - One of the workloads did not exist.
+    /// - FixedTimesteps borrow failed.
+    /// - Storage borrow failed.
+    /// - User error returned by system.
+    ///
+    /// [`FixedTimesteps`]: struct.FixedTimesteps.html
+    /// [`FixedTimestep`]: struct.FixedTimestep.html
+    /// [`run_fixed_timestep`]: struct.World.html#method.run_fixed_timestep
+    pub fn try_run_multi_rate(
+        &self,
+        workload: impl AsRef<str>,
+        delta: core::time::Duration,
+    ) -> Result<(), error::RunWorkload> {
+        let scheduler = self
+            .scheduler
+            .try_borrow()
+            .map_err(|_| error::RunWorkload::Scheduler)?;
+
+        let batches = scheduler.workload(workload.as_ref())?;
+
+        let fixed_workloads = {
+            let mut fixed_timesteps = self
+                .try_borrow::<UniqueViewMut<'_, FixedTimesteps>>()
+                .map_err(error::RunWorkload::FixedTimestep)?;
+
+            let mut fixed_workloads = Vec::with_capacity(fixed_timesteps.timesteps.len());
+            for (name, fixed_timestep) in &mut fixed_timesteps.timesteps {
+                fixed_workloads.push((name.clone(), scheduler.workload(name)?));
+                fixed_timestep.accumulate(delta);
+            }
+
+            fixed_workloads
+        };
+
+        for (index, (name, fixed_batches)) in fixed_workloads.iter().enumerate() {
+            // the storage isn't borrowed while the fixed workload runs, its systems can borrow it
+            while self
+                .try_borrow::<UniqueViewMut<'_, FixedTimesteps>>()
+                .map_err(error::RunWorkload::FixedTimestep)?
+                .timesteps[index]
+                .1
+                .consume_step()
+            {
+                self.try_run_workload_index(&scheduler, name, fixed_batches)?;
+            }
+        }
+
+        self.try_run_workload_index(&scheduler, workload.as_ref(), batches)
+    }
+    /// Runs each fixed workload of the [`FixedTimesteps`] unique storage as many times as `delta` allows with its own step,
+    /// then runs `workload` once.  
+    /// Fixed workloads run in the order they were added to [`FixedTimesteps`], each one catching up before the next.  
+    /// Every [`FixedTimestep`] keeps its own accumulator and alpha, they behave the same as with [`run_fixed_timestep`].  
+    /// Unwraps errors.
+    ///
+    /// `delta` is the time elapsed since the last call, usually measured by the caller's game loop.
+    ///
+    /// ### Borrows
+    ///
+    /// - Scheduler (shared)
+    /// - FixedTimesteps (exclusive) between fixed steps
+    /// - Systems' borrow as they are executed
+    ///
+    /// ### Errors
+    ///
+    /// - Scheduler borrow failed.
+    /// - One of the workloads did not exist.
+    /// - FixedTimesteps borrow failed.
+    /// - Storage borrow failed.
+    /// - User error returned by system.
+    ///
+    /// [`FixedTimesteps`]: struct.FixedTimesteps.html
+    /// [`FixedTimestep`]: struct.FixedTimestep.html
+    /// [`run_fixed_timestep`]: struct.World.html#method.run_fixed_timestep
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn run_multi_rate(&self, workload: impl AsRef<str>, delta: core::time::Duration) {
+        match self.try_run_multi_rate(workload, delta) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Installs `profiler`, it will be called before and after each system when running workloads.  
+    /// Replaces the previous profiler if there was one.
+    ///
+    /// ### Borrows
+    ///
+    /// - Scheduler (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - Scheduler borrow failed.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn try_set_profiler(
+        &self,
+        profiler: alloc::sync::Arc<dyn scheduler::profiling::Profiler>,
+    ) -> Result<(), error::Borrow> {
+        self.scheduler.try_borrow_mut()?.profiler = Some(profiler);
+
+        Ok(())
+    }
+    /// Installs `profiler`, it will be called before and after each system when running workloads.  
+    /// Replaces the previous profiler if there was one.  
+    /// Unwraps errors.
+    ///
+    /// ### Borrows
+    ///
+    /// - Scheduler (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - Scheduler borrow failed.
+    #[cfg(all(feature = "std", feature = "panic"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "panic"))))]
+    #[track_caller]
+    pub fn set_profiler(&self, profiler: alloc::sync::Arc<dyn scheduler::profiling::Profiler>) {
+        match self.try_set_profiler(profiler) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Removes the profiler and returns it if there was one.
+    ///
+    /// ### Borrows
+    ///
+    /// - Scheduler (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - Scheduler borrow failed.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn try_remove_profiler(
+        &self,
+    ) -> Result<Option<alloc::sync::Arc<dyn scheduler::profiling::Profiler>>, error::Borrow> {
+        Ok(self.scheduler.try_borrow_mut()?.profiler.take())
+    }
+    /// Removes the profiler and returns it if there was one.  
+    /// Unwraps errors.
+    ///
+    /// ### Borrows
+    ///
+    /// - Scheduler (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - Scheduler borrow failed.
+    #[cfg(all(feature = "std", feature = "panic"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "panic"))))]
+    #[track_caller]
+    pub fn remove_profiler(&self) -> Option<alloc::sync::Arc<dyn scheduler::profiling::Profiler>> {
+        match self.try_remove_profiler() {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Applies the commands recorded by [`Commands`] views and not applied yet.  
+    /// Then sends a [`GenerationExhausted`] event for each entity deleted with an exhausted generation and not reported yet.  
+    /// Workloads call it at the end of each batch.
+    ///
+    /// ### Borrows
+    ///
+    /// - AllStorages (exclusive) if there are commands to apply or events to send
+    ///
+    /// ### Errors
+    ///
+    /// - AllStorages borrow failed.
+    ///
+    /// [`Commands`]: struct.Commands.html
+    /// [`GenerationExhausted`]: struct.GenerationExhausted.html
+    pub fn try_apply_commands(&self) -> Result<(), error::Borrow> {
+        if !self.all_storages.try_borrow()?.has_pending_commands() {
+            return Ok(());
+        }
+
+        self.all_storages.try_borrow_mut()?.apply_commands();
+
+        Ok(())
+    }
+    /// Applies the commands recorded by [`Commands`] views and not applied yet.  
+    /// Then sends a [`GenerationExhausted`] event for each entity deleted with an exhausted generation and not reported yet.  
+    /// Workloads call it at the end of each batch.  
+    /// Unwraps errors.
+    ///
+    /// ### Borrows
+    ///
+    /// - AllStorages (exclusive) if there are commands to apply or events to send
+    ///
+    /// ### Errors
+    ///
+    /// - AllStorages borrow failed.
+    ///
+    /// [`Commands`]: struct.Commands.html
+    /// [`GenerationExhausted`]: struct.GenerationExhausted.html
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn apply_commands(&self) {
+        match self.try_apply_commands() {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
     /// Returns a `Ref<&AllStorages>`, used to implement custom storages.   
     /// To borrow `AllStorages` you should use `borrow` or `run` with `AllStoragesViewMut`.
     ///
@@ -1378,109 +1926,345 @@ let i = world.run(sys1);
             Err(err) => panic!("{:?}", err),
         }
     }
-    // /// Serializes the [World] the way `ser_config` defines it.
-    // ///
-    // /// ### Borrows
-    // ///
-    // /// - [AllStorages] (exclusively)
-    // ///
-    // /// ### Errors
-    // ///
-    // /// - [AllStorages] borrow failed.
-    // /// - Serialization error.
-    // /// - Config not implemented. (temporary)
-    // ///
-    // /// [AllStorages]: struct.AllStorages.html
-    // /// [World]: struct.World.html
-    // #[cfg(feature = "serde1")]
-    // #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
-    // pub fn serialize<S>(
-    //     &self,
-    //     ser_config: GlobalSerConfig,
-    //     serializer: S,
-    // ) -> Result<S::Ok, S::Error>
-    // where
-    //     S: serde::Serializer,
-    //     <S as serde::Serializer>::Ok: 'static,
-    // {
-    //     if ser_config.same_binary == true
-    //         && ser_config.with_entities == true
-    //         && ser_config.with_shared == WithShared::PerStorage
-    //     {
-    //         serializer.serialize_newtype_struct(
-    //             "World",
-    //             &crate::storage::AllStoragesSerializer {
-    //                 all_storages: self
-    //                     .all_storages
-    //                     .try_borrow_mut()
-    //                     .map_err(|err| serde::ser::Error::custom(err))?,
-    //                 ser_config,
-    //             },
-    //         )
-    //     } else {
-    //         Err(serde::ser::Error::custom(
-    //             "ser_config other than default isn't implemented yet",
-    //         ))
-    //     }
-    // }
-    // #[cfg(feature = "serde1")]
-    // pub fn deserialize<'de, D>(
-    //     &self,
-    //     de_config: GlobalDeConfig,
-    //     deserializer: D,
-    // ) -> Result<(), D::Error>
-    // where
-    //     D: serde::Deserializer<'de>,
-    // {
-    //     if de_config.existing_entities == ExistingEntities::AsNew
-    //         && de_config.with_shared == WithShared::PerStorage
-    //     {
-    //         Ok(())
-    //     } else {
-    //         Err(serde::de::Error::custom(
-    //             "de_config other than default isn't implemented yet",
-    //         ))
-    //     }
-    // }
-    // /// Creates a new [World] from a deserializer the way `de_config` defines it.
-    // ///
-    // /// ### Errors
-    // ///
-    // /// - Deserialization error.
-    // /// - Config not implemented. (temporary)
-    // ///
-    // /// [World]: struct.World.html
-    // #[cfg(feature = "serde1")]
-    // #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
-    // pub fn new_deserialized<'de, D>(
-    //     de_config: GlobalDeConfig,
-    //     deserializer: D,
-    // ) -> Result<Self, D::Error>
-    // where
-    //     D: serde::Deserializer<'de>,
-    // {
-    //     if de_config.existing_entities == ExistingEntities::AsNew
-    //         && de_config.with_shared == WithShared::PerStorage
-    //     {
-    //         let world = World::new();
-    //         deserializer.deserialize_struct(
-    //             "World",
-    //             &["metadata", "storages"],
-    //             WorldVisitor {
-    //                 all_storages: world
-    //                     .all_storages
-    //                     .try_borrow_mut()
-    //                     .map_err(serde::de::Error::custom)?,
-    //                 de_config,
-    //             },
-    //         )?;
-    //         Ok(world)
-    //     } else {
-    //         Err(serde::de::Error::custom(
-    //             "de_config other than default isn't implemented yet",
-    //         ))
-    //     }
-    // }
+    /// Registers a storage of dynamic components described by `descriptor` under `StorageId::Custom(id)`.  
+    /// Does nothing if a storage is already registered with this id.  
+    /// Moving or merging entities to another `World` panics if `id` is registered there with a different layout.
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (shared)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    pub fn try_register_dynamic_component(
+        &self,
+        id: u64,
+        descriptor: ComponentDescriptor,
+    ) -> Result<(), error::Borrow> {
+        self.all_storages
+            .try_borrow()?
+            .register_dynamic_component(id, descriptor);
+
+        Ok(())
+    }
+    /// Registers a storage of dynamic components described by `descriptor` under `StorageId::Custom(id)`.  
+    /// Does nothing if a storage is already registered with this id.  
+    /// Moving or merging entities to another `World` panics if `id` is registered there with a different layout.  
+    /// Unwraps errors.
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (shared)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn register_dynamic_component(&self, id: u64, descriptor: ComponentDescriptor) {
+        match self.try_register_dynamic_component(id, descriptor) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Borrows the dynamic storage registered under `StorageId::Custom(id)`.  
+    /// Systems borrowing dynamic storages declare it with [`WorkloadBuilder::with_dynamic_borrow`].
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (shared)
+    /// - The dynamic storage (shared)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    /// - No storage was registered with this id.
+    /// - The storage is already borrowed exclusively.
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    /// [`WorkloadBuilder::with_dynamic_borrow`]: struct.WorkloadBuilder.html#method.with_dynamic_borrow
+    pub fn try_borrow_dynamic(&self, id: u64) -> Result<DynamicView<'_>, error::GetStorage> {
+        let (all_storages, all_borrow) = unsafe {
+            Ref::destructure(
+                self.all_storages
+                    .try_borrow()
+                    .map_err(error::GetStorage::AllStoragesBorrow)?,
+            )
+        };
+
+        Ok(DynamicView {
+            storage: all_storages.custom_storage_by_id(StorageId::Custom(id))?,
+            _all_borrow: Some(all_borrow),
+        })
+    }
+    /// Borrows the dynamic storage registered under `StorageId::Custom(id)`.  
+    /// Unwraps errors.
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (shared)
+    /// - The dynamic storage (shared)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    /// - No storage was registered with this id.
+    /// - The storage is already borrowed exclusively.
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn borrow_dynamic(&self, id: u64) -> DynamicView<'_> {
+        match self.try_borrow_dynamic(id) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Borrows exclusively the dynamic storage registered under `StorageId::Custom(id)`.  
+    /// Systems borrowing dynamic storages declare it with [`WorkloadBuilder::with_dynamic_borrow`].
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (shared)
+    /// - The dynamic storage (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    /// - No storage was registered with this id.
+    /// - The storage is already borrowed.
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    /// [`WorkloadBuilder::with_dynamic_borrow`]: struct.WorkloadBuilder.html#method.with_dynamic_borrow
+    pub fn try_borrow_dynamic_mut(&self, id: u64) -> Result<DynamicViewMut<'_>, error::GetStorage> {
+        let (all_storages, all_borrow) = unsafe {
+            Ref::destructure(
+                self.all_storages
+                    .try_borrow()
+                    .map_err(error::GetStorage::AllStoragesBorrow)?,
+            )
+        };
+
+        Ok(DynamicViewMut {
+            storage: all_storages.custom_storage_mut_by_id(StorageId::Custom(id))?,
+            _all_borrow: Some(all_borrow),
+        })
+    }
+    /// Borrows exclusively the dynamic storage registered under `StorageId::Custom(id)`.  
+    /// Unwraps errors.
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (shared)
+    /// - The dynamic storage (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    /// - No storage was registered with this id.
+    /// - The storage is already borrowed.
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn borrow_dynamic_mut(&self, id: u64) -> DynamicViewMut<'_> {
+        match self.try_borrow_dynamic_mut(id) {
+            Ok(r) => r,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Serializes the storages setup for serialization, the way `ser_config` defines it.  
+    /// Storages opt in with [ViewMut::setup_serde] or [UniqueViewMut::setup_serde].
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    /// - A storage is already borrowed.
+    /// - Two storages use the same identifier.
+    /// - Serialization error.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{GlobalSerConfig, SerConfig, ViewMut, World};
+    ///
+    /// let mut world = World::new();
+    ///
+    /// world.borrow::<ViewMut<u32>>().setup_serde(SerConfig::default());
+    /// world.add_entity((0u32,));
+    ///
+    /// let json = world
+    ///     .serialize(GlobalSerConfig::default(), serde_json::value::Serializer)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    /// [ViewMut::setup_serde]: struct.SparseSet.html#method.setup_serde
+    /// [UniqueViewMut::setup_serde]: struct.UniqueViewMut.html#method.setup_serde
+    #[cfg(feature = "serde1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn serialize<S>(
+        &self,
+        ser_config: GlobalSerConfig,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let all_storages = self
+            .all_storages
+            .try_borrow_mut()
+            .map_err(serde::ser::Error::custom)?;
+
+        serializer.serialize_newtype_struct(
+            "World",
+            &crate::storage::AllStoragesSerializer {
+                all_storages: &all_storages,
+                ser_config,
+            },
+        )
+    }
+    /// Loads a serialized [World] into this one the way `de_config` defines it.  
+    /// Storages have to be setup for serialization with the same identifiers they were serialized with.
+    ///
+    /// If this [World] never had any entity, entities keep the id they were serialized with.  
+    /// Otherwise, or when [GlobalDeConfig::remap_entities] is set, they're added alongside the existing ones with new ids.
+    /// Components of storages setup with `setup_serde_remap` get the `EntityId`s they store updated as well.
+    ///
+    /// Returns the mapping from the serialized ids to the new ones.
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    /// - No storage was setup with a serialized identifier.
+    /// - Entities have to be remapped but weren't serialized.
+    /// - Entities weren't serialized and this [World] already had entities.
+    /// - A component belongs to an entity that isn't alive in the serialized entities.
+    /// - Deserialization error.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{GlobalDeConfig, GlobalSerConfig, Get, SerConfig, View, ViewMut, World};
+    ///
+    /// let mut world = World::new();
+    /// world.borrow::<ViewMut<u32>>().setup_serde(SerConfig::default());
+    /// let entity = world.add_entity((0u32,));
+    ///
+    /// let json = world
+    ///     .serialize(GlobalSerConfig::default(), serde_json::value::Serializer)
+    ///     .unwrap();
+    ///
+    /// let new_world = World::new();
+    /// new_world.borrow::<ViewMut<u32>>().setup_serde(SerConfig::default());
+    /// new_world
+    ///     .deserialize(GlobalDeConfig::default(), json.clone())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(new_world.borrow::<View<u32>>().get(entity), Ok(&0));
+    ///
+    /// // loading a second time gives new ids
+    /// let entities_map = new_world
+    ///     .deserialize(GlobalDeConfig::default(), json)
+    ///     .unwrap();
+    /// let new_entity = entities_map.get(entity).unwrap();
+    ///
+    /// assert_ne!(new_entity, entity);
+    /// assert_eq!(new_world.borrow::<View<u32>>().get(new_entity), Ok(&0));
+    /// ```
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    /// [World]: struct.World.html
+    /// [GlobalDeConfig::remap_entities]: struct.GlobalDeConfig.html#structfield.remap_entities
+    #[cfg(feature = "serde1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn deserialize<'de, D>(
+        &self,
+        de_config: GlobalDeConfig,
+        deserializer: D,
+    ) -> Result<EntitiesMap, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut all_storages = self
+            .all_storages
+            .try_borrow_mut()
+            .map_err(serde::de::Error::custom)?;
+
+        deserializer.deserialize_newtype_struct(
+            "World",
+            WorldVisitor {
+                all_storages: &mut all_storages,
+                de_config,
+            },
+        )
+    }
+    /// Registers the templates serialized in `deserializer`, replacing the templates previously registered with the same names.
+    /// Templates are a map of names to templates, each template is a map of storage identifiers to components.
+    /// Storages have to be setup with [SparseSet::setup_serde_template].
+    ///
+    /// ### Borrows
+    ///
+    /// - [AllStorages] (exclusive)
+    ///
+    /// ### Errors
+    ///
+    /// - [AllStorages] borrow failed.
+    /// - No storage was setup with a serialized identifier.
+    /// - A storage wasn't setup to be part of templates.
+    /// - Deserialization error.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{Identifier, SerConfig, View, ViewMut, World};
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let mut u32s = world.borrow::<ViewMut<u32>>();
+    /// u32s.setup_serde(SerConfig {
+    ///     identifier: Some(Identifier::new("health")),
+    /// });
+    /// u32s.setup_serde_template();
+    /// drop(u32s);
+    ///
+    /// world
+    ///     .load_templates(serde_json::json!({
+    ///         "orc": { "health": 50 },
+    ///     }))
+    ///     .unwrap();
+    ///
+    /// let orc = world.instantiate("orc", ()).unwrap();
+    ///
+    /// assert_eq!(world.borrow::<View<u32>>()[orc], 50);
+    /// ```
+    ///
+    /// [AllStorages]: struct.AllStorages.html
+    /// [SparseSet::setup_serde_template]: struct.SparseSet.html#method.setup_serde_template
+    #[cfg(feature = "serde1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+    pub fn load_templates<'de, D>(&self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.all_storages
+            .try_borrow_mut()
+            .map_err(serde::de::Error::custom)?
+            .load_templates(deserializer)
+    }
 }
 
 impl World {
@@ -1519,6 +2303,79 @@ impl World {
     pub fn bulk_add_entity<T: BulkAddEntity + 'static>(&mut self, source: T) -> BulkEntityIter<'_> {
         self.all_storages.get_mut().bulk_add_entity(source)
     }
+    /// Registers `template` under `name`, replacing the template previously registered with this name.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{Template, World};
+    ///
+    /// let mut world = World::new();
+    ///
+    /// world.add_template("orc", Template::new().with(50u32));
+    /// ```
+    #[inline]
+    pub fn add_template(&mut self, name: impl Into<Cow<'static, str>>, template: Template) {
+        self.all_storages.get_mut().add_template(name, template);
+    }
+    /// Creates a new entity with the components of the template registered under `name` and returns its `EntityId`.
+    /// Components in `overrides` are added instead of the template's ones of the same type.
+    /// `overrides` must always be a tuple, even for a single component.
+    ///
+    /// ### Errors
+    ///
+    /// - No template is registered under `name`.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{Template, World};
+    ///
+    /// let mut world = World::new();
+    ///
+    /// world.add_template("orc", Template::new().with(50u32).with(1usize));
+    ///
+    /// let orc = world.instantiate("orc", ()).unwrap();
+    /// let boss = world.instantiate("orc", (500u32,)).unwrap();
+    /// ```
+    #[inline]
+    pub fn instantiate<C: AddComponent>(
+        &mut self,
+        name: &str,
+        overrides: C,
+    ) -> Result<EntityId, error::MissingTemplate> {
+        self.all_storages.get_mut().instantiate(name, overrides)
+    }
+    /// Creates multiple new entities with the components of the template registered under `name`
+    /// and returns an iterator yielding the new `EntityId`s.
+    /// There is one new entity for each item of `overrides`, its components are added instead of the template's ones of the same type.
+    /// `overrides` must always yield a tuple, even for a single component.
+    ///
+    /// ### Errors
+    ///
+    /// - No template is registered under `name`.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{Template, World};
+    ///
+    /// let mut world = World::new();
+    ///
+    /// world.add_template("orc", Template::new().with(50u32).with(1usize));
+    ///
+    /// let orcs = world.bulk_instantiate("orc", (0..10).map(|_| ())).unwrap();
+    /// ```
+    #[inline]
+    pub fn bulk_instantiate<T: BulkAddEntity + 'static>(
+        &mut self,
+        name: &str,
+        overrides: T,
+    ) -> Result<alloc::vec::IntoIter<EntityId>, error::MissingTemplate> {
+        self.all_storages
+            .get_mut()
+            .bulk_instantiate(name, overrides)
+    }
     /// Adds components to an existing entity.  
     /// If the entity already owned a component it will be replaced.  
     /// `component` must always be a tuple, even for a single component.
@@ -1586,7 +2443,8 @@ impl World {
     pub fn delete_component<C: DeleteComponent>(&mut self, entity: EntityId) {
         self.all_storages.get_mut().delete_component::<C>(entity)
     }
-    /// Deletes an entity with all its components. Returns true if the entity were alive.
+    /// Deletes an entity with all its components. Returns true if the entity were alive.  
+    /// If the entity is part of a [`hierarchy`], all its descendants are deleted too.
     ///
     /// ### Example
     ///
@@ -1599,6 +2457,8 @@ impl World {
     ///
     /// assert!(world.delete_entity(entity));
     /// ```
+    ///
+    /// [`hierarchy`]: hierarchy/index.html
     #[inline]
     pub fn delete_entity(&mut self, entity: EntityId) -> bool {
         self.all_storages.get_mut().delete_entity(entity)
@@ -1671,6 +2531,77 @@ impl World {
             .get_mut()
             .retain_storage(entity, excluded_storage);
     }
+    /// Moves `entities` and all their components to `other`.
+    /// Moved entities get a new `EntityId` in `other` and are deleted from this `World`.
+    /// Returns the moved entities as `(old_id, new_id)` pairs, entities that aren't alive are skipped.
+    ///
+    /// Storages missing from `other` are created, without any of this storage's setup like packs or callbacks.
+    /// `Unique` storages are left untouched.
+    /// Moved entities are removed from their [`hierarchy`] and lose their [`Relations`].
+    /// `EntityId`s stored inside components aren't updated.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{View, World};
+    ///
+    /// let mut staging = World::new();
+    /// let mut world = World::new();
+    ///
+    /// let entity = staging.add_entity((0u32, 1usize));
+    ///
+    /// let moved = staging.move_entities_to(&mut world, &[entity]);
+    /// let (_, new_entity) = moved[0];
+    ///
+    /// assert_eq!(world.borrow::<View<u32>>()[new_entity], 0);
+    /// ```
+    ///
+    /// [`hierarchy`]: hierarchy/index.html
+    /// [`Relations`]: struct.Relations.html
+    #[inline]
+    pub fn move_entities_to(
+        &mut self,
+        other: &mut World,
+        entities: &[EntityId],
+    ) -> Vec<(EntityId, EntityId)> {
+        self.all_storages
+            .get_mut()
+            .move_entities_to(other.all_storages.get_mut(), entities)
+    }
+    /// Moves all entities of `other` and their components to this `World`.
+    /// Merged entities get a new `EntityId`, components are appended in bulk for each storage.
+    /// Returns the merged entities as `(old_id, new_id)` pairs.
+    ///
+    /// Storages missing from this `World` are created, without any of `other`'s setup like packs or callbacks.
+    /// `Unique` storages of `other` are dropped.
+    /// [`hierarchy`] and [`Relations`] are merged with their ids updated,
+    /// `EntityId`s stored inside other components aren't.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use shipyard::{View, World};
+    ///
+    /// let mut world = World::new();
+    /// let mut chunk = World::new();
+    ///
+    /// world.add_entity((0u32,));
+    /// chunk.bulk_add_entity((1..10).map(|i| (i as u32,)));
+    ///
+    /// let merged = world.merge(chunk);
+    /// assert_eq!(merged.len(), 9);
+    ///
+    /// assert_eq!(world.borrow::<View<u32>>().len(), 10);
+    /// ```
+    ///
+    /// [`hierarchy`]: hierarchy/index.html
+    /// [`Relations`]: struct.Relations.html
+    #[inline]
+    pub fn merge(&mut self, mut other: World) -> Vec<(EntityId, EntityId)> {
+        self.all_storages
+            .get_mut()
+            .merge(other.all_storages.get_mut())
+    }
     /// Deletes all entities and components in the `World`.
     ///
     /// ### Example
@@ -1688,162 +2619,30 @@ impl World {
     }
 }
 
-// #[cfg(feature = "serde1")]
-// struct WorldVisitor<'a> {
-//     all_storages: RefMut<'a, AllStorages>,
-//     de_config: GlobalDeConfig,
-// }
-
-// #[cfg(feature = "serde1")]
-// impl<'de, 'a> serde::de::Visitor<'de> for WorldVisitor<'a> {
-//     type Value = ();
-
-//     fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-//         formatter.write_str("Could not format World")
-//     }
-
-//     fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-//     where
-//         A: serde::de::MapAccess<'de>,
-//     {
-//         match map.next_key()? {
-//             Some("ser_infos") => (),
-//             Some(field) => {
-//                 return Err(serde::de::Error::unknown_field(
-//                     field,
-//                     &["ser_infos", "metadata", "storages"],
-//                 ))
-//             }
-//             None => return Err(serde::de::Error::missing_field("ser_infos")),
-//         };
-
-//         let ser_infos: crate::serde_setup::SerInfos = map.next_value()?;
-
-//         if ser_infos.same_binary {
-//             let metadata: Vec<(StorageId, usize)>;
-
-//             match map.next_entry()? {
-//                 Some(("metadata", types)) => metadata = types,
-//                 Some((field, _)) => {
-//                     return Err(serde::de::Error::unknown_field(
-//                         field,
-//                         &["ser_infos", "metadata", "storages"],
-//                     ))
-//                 }
-//                 None => return Err(serde::de::Error::missing_field("metadata")),
-//             }
-
-//             match map.next_key_seed(core::marker::PhantomData)? {
-//                 Some("storages") => (),
-//                 Some(field) => {
-//                     return Err(serde::de::Error::unknown_field(
-//                         field,
-//                         &["ser_infos", "metadata", "storages"],
-//                     ))
-//                 }
-//                 None => return Err(serde::de::Error::missing_field("storages")),
-//             }
-
-//             map.next_value_seed(StoragesSeed {
-//                 metadata,
-//                 all_storages: self.all_storages,
-//                 de_config: self.de_config,
-//             })?;
-//         } else {
-//             todo!()
-//         }
-
-//         Ok(())
-//     }
-// }
-
-// #[cfg(feature = "serde1")]
-// struct StoragesSeed<'all> {
-//     metadata: Vec<(StorageId, usize)>,
-//     all_storages: RefMut<'all, AllStorages>,
-//     de_config: GlobalDeConfig,
-// }
-
-// #[cfg(feature = "serde1")]
-// impl<'de> serde::de::DeserializeSeed<'de> for StoragesSeed<'_> {
-//     type Value = ();
-
-//     fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-//     where
-//         D: serde::Deserializer<'de>,
-//     {
-//         struct StoragesVisitor<'all> {
-//             metadata: Vec<(StorageId, usize)>,
-//             all_storages: RefMut<'all, AllStorages>,
-//             de_config: GlobalDeConfig,
-//         }
-
-//         impl<'de> serde::de::Visitor<'de> for StoragesVisitor<'_> {
-//             type Value = ();
-
-//             fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-//                 formatter.write_str("storages value")
-//             }
-
-//             fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
-//             where
-//                 A: serde::de::SeqAccess<'de>,
-//             {
-//                 let storages = self.all_storages.storages();
-
-//                 for (i, (storage_id, deserialize_ptr)) in self.metadata.into_iter().enumerate() {
-//                     let storage: &mut Storage =
-//                         &mut storages.entry(storage_id).or_insert_with(|| {
-//                             let deserialize =
-//                                 unsafe { crate::unknown_storage::deserialize_fn(deserialize_ptr) };
-
-//                             let mut sparse_set = crate::sparse_set::SparseSet::<u8>::new();
-//                             sparse_set.metadata.serde = Some(crate::sparse_set::SerdeInfos {
-//                                 serialization:
-//                                     |sparse_set: &crate::sparse_set::SparseSet<u8>,
-//                                     ser_config: GlobalSerConfig,
-//                                     serializer: &mut dyn crate::erased_serde::Serializer| {
-//                                         crate::erased_serde::Serialize::erased_serialize(
-//                                             &crate::sparse_set::SparseSetSerializer {
-//                                                 sparse_set: &sparse_set,
-//                                                 ser_config,
-//                                             },
-//                                             serializer,
-//                                         )
-//                                     },
-//                                 deserialization: deserialize,
-//                                 with_shared: true,
-//                                 identifier: None,
-//                             });
-
-//                             Storage(Box::new(AtomicRefCell::new(sparse_set, None, true)))
-//                         });
-
-//                     if seq
-//                         .next_element_seed(crate::storage::StorageDeserializer {
-//                             storage,
-//                             de_config: self.de_config,
-//                         })?
-//                         .is_none()
-//                     {
-//                         return Err(serde::de::Error::invalid_length(i, &"more storages"));
-//                     }
-//                 }
-
-//                 Ok(())
-//             }
-//         }
-
-//         deserializer.deserialize_seq(StoragesVisitor {
-//             metadata: self.metadata,
-//             all_storages: self.all_storages,
-//             de_config: self.de_config,
-//         })
-//     }
-// }
-
-// #[cfg(feature = "serde1")]
-// struct ExistingWorldVisitor<'a> {
-//     all_storages: RefMut<'a, AllStorages>,
-//     de_config: GlobalDeConfig,
-// }
+#[cfg(feature = "serde1")]
+struct WorldVisitor<'a> {
+    all_storages: &'a mut AllStorages,
+    de_config: GlobalDeConfig,
+}
+
+#[cfg(feature = "serde1")]
+impl<'de> serde::de::Visitor<'de> for WorldVisitor<'_> {
+    type Value = EntitiesMap;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("newtype struct World")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::de::DeserializeSeed::deserialize(
+            crate::storage::AllStoragesDeserializer {
+                all_storages: self.all_storages,
+                de_config: self.de_config,
+            },
+            deserializer,
+        )
+    }
+}
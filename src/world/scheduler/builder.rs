@@ -1,5 +1,5 @@
 use super::info::{BatchInfo, Conflict, SystemId, SystemInfo, TypeInfo, WorkloadInfo};
-use super::{Batches, Scheduler};
+use super::{Batches, Label, Scheduler, SystemContext};
 use crate::borrow::Mutability;
 use crate::error;
 use crate::storage::{AllStorages, StorageId};
@@ -44,12 +44,41 @@ pub struct WorkloadBuilder {
         TypeId,
         &'static str,
         Range<usize>,
-        Box<dyn Fn(&World) -> Result<(), error::Run> + Send + Sync + 'static>,
+        Box<dyn Fn(&World) -> Result<(), error::RunWorkload> + Send + Sync + 'static>,
+        SystemConfig,
     )>,
     borrow_info: Vec<TypeInfo>,
+    run_if: Vec<(
+        &'static str,
+        Vec<TypeInfo>,
+        Box<dyn Fn(&World) -> Result<bool, error::Run> + Send + Sync + 'static>,
+    )>,
     name: Cow<'static, str>,
 }
 
+/// Labels, ordering constraints and run conditions of a system.
+#[allow(clippy::type_complexity)]
+#[derive(Default)]
+struct SystemConfig {
+    /// `TypeId` of the system's function, matched by labels created from a system.
+    function: Option<TypeId>,
+    labels: Vec<Cow<'static, str>>,
+    before: Vec<Label>,
+    after: Vec<Label>,
+    run_if: Vec<Box<dyn Fn(&World) -> Result<bool, error::Run> + Send + Sync + 'static>>,
+    /// The system runs the workload with the same name.
+    is_workload: bool,
+}
+
+impl SystemConfig {
+    fn is_labelled(&self, label: &Label) -> bool {
+        match label {
+            Label::System(type_id, _) => self.function == Some(*type_id),
+            Label::Name(name) => self.labels.contains(name),
+        }
+    }
+}
+
 impl WorkloadBuilder {
     /// Creates a new empty [`WorkloadBuilder`].
     ///
@@ -94,6 +123,7 @@ impl WorkloadBuilder {
         WorkloadBuilder {
             systems: Vec::new(),
             borrow_info: Vec::new(),
+            run_if: Vec::new(),
             name: name.into(),
         }
     }
@@ -148,7 +178,7 @@ impl WorkloadBuilder {
         'a,
         B,
         R,
-        F: System<'a, (), B, R>,
+        F: System<'a, (), B, R> + 'static,
         S: Fn(&World) -> Result<(), error::Run> + Send + Sync + 'static,
     >(
         &mut self,
@@ -157,43 +187,25 @@ impl WorkloadBuilder {
         let old_len = self.borrow_info.len();
         F::borrow_info(&mut self.borrow_info);
 
-        let borrows = &self.borrow_info[old_len..];
+        if let Err(err) = check_borrows(&self.borrow_info[old_len..]) {
+            self.borrow_info.truncate(old_len);
 
-        if borrows.contains(&TypeInfo {
-            name: "",
-            storage_id: StorageId::of::<AllStorages>(),
-            mutability: Mutability::Exclusive,
-            is_send: true,
-            is_sync: true,
-        }) && borrows.len() > 1
-        {
-            return Err(error::InvalidSystem::AllStorages);
+            return Err(err);
         }
 
-        let mid = borrows.len() / 2 + (borrows.len() % 2 != 0) as usize;
-
-        for a_type_info in &borrows[..mid] {
-            for b_type_info in &borrows[mid..] {
-                if a_type_info.storage_id == b_type_info.storage_id {
-                    match (a_type_info.mutability, b_type_info.mutability) {
-                        (Mutability::Exclusive, Mutability::Exclusive) => {
-                            return Err(error::InvalidSystem::MultipleViewsMut)
-                        }
-                        (Mutability::Exclusive, Mutability::Shared)
-                        | (Mutability::Shared, Mutability::Exclusive) => {
-                            return Err(error::InvalidSystem::MultipleViews)
-                        }
-                        (Mutability::Shared, Mutability::Shared) => {}
-                    }
-                }
-            }
-        }
+        let system_name = type_name::<F>();
 
         self.systems.push((
             TypeId::of::<S>(),
-            type_name::<F>(),
+            system_name,
             old_len..self.borrow_info.len(),
-            Box::new(system),
+            Box::new(move |world: &World| {
+                system(world).map_err(|err| error::RunWorkload::Run((system_name, err)))
+            }),
+            SystemConfig {
+                function: Some(TypeId::of::<F>()),
+                ..SystemConfig::default()
+            },
         ));
 
         Ok(self)
@@ -248,7 +260,7 @@ impl WorkloadBuilder {
         'a,
         B,
         R,
-        F: System<'a, (), B, R>,
+        F: System<'a, (), B, R> + 'static,
         S: Fn(&World) -> Result<(), error::Run> + Send + Sync + 'static,
     >(
         &mut self,
@@ -265,15 +277,500 @@ impl WorkloadBuilder {
         let offset_ranges_by = self.borrow_info.len();
         self.borrow_info.extend(other.borrow_info.drain(..));
         self.systems.extend(other.systems.drain(..).map(
-            |(type_id, type_name, mut borrow_info_range, system_fn)| {
+            |(type_id, type_name, mut borrow_info_range, system_fn, config)| {
                 borrow_info_range.start += offset_ranges_by;
                 borrow_info_range.end += offset_ranges_by;
-                (type_id, type_name, borrow_info_range, system_fn)
+                (type_id, type_name, borrow_info_range, system_fn, config)
+            },
+        ));
+
+        self
+    }
+    /// Adds the workload `name` to the workload being created, as if it was a single system.  
+    /// The nested workload has to be added to the [`World`] before this workload.
+    /// It's shared with the other workloads using it and keeps its own batches and run conditions.  
+    /// In this workload it's scheduled as one unit borrowing all the storages its systems borrow.  
+    /// It's labelled with its name, other systems can use [`before`] and [`after`] with `name` to be ordered relative to it.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{system, UniqueView, UniqueViewMut, Workload, World};
+    ///
+    /// fn gravity(mut speed: UniqueViewMut<f32>) {
+    ///     *speed -= 9.8;
+    /// }
+    ///
+    /// fn movement(speed: UniqueView<f32>, mut height: UniqueViewMut<f64>) {
+    ///     *height += *speed as f64;
+    /// }
+    ///
+    /// fn render(_: UniqueView<f64>) {}
+    ///
+    /// let world = World::new();
+    /// world.add_unique(0f32);
+    /// world.add_unique(100f64);
+    ///
+    /// Workload::builder("Physics")
+    ///     .with_system(system!(gravity))
+    ///     .with_system(system!(movement))
+    ///     .add_to_world(&world)
+    ///     .unwrap();
+    ///
+    /// let info = Workload::builder("Game")
+    ///     .with_workload("Physics")
+    ///     .with_system(system!(render))
+    ///     .add_to_world_with_info(&world)
+    ///     .unwrap();
+    ///
+    /// let physics = info.batch_info[0].systems[0].workload.as_ref().unwrap();
+    /// assert_eq!(physics.batch_info.len(), 2);
+    ///
+    /// world.run_workload("Game");
+    /// world.run(|height: UniqueView<f64>| assert_eq!(*height, 100. - 9.8f32 as f64));
+    /// ```
+    ///
+    /// [`World`]: struct.World.html
+    /// [`before`]: struct.WorkloadBuilder.html#method.before
+    /// [`after`]: struct.WorkloadBuilder.html#method.after
+    pub fn with_workload(&mut self, name: &'static str) -> &mut Self {
+        let len = self.borrow_info.len();
+
+        self.systems.push((
+            TypeId::of::<Workload>(),
+            name,
+            len..len,
+            Box::new(move |world: &World| world.try_run_nested_workload(name)),
+            SystemConfig {
+                labels: vec![name.into()],
+                is_workload: true,
+                ..SystemConfig::default()
             },
         ));
 
         self
     }
+    /// Gives `label` to the last system added.  
+    /// Other systems can then be ordered relative to all systems sharing this label with [`before`] and [`after`].  
+    /// Does nothing if no system was added yet.
+    ///
+    /// [`before`]: struct.WorkloadBuilder.html#method.before
+    /// [`after`]: struct.WorkloadBuilder.html#method.after
+    pub fn label<L: Into<Cow<'static, str>>>(&mut self, label: L) -> &mut Self {
+        if let Some((_, _, _, _, config)) = self.systems.last_mut() {
+            config.labels.push(label.into());
+        }
+
+        self
+    }
+    /// Makes the last system added run before the systems identified by `label`, even if they don't borrow the same storages.  
+    /// `label` can be a system or a name given to systems with [`label`].
+    /// Labels not matching any system in the workload are ignored.  
+    /// Does nothing if no system was added yet.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{system, UniqueView, UniqueViewMut, Workload, World};
+    ///
+    /// fn physics(mut frame: UniqueViewMut<u32>) {
+    ///     *frame += 1;
+    /// }
+    ///
+    /// fn input(_: UniqueView<usize>) {}
+    ///
+    /// let world = World::new();
+    /// world.add_unique(0u32);
+    /// world.add_unique(0usize);
+    ///
+    /// let info = Workload::builder("Game")
+    ///     .with_system(system!(physics))
+    ///     .with_system(system!(input))
+    ///     .before(system!(physics))
+    ///     .add_to_world_with_info(&world)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(info.batch_info.len(), 2);
+    /// assert!(info.batch_info[0].systems[0].name.ends_with("input"));
+    /// ```
+    ///
+    /// [`label`]: struct.WorkloadBuilder.html#method.label
+    pub fn before<L: Into<Label>>(&mut self, label: L) -> &mut Self {
+        if let Some((_, _, _, _, config)) = self.systems.last_mut() {
+            config.before.push(label.into());
+        }
+
+        self
+    }
+    /// Makes the last system added run after the systems identified by `label`, even if they don't borrow the same storages.  
+    /// `label` can be a system or a name given to systems with [`label`].
+    /// Labels not matching any system in the workload are ignored.  
+    /// Does nothing if no system was added yet.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{system, UniqueView, UniqueViewMut, Workload, World};
+    ///
+    /// fn read_input(_: UniqueView<usize>) {}
+    ///
+    /// fn poll_gamepad(_: UniqueView<usize>) {}
+    ///
+    /// fn physics(mut frame: UniqueViewMut<u32>) {
+    ///     *frame += 1;
+    /// }
+    ///
+    /// let world = World::new();
+    /// world.add_unique(0u32);
+    /// world.add_unique(0usize);
+    ///
+    /// let info = Workload::builder("Game")
+    ///     .with_system(system!(read_input))
+    ///     .label("input")
+    ///     .with_system(system!(poll_gamepad))
+    ///     .label("input")
+    ///     .with_system(system!(physics))
+    ///     .after("input")
+    ///     .add_to_world_with_info(&world)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(info.batch_info.len(), 2);
+    /// assert_eq!(info.batch_info[0].systems.len(), 2);
+    /// assert_eq!(info.batch_info[1].systems[0].after.len(), 2);
+    /// ```
+    ///
+    /// [`label`]: struct.WorkloadBuilder.html#method.label
+    pub fn after<L: Into<Label>>(&mut self, label: L) -> &mut Self {
+        if let Some((_, _, _, _, config)) = self.systems.last_mut() {
+            config.after.push(label.into());
+        }
+
+        self
+    }
+    /// Adds a run condition to the last system added.  
+    /// Each time the workload runs, the system only runs if all its conditions return `true`.  
+    /// Conditions borrow storages like systems do, right before the system and in the same batch.  
+    /// Does nothing if no system was added yet.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{system, UniqueView, UniqueViewMut, Workload, World};
+    ///
+    /// fn is_running(paused: UniqueView<bool>) -> bool {
+    ///     !*paused
+    /// }
+    ///
+    /// fn physics(mut frame: UniqueViewMut<u32>) {
+    ///     *frame += 1;
+    /// }
+    ///
+    /// let world = World::new();
+    /// world.add_unique(false);
+    /// world.add_unique(0u32);
+    ///
+    /// Workload::builder("Game")
+    ///     .with_system(system!(physics))
+    ///     .try_run_if((|world: &World| world.try_run(is_running), is_running))
+    ///     .unwrap()
+    ///     .add_to_world(&world)
+    ///     .unwrap();
+    ///
+    /// world.run_default();
+    /// world.run(|mut paused: UniqueViewMut<bool>| *paused = true);
+    /// world.run_default();
+    ///
+    /// world.run(|frame: UniqueView<u32>| assert_eq!(*frame, 1));
+    /// ```
+    ///
+    /// ### Errors
+    ///
+    /// - The condition borrows the same storage twice or `AllStorages` alongside other storages.
+    pub fn try_run_if<
+        'a,
+        B,
+        F: System<'a, (), B, bool>,
+        S: Fn(&World) -> Result<bool, error::Run> + Send + Sync + 'static,
+    >(
+        &mut self,
+        (condition, _): (S, F),
+    ) -> Result<&mut Self, error::InvalidSystem> {
+        let mut borrows = Vec::new();
+        F::borrow_info(&mut borrows);
+        check_borrows(&borrows)?;
+
+        if let Some((_, _, info_range, _, config)) = self.systems.last_mut() {
+            self.borrow_info.extend(borrows);
+            info_range.end = self.borrow_info.len();
+            config.run_if.push(Box::new(condition));
+        }
+
+        Ok(self)
+    }
+    /// Adds a run condition to the last system added.  
+    /// Each time the workload runs, the system only runs if all its conditions return `true`.  
+    /// Conditions borrow storages like systems do, right before the system and in the same batch.  
+    /// Does nothing if no system was added yet.  
+    /// Unwraps errors.
+    ///
+    /// ### Errors
+    ///
+    /// - The condition borrows the same storage twice or `AllStorages` alongside other storages.
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn run_if<
+        'a,
+        B,
+        F: System<'a, (), B, bool>,
+        S: Fn(&World) -> Result<bool, error::Run> + Send + Sync + 'static,
+    >(
+        &mut self,
+        condition: (S, F),
+    ) -> &mut Self {
+        match self.try_run_if(condition) {
+            Ok(s) => s,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Declares the last system added borrows the dynamic storage registered under `StorageId::Custom(id)`.  
+    /// Dynamic storages are borrowed by id so they can't be part of a system's signature,
+    /// this makes the borrow part of the system's [`TypeInfo`] for scheduling and workload information.  
+    /// `name` is displayed in workload information, usually the name of the component's descriptor.  
+    /// Does nothing if no system was added yet.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{ComponentDescriptor, Mutability, View, Workload, World};
+    ///
+    /// const SPEED: u64 = 0;
+    ///
+    /// fn read_speed(world: &World) {
+    ///     let _speeds = world.borrow_dynamic(SPEED);
+    ///     let _u32s = world.borrow::<View<u32>>();
+    /// }
+    ///
+    /// let world = World::new();
+    /// world.register_dynamic_component(SPEED, ComponentDescriptor::of::<f32>("speed"));
+    ///
+    /// let info = Workload::builder("Scripts")
+    ///     .with_system((|world: &World| Ok(read_speed(world)), |_: View<u32>| {}))
+    ///     .try_with_dynamic_borrow(SPEED, "speed", Mutability::Shared)
+    ///     .unwrap()
+    ///     .add_to_world_with_info(&world)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(info.batch_info[0].systems[0].borrow.len(), 2);
+    ///
+    /// world.run_default();
+    /// ```
+    ///
+    /// ### Errors
+    ///
+    /// - The system already borrows this storage and one of the borrows is exclusive.
+    ///
+    /// [`TypeInfo`]: info/struct.TypeInfo.html
+    pub fn try_with_dynamic_borrow(
+        &mut self,
+        id: u64,
+        name: &'static str,
+        mutability: Mutability,
+    ) -> Result<&mut Self, error::InvalidSystem> {
+        if let Some((_, _, info_range, _, _)) = self.systems.last_mut() {
+            let type_info = TypeInfo {
+                name,
+                mutability,
+                storage_id: StorageId::Custom(id),
+                is_send: true,
+                is_sync: true,
+            };
+
+            let mut borrows = self.borrow_info[info_range.clone()].to_vec();
+            borrows.push(type_info.clone());
+            check_borrows(&borrows)?;
+
+            self.borrow_info.push(type_info);
+            info_range.end = self.borrow_info.len();
+        }
+
+        Ok(self)
+    }
+    /// Declares the last system added borrows the dynamic storage registered under `StorageId::Custom(id)`.  
+    /// Does nothing if no system was added yet.  
+    /// Unwraps errors.
+    ///
+    /// ### Errors
+    ///
+    /// - The system already borrows this storage and one of the borrows is exclusive.
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn with_dynamic_borrow(
+        &mut self,
+        id: u64,
+        name: &'static str,
+        mutability: Mutability,
+    ) -> &mut Self {
+        match self.try_with_dynamic_borrow(id, name, mutability) {
+            Ok(s) => s,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Adds a run condition to the workload.  
+    /// Each time the workload runs, its conditions are checked before any system and
+    /// none of its systems run if one of them returns `false`.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{system, UniqueView, UniqueViewMut, Workload, World};
+    ///
+    /// fn is_loaded(loaded: UniqueView<bool>) -> bool {
+    ///     *loaded
+    /// }
+    ///
+    /// fn physics(mut frame: UniqueViewMut<u32>) {
+    ///     *frame += 1;
+    /// }
+    ///
+    /// let world = World::new();
+    /// world.add_unique(false);
+    /// world.add_unique(0u32);
+    ///
+    /// Workload::builder("Game")
+    ///     .with_system(system!(physics))
+    ///     .run_workload_if((|world: &World| world.try_run(is_loaded), is_loaded))
+    ///     .add_to_world(&world)
+    ///     .unwrap();
+    ///
+    /// world.run_default();
+    /// world.run(|frame: UniqueView<u32>| assert_eq!(*frame, 0));
+    /// ```
+    ///
+    /// ### Errors
+    ///
+    /// - The condition borrows the same storage twice or `AllStorages` alongside other storages.
+    pub fn try_run_workload_if<
+        'a,
+        B,
+        F: System<'a, (), B, bool>,
+        S: Fn(&World) -> Result<bool, error::Run> + Send + Sync + 'static,
+    >(
+        &mut self,
+        (condition, _): (S, F),
+    ) -> Result<&mut Self, error::InvalidSystem> {
+        let mut borrows = Vec::new();
+        F::borrow_info(&mut borrows);
+        check_borrows(&borrows)?;
+
+        self.run_if
+            .push((type_name::<F>(), borrows, Box::new(condition)));
+
+        Ok(self)
+    }
+    /// Adds a run condition to the workload.  
+    /// Each time the workload runs, its conditions are checked before any system and
+    /// none of its systems run if one of them returns `false`.  
+    /// Unwraps errors.
+    ///
+    /// ### Errors
+    ///
+    /// - The condition borrows the same storage twice or `AllStorages` alongside other storages.
+    #[cfg(feature = "panic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "panic")))]
+    #[track_caller]
+    pub fn run_workload_if<
+        'a,
+        B,
+        F: System<'a, (), B, bool>,
+        S: Fn(&World) -> Result<bool, error::Run> + Send + Sync + 'static,
+    >(
+        &mut self,
+        condition: (S, F),
+    ) -> &mut Self {
+        match self.try_run_workload_if(condition) {
+            Ok(s) => s,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    /// Sorts the systems so each system is placed after all the systems it has to run after.  
+    /// Systems are kept in insertion order when no constraint applies.  
+    /// Returns, for each system in the new order, the position of the systems it has to run after.
+    fn sort_systems(&mut self) -> Result<Vec<Vec<usize>>, error::AddWorkload> {
+        let len = self.systems.len();
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); len];
+        let mut predecessor_count = vec![0; len];
+
+        for (i, (_, _, _, _, config)) in self.systems.iter().enumerate() {
+            for (j, (_, _, _, _, other_config)) in self.systems.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+
+                let is_before = config
+                    .before
+                    .iter()
+                    .any(|label| other_config.is_labelled(label));
+                if is_before && !successors[i].contains(&j) {
+                    successors[i].push(j);
+                    predecessor_count[j] += 1;
+                }
+
+                let is_after = config
+                    .after
+                    .iter()
+                    .any(|label| other_config.is_labelled(label));
+                if is_after && !successors[j].contains(&i) {
+                    successors[j].push(i);
+                    predecessor_count[i] += 1;
+                }
+            }
+        }
+
+        let mut sorted = Vec::with_capacity(len);
+        let mut is_sorted = vec![false; len];
+
+        while sorted.len() < len {
+            match (0..len).find(|&i| !is_sorted[i] && predecessor_count[i] == 0) {
+                Some(system) => {
+                    is_sorted[system] = true;
+                    sorted.push(system);
+
+                    for &successor in &successors[system] {
+                        predecessor_count[successor] -= 1;
+                    }
+                }
+                None => {
+                    // all remaining systems have a remaining predecessor
+                    // going back from predecessor to predecessor ends up in a cycle
+                    let mut system = (0..len).find(|&i| !is_sorted[i]).unwrap();
+                    for _ in 0..len {
+                        system = (0..len)
+                            .find(|&i| !is_sorted[i] && successors[i].contains(&system))
+                            .unwrap();
+                    }
+
+                    return Err(error::AddWorkload::Cycle(self.systems[system].1));
+                }
+            }
+        }
+
+        let mut positions = vec![0; len];
+        for (position, &system) in sorted.iter().enumerate() {
+            positions[system] = position;
+        }
+
+        let mut predecessors = vec![Vec::new(); len];
+        for (system, successors) in successors.iter().enumerate() {
+            for &successor in successors {
+                predecessors[positions[successor]].push(positions[system]);
+            }
+        }
+
+        let mut systems: Vec<_> = self.systems.drain(..).map(Some).collect();
+        self.systems.extend(
+            sorted
+                .into_iter()
+                .map(|system| systems[system].take().unwrap()),
+        );
+
+        Ok(predecessors)
+    }
     /// Finishes the workload creation and stores it in the [`World`].
     ///
     /// ### Borrows
@@ -284,6 +781,7 @@ impl WorkloadBuilder {
     ///
     /// - Scheduler borrow failed.
     /// - Workload with an identical name already present.
+    /// - Ordering constraints between systems form a cycle.
     ///
     /// [`World`]: struct.World.html
     pub fn add_to_world(&mut self, world: &World) -> Result<(), error::AddWorkload> {
@@ -300,6 +798,7 @@ impl WorkloadBuilder {
     ///
     /// - Scheduler borrow failed.
     /// - Workload with an identical name already present.
+    /// - Ordering constraints between systems form a cycle.
     ///
     /// [`World`]: struct.World.html
     #[allow(clippy::blocks_in_if_conditions)]
@@ -316,10 +815,56 @@ impl WorkloadBuilder {
             systems,
             system_names,
             lookup_table,
+            conditions,
+            condition_names,
             workloads,
+            workload_ids,
+            system_contexts,
+            workload_infos,
             default,
+            ..
         } = &mut *scheduler;
 
+        // nested workloads borrow everything their systems and conditions borrow
+        for (_, name, info_range, _, config) in &mut self.systems {
+            if config.is_workload {
+                let (workload_borrows, _) = workload_infos
+                    .get(*name)
+                    .ok_or(error::AddWorkload::MissingWorkload(name))?;
+
+                let start = self.borrow_info.len();
+                for index in info_range.clone() {
+                    let type_info = self.borrow_info[index].clone();
+                    self.borrow_info.push(type_info);
+                }
+                self.borrow_info.extend(workload_borrows.iter().cloned());
+                *info_range = start..self.borrow_info.len();
+            }
+        }
+
+        let predecessors = self.sort_systems()?;
+
+        // systems without borrow info or borrowing `AllStorages` could borrow anything
+        let system_context = self.systems.iter().any(|(_, name, info_range, _, config)| {
+            let borrows = &self.borrow_info[info_range.clone()];
+
+            borrows.is_empty()
+                || borrows.iter().any(|type_info| {
+                    SystemContext::is(type_info)
+                        || type_info.storage_id == StorageId::of::<AllStorages>()
+                })
+                || (config.is_workload && system_contexts[workload_ids[*name]])
+        });
+
+        let mut workload_borrows = Vec::new();
+        for type_info in self
+            .borrow_info
+            .iter()
+            .chain(self.run_if.iter().flat_map(|(_, borrows, _)| borrows))
+        {
+            merge_borrow(&mut workload_borrows, type_info);
+        }
+
         let mut workload_info;
 
         if self.systems.is_empty() {
@@ -359,13 +904,20 @@ impl WorkloadBuilder {
                 }
             };
 
-            let (type_id, system_name, _, system) = self.systems.pop().unwrap();
+            let (type_id, system_name, info_range, system, config) = self.systems.pop().unwrap();
 
-            let system_index = *lookup_table.entry(type_id).or_insert_with(|| {
-                systems.push(system);
+            let system_index = if config.run_if.is_empty() && !config.is_workload {
+                *lookup_table.entry(type_id).or_insert_with(|| {
+                    systems.push(system);
+                    system_names.push(system_name);
+                    systems.len() - 1
+                })
+            } else {
+                // systems with run conditions and nested workloads aren't shared with other workloads
+                systems.push(conditional_system(system_name, system, config.run_if));
                 system_names.push(system_name);
                 systems.len() - 1
-            });
+            };
 
             batches.parallel.push(vec![system_index]);
             batches.sequential.push(system_index);
@@ -374,8 +926,16 @@ impl WorkloadBuilder {
                 systems: vec![SystemInfo {
                     name: system_name,
                     type_id,
-                    borrow: core::mem::take(&mut self.borrow_info),
+                    borrow: visible_borrows(&self.borrow_info[info_range]),
+                    after: Vec::new(),
                     conflict: None,
+                    workload: if config.is_workload {
+                        workload_infos
+                            .get(system_name)
+                            .map(|(_, info)| info.clone())
+                    } else {
+                        None
+                    },
                 }],
             };
 
@@ -406,12 +966,47 @@ impl WorkloadBuilder {
                 batch_info: vec![],
             };
 
-            for (system_type_id, system_name, info_range, system) in self.systems.drain(..) {
-                let system_index = *lookup_table.entry(system_type_id).or_insert_with(|| {
-                    systems.push(system);
+            let system_ids: Vec<SystemId> = self
+                .systems
+                .iter()
+                .map(|&(type_id, name, _, _, _)| SystemId { name, type_id })
+                .collect();
+            let mut system_batches = Vec::with_capacity(system_ids.len());
+
+            for (position, (system_type_id, system_name, info_range, system, config)) in
+                self.systems.drain(..).enumerate()
+            {
+                let after: Vec<SystemId> = predecessors[position]
+                    .iter()
+                    .map(|&predecessor| system_ids[predecessor].clone())
+                    .collect();
+
+                // the system has to be in a later batch than all systems it runs after
+                let last_predecessor = predecessors[position]
+                    .iter()
+                    .copied()
+                    .max_by_key(|&predecessor| system_batches[predecessor]);
+
+                let system_index = if config.run_if.is_empty() && !config.is_workload {
+                    *lookup_table.entry(system_type_id).or_insert_with(|| {
+                        systems.push(system);
+                        system_names.push(system_name);
+                        systems.len() - 1
+                    })
+                } else {
+                    // systems with run conditions and nested workloads aren't shared with other workloads
+                    systems.push(conditional_system(system_name, system, config.run_if));
                     system_names.push(system_name);
                     systems.len() - 1
-                });
+                };
+
+                let nested_info = if config.is_workload {
+                    workload_infos
+                        .get(system_name)
+                        .map(|(_, info)| info.clone())
+                } else {
+                    None
+                };
 
                 batches.sequential.push(system_index);
 
@@ -424,14 +1019,26 @@ impl WorkloadBuilder {
                     let mut system_info = SystemInfo {
                         name: system_name,
                         type_id: system_type_id,
-                        borrow: self.borrow_info[info_range.clone()].to_vec(),
+                        borrow: visible_borrows(&self.borrow_info[info_range.clone()]),
+                        after,
                         conflict: None,
+                        workload: nested_info,
                     };
 
                     let mut valid = batches.parallel.len();
 
                     'batch: for (i, batch_info) in workload_info.batch_info.iter().enumerate().rev()
                     {
+                        if let Some(predecessor) = last_predecessor {
+                            if i == system_batches[predecessor] {
+                                system_info.conflict = Some(Conflict::Ordering {
+                                    system: system_ids[predecessor].clone(),
+                                });
+
+                                break 'batch;
+                            }
+                        }
+
                         for system in &batch_info.systems {
                             for system_type_info in system.borrow.iter() {
                                 for type_info in &self.borrow_info[info_range.clone()] {
@@ -491,6 +1098,8 @@ impl WorkloadBuilder {
                             systems: vec![system_info],
                         });
                     }
+
+                    system_batches.push(valid);
                 } else {
                     let system_info = SystemInfo {
                         name: system_name,
@@ -502,9 +1111,12 @@ impl WorkloadBuilder {
                             is_send: true,
                             is_sync: true,
                         }],
+                        after,
                         conflict: Some(Conflict::NotSendSync),
+                        workload: nested_info,
                     };
 
+                    system_batches.push(batches.parallel.len());
                     batches.parallel.push(vec![system_index]);
                     workload_info.batch_info.push(BatchInfo {
                         systems: vec![system_info],
@@ -513,10 +1125,111 @@ impl WorkloadBuilder {
             }
         }
 
+        let run_if = self
+            .run_if
+            .drain(..)
+            .map(|(condition_name, _, condition)| {
+                conditions.push(condition);
+                condition_names.push(condition_name);
+                conditions.len() - 1
+            })
+            .collect();
+        workloads.get_mut(&*workload_info.name).unwrap().run_if = run_if;
+        let workload_id = workload_ids.len();
+        workload_ids.insert(workload_info.name.clone(), workload_id);
+        system_contexts.push(system_context);
+        workload_infos.insert(
+            workload_info.name.clone(),
+            (workload_borrows, workload_info.clone()),
+        );
+        self.borrow_info.clear();
+
         Ok(workload_info)
     }
 }
 
+/// Checks that a system or run condition doesn't borrow the same storage twice
+/// and doesn't borrow `AllStorages` alongside other storages.
+fn check_borrows(borrows: &[TypeInfo]) -> Result<(), error::InvalidSystem> {
+    if borrows.contains(&TypeInfo {
+        name: "",
+        storage_id: StorageId::of::<AllStorages>(),
+        mutability: Mutability::Exclusive,
+        is_send: true,
+        is_sync: true,
+    }) && borrows.len() > 1
+    {
+        return Err(error::InvalidSystem::AllStorages);
+    }
+
+    let mid = borrows.len() / 2 + (borrows.len() % 2 != 0) as usize;
+
+    for a_type_info in &borrows[..mid] {
+        for b_type_info in &borrows[mid..] {
+            if a_type_info.storage_id == b_type_info.storage_id {
+                match (a_type_info.mutability, b_type_info.mutability) {
+                    (Mutability::Exclusive, Mutability::Exclusive) => {
+                        return Err(error::InvalidSystem::MultipleViewsMut)
+                    }
+                    (Mutability::Exclusive, Mutability::Shared)
+                    | (Mutability::Shared, Mutability::Exclusive) => {
+                        return Err(error::InvalidSystem::MultipleViews)
+                    }
+                    (Mutability::Shared, Mutability::Shared) => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the borrows listed in workload infos, `SystemContext` isn't a storage.
+fn visible_borrows(borrows: &[TypeInfo]) -> Vec<TypeInfo> {
+    borrows
+        .iter()
+        .filter(|type_info| !SystemContext::is(type_info))
+        .cloned()
+        .collect()
+}
+
+/// Adds `type_info` to `borrows`, a storage borrowed both shared and exclusively is kept exclusive.
+fn merge_borrow(borrows: &mut Vec<TypeInfo>, type_info: &TypeInfo) {
+    match borrows
+        .iter_mut()
+        .find(|borrow| borrow.storage_id == type_info.storage_id)
+    {
+        Some(borrow) => {
+            if type_info.mutability == Mutability::Exclusive {
+                borrow.mutability = Mutability::Exclusive;
+            }
+        }
+        None => borrows.push(type_info.clone()),
+    }
+}
+
+/// Wraps `system` to only run it when all its run conditions are met.
+#[allow(clippy::type_complexity)]
+fn conditional_system(
+    system_name: &'static str,
+    system: Box<dyn Fn(&World) -> Result<(), error::RunWorkload> + Send + Sync + 'static>,
+    run_if: Vec<Box<dyn Fn(&World) -> Result<bool, error::Run> + Send + Sync + 'static>>,
+) -> Box<dyn Fn(&World) -> Result<(), error::RunWorkload> + Send + Sync + 'static> {
+    if run_if.is_empty() {
+        return system;
+    }
+
+    Box::new(move |world: &World| {
+        for condition in &run_if {
+            if !condition(world).map_err(|err| error::RunWorkload::Run((system_name, err)))? {
+                return Ok(());
+            }
+        }
+
+        system(world)
+    })
+}
+
 #[test]
 fn single_immutable() {
     use crate::{View, World};
@@ -539,6 +1252,7 @@ fn single_immutable() {
         Some(&Batches {
             parallel: vec![vec![0]],
             sequential: vec![0],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "System1");
@@ -566,6 +1280,7 @@ fn single_mutable() {
         Some(&Batches {
             parallel: vec![vec![0]],
             sequential: vec![0],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "System1");
@@ -595,7 +1310,8 @@ fn multiple_immutable() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0, 1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -626,6 +1342,7 @@ fn multiple_mutable() {
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
             sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -655,7 +1372,8 @@ fn multiple_mixed() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -677,7 +1395,8 @@ fn multiple_mixed() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -717,7 +1436,8 @@ fn append_optimizes_batches() {
         scheduler.workloads.get("Combined"),
         Some(&Batches {
             parallel: vec![vec![0, 2], vec![1]],
-            sequential: vec![0, 1, 2]
+            sequential: vec![0, 1, 2],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Combined");
@@ -745,7 +1465,8 @@ fn all_storages() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0]],
-            sequential: vec![0]
+            sequential: vec![0],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -767,7 +1488,8 @@ fn all_storages() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -789,7 +1511,8 @@ fn all_storages() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -811,7 +1534,8 @@ fn all_storages() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -847,7 +1571,8 @@ fn non_send() {
         scheduler.workloads.get("Test"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Test");
@@ -869,7 +1594,8 @@ fn non_send() {
         scheduler.workloads.get("Test"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Test");
@@ -891,7 +1617,8 @@ fn non_send() {
         scheduler.workloads.get("Test"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Test");
@@ -913,7 +1640,8 @@ fn non_send() {
         scheduler.workloads.get("Test"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Test");
@@ -935,7 +1663,8 @@ fn non_send() {
         scheduler.workloads.get("Test"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Test");
@@ -969,7 +1698,8 @@ fn fake_borrow() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0], vec![1], vec![2]],
-            sequential: vec![0, 1, 2]
+            sequential: vec![0, 1, 2],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -1008,7 +1738,8 @@ fn unique_fake_borrow() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0, 1, 3], vec![2], vec![4]],
-            sequential: vec![0, 1, 2, 3, 4]
+            sequential: vec![0, 1, 2, 3, 4],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -1038,7 +1769,8 @@ fn unique_and_non_unique() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![vec![0, 1]],
-            sequential: vec![0, 1]
+            sequential: vec![0, 1],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -1059,7 +1791,8 @@ fn empty_workload() {
         scheduler.workloads.get("Systems"),
         Some(&Batches {
             parallel: vec![],
-            sequential: vec![]
+            sequential: vec![],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Systems");
@@ -1105,7 +1838,8 @@ fn append_ensures_multiple_batches_can_be_optimized_over() {
         scheduler.workloads.get("Combined"),
         Some(&Batches {
             parallel: vec![vec![0, 3], vec![1, 2]],
-            sequential: vec![0, 1, 2, 3]
+            sequential: vec![0, 1, 2, 3],
+            run_if: Vec::new(),
         })
     );
     assert_eq!(scheduler.default, "Combined");
@@ -0,0 +1,97 @@
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+/// Systems running on a thread, nested ones after the system running their workload.
+#[derive(Default)]
+struct CurrentSystem {
+    /// Keys of all systems running on this thread, the current system's key starts at `start`.
+    /// A key is the position of the system in the workload,
+    /// used to apply commands in the same order regardless of the threads systems ran on.  
+    /// The buffer is reused to not allocate each time a system runs.
+    keys: Vec<usize>,
+    start: usize,
+    /// Id of the top-level workload the system runs in,
+    /// used with the key to give each system its own events cursor and to count events runs per workload.
+    workload: Option<usize>,
+}
+
+std::thread_local! {
+    static CURRENT_SYSTEM: RefCell<CurrentSystem> = RefCell::new(CurrentSystem::default());
+}
+
+/// Sets the system running on this thread for the duration of `f`.  
+/// Its key is `prefix`, the key of the system running its workload if it's nested, followed by `batch` and `position`.
+pub(crate) fn with_current_system<R>(
+    workload: usize,
+    prefix: &[usize],
+    batch: usize,
+    position: usize,
+    f: impl FnOnce() -> R,
+) -> R {
+    let previous = CURRENT_SYSTEM.with(|current| {
+        let mut current = current.borrow_mut();
+        let previous = Previous {
+            start: current.start,
+            workload: current.workload,
+        };
+
+        current.start = current.keys.len();
+        current.keys.extend_from_slice(prefix);
+        current.keys.push(batch);
+        current.keys.push(position);
+        current.workload = Some(workload);
+
+        previous
+    });
+
+    // restores the previous system even if `f` panics
+    let _restore = previous;
+
+    f()
+}
+
+/// System that was running on this thread before `with_current_system`, put back when dropped.
+struct Previous {
+    start: usize,
+    workload: Option<usize>,
+}
+
+impl Drop for Previous {
+    fn drop(&mut self) {
+        CURRENT_SYSTEM.with(|current| {
+            let mut current = current.borrow_mut();
+            let start = current.start;
+
+            current.keys.truncate(start);
+            current.start = self.start;
+            current.workload = self.workload;
+        });
+    }
+}
+
+/// Returns the key of the system running on this thread.
+pub(crate) fn current_key() -> Vec<usize> {
+    CURRENT_SYSTEM.with(|current| {
+        let current = current.borrow();
+
+        current.keys[current.start..].to_vec()
+    })
+}
+
+/// Returns the id of the top-level workload running on this thread, `None` outside of workloads.
+pub(crate) fn current_workload() -> Option<usize> {
+    CURRENT_SYSTEM.with(|current| current.borrow().workload)
+}
+
+#[test]
+fn restore_on_panic() {
+    let result = std::panic::catch_unwind(|| {
+        with_current_system(0, &[], 1, 2, || {
+            with_current_system(1, &[1, 2], 3, 4, || panic!());
+        })
+    });
+
+    assert!(result.is_err());
+    assert!(current_key().is_empty());
+    assert_eq!(current_workload(), None);
+}
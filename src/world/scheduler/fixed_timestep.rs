@@ -0,0 +1,183 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// Unique storage driving [`World::run_fixed_timestep`].
+///
+/// Keeps the time left over after running the fixed workload as many times as possible
+/// and exposes it to systems as an interpolation factor with [`alpha`].
+///
+/// Workloads running at different fixed rates each get their own `FixedTimestep` inside [`FixedTimesteps`].
+///
+/// [`World::run_fixed_timestep`]: struct.World.html#method.run_fixed_timestep
+/// [`alpha`]: struct.FixedTimestep.html#method.alpha
+/// [`FixedTimesteps`]: struct.FixedTimesteps.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTimestep {
+    step: Duration,
+    accumulator: Duration,
+    alpha: f64,
+    max_steps: Option<u32>,
+    /// Steps run since the last call to `accumulate`.
+    steps: u32,
+}
+
+impl FixedTimestep {
+    /// Creates a new `FixedTimestep` running the fixed workload every `step`.  
+    /// `step` can't be zero.
+    ///
+    /// ### Example
+    /// ```
+    /// use core::time::Duration;
+    /// use shipyard::FixedTimestep;
+    ///
+    /// // 60Hz
+    /// let fixed_timestep = FixedTimestep::new(Duration::from_secs(1) / 60);
+    /// ```
+    pub fn new(step: Duration) -> Self {
+        assert!(
+            step != Duration::from_secs(0),
+            "FixedTimestep's step can't be zero."
+        );
+
+        FixedTimestep {
+            step,
+            accumulator: Duration::from_secs(0),
+            alpha: 0.,
+            max_steps: None,
+            steps: 0,
+        }
+    }
+    /// Limits the number of fixed steps run by a single call to [`World::run_fixed_timestep`].  
+    /// When a long frame would need more steps, the excess time is dropped instead of being caught up later.
+    /// This keeps a slow fixed workload from taking longer and longer each frame.  
+    /// `max_steps` can't be zero.
+    ///
+    /// ### Example
+    /// ```
+    /// use core::time::Duration;
+    /// use shipyard::FixedTimestep;
+    ///
+    /// // 60Hz, never more than 5 steps per frame
+    /// let fixed_timestep = FixedTimestep::new(Duration::from_secs(1) / 60).with_max_steps(5);
+    /// ```
+    ///
+    /// [`World::run_fixed_timestep`]: struct.World.html#method.run_fixed_timestep
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        assert!(max_steps != 0, "FixedTimestep's max steps can't be zero.");
+
+        self.max_steps = Some(max_steps);
+        self
+    }
+    /// Returns the duration of a single fixed step.
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+    /// Returns the maximum number of steps run by a single call, `None` if there isn't any.
+    pub fn max_steps(&self) -> Option<u32> {
+        self.max_steps
+    }
+    /// Returns the time not yet consumed by the fixed workload.
+    pub fn accumulator(&self) -> Duration {
+        self.accumulator
+    }
+    /// Returns how far the time is between the last fixed step and the next one, between `0.0` and `1.0`.  
+    /// Systems can use it to interpolate between the last two states computed by the fixed workload.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+    /// Adds `delta` to the accumulator.
+    pub(crate) fn accumulate(&mut self, delta: Duration) {
+        self.accumulator += delta;
+        self.steps = 0;
+    }
+    /// Consumes one step from the accumulator if there is enough time left and the maximum isn't reached.  
+    /// Drops the time that would need more than the maximum number of steps.  
+    /// Updates alpha when no step is consumed.
+    pub(crate) fn consume_step(&mut self) -> bool {
+        if self.max_steps == Some(self.steps) && self.accumulator >= self.step {
+            // keeps less than a step so alpha stays meaningful
+            let remainder = self.accumulator.as_nanos() % self.step.as_nanos();
+            self.accumulator = Duration::from_nanos(remainder as u64);
+        }
+
+        if self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            self.steps += 1;
+
+            true
+        } else {
+            self.alpha = self.accumulator.as_secs_f64() / self.step.as_secs_f64();
+
+            false
+        }
+    }
+}
+
+/// Unique storage driving [`World::run_multi_rate`].
+///
+/// Holds a [`FixedTimestep`] per fixed workload, each one with its own step, accumulator and alpha.
+///
+/// [`World::run_multi_rate`]: struct.World.html#method.run_multi_rate
+/// [`FixedTimestep`]: struct.FixedTimestep.html
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FixedTimesteps {
+    pub(crate) timesteps: Vec<(Cow<'static, str>, FixedTimestep)>,
+}
+
+impl FixedTimesteps {
+    /// Creates an empty `FixedTimesteps`.
+    pub fn new() -> Self {
+        FixedTimesteps {
+            timesteps: Vec::new(),
+        }
+    }
+    /// Runs the workload named `workload` at `fixed_timestep`'s rate.  
+    /// Workloads run in the order they're added, replaces `workload`'s previous `FixedTimestep` if there was one.
+    ///
+    /// ### Example
+    /// ```
+    /// use core::time::Duration;
+    /// use shipyard::{FixedTimestep, FixedTimesteps};
+    ///
+    /// // physics at 60Hz and AI at 10Hz
+    /// let fixed_timesteps = FixedTimesteps::new()
+    ///     .with_workload("Physics", FixedTimestep::new(Duration::from_secs(1) / 60))
+    ///     .with_workload("AI", FixedTimestep::new(Duration::from_secs(1) / 10));
+    /// ```
+    pub fn with_workload(
+        mut self,
+        workload: impl Into<Cow<'static, str>>,
+        fixed_timestep: FixedTimestep,
+    ) -> Self {
+        let workload = workload.into();
+
+        if let Some((_, timestep)) = self
+            .timesteps
+            .iter_mut()
+            .find(|(name, _)| *name == workload)
+        {
+            *timestep = fixed_timestep;
+        } else {
+            self.timesteps.push((workload, fixed_timestep));
+        }
+
+        self
+    }
+    /// Returns the `FixedTimestep` of `workload`, `None` if it doesn't run at a fixed rate.  
+    /// Systems can use its [`alpha`] to interpolate between the last two states computed by `workload`.
+    ///
+    /// [`alpha`]: struct.FixedTimestep.html#method.alpha
+    pub fn get(&self, workload: &str) -> Option<&FixedTimestep> {
+        self.timesteps
+            .iter()
+            .find(|(name, _)| name == workload)
+            .map(|(_, timestep)| timestep)
+    }
+    /// Returns an iterator over all fixed workloads' name and `FixedTimestep`, in the order they run.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FixedTimestep)> {
+        self.timesteps
+            .iter()
+            .map(|(name, timestep)| (&**name, timestep))
+    }
+}
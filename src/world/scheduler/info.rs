@@ -5,7 +5,9 @@ pub use crate::type_id::TypeId;
 use crate::borrow::Mutability;
 use crate::storage::StorageId;
 use alloc::borrow::Cow;
+use alloc::string::String;
 use alloc::vec::Vec;
+use core::fmt::Write;
 
 /// Contains information related to a workload.
 ///
@@ -16,6 +18,144 @@ pub struct WorkloadInfo {
     pub batch_info: Vec<BatchInfo>,
 }
 
+impl WorkloadInfo {
+    /// Renders the workload as a [Graphviz](https://graphviz.org) DOT graph.
+    ///
+    /// Systems are nodes and batches are clusters, nested workloads are clusters containing their own batches.  
+    /// A solid edge goes from the system that prevented a system from joining an earlier batch
+    /// to this system, labelled with the conflicting storage and borrow.  
+    /// Dashed edges come from ordering constraints and systems borrowing `!Send` or `!Sync` storages are dashed.
+    ///
+    /// ### Example
+    /// ```
+    /// use shipyard::{system, View, ViewMut, Workload, World};
+    ///
+    /// fn read(_: View<u32>) {}
+    ///
+    /// fn write(_: ViewMut<u32>) {}
+    ///
+    /// let world = World::new();
+    ///
+    /// let info = Workload::builder("Systems")
+    ///     .with_system(system!(write))
+    ///     .with_system(system!(read))
+    ///     .add_to_world_with_info(&world)
+    ///     .unwrap();
+    ///
+    /// let dot = info.to_dot();
+    /// assert!(dot.starts_with("digraph"));
+    /// assert!(dot.contains("SparseSet<u32> (Shared)"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let mut edges = String::new();
+
+        dot.push_str("digraph {\n");
+        dot.push_str("    node [shape=box];\n");
+        dot.push_str("    label=");
+        push_dot_str(&mut dot, &self.name);
+        dot.push_str(";\n");
+        self.write_dot(&mut dot, &mut edges, "s", 1);
+        dot.push_str(&edges);
+        dot.push_str("}\n");
+
+        dot
+    }
+    fn write_dot(&self, dot: &mut String, edges: &mut String, prefix: &str, depth: usize) {
+        let indent = "    ".repeat(depth);
+        // node id of each system of this workload, to link conflicts
+        let mut ids: Vec<(&SystemInfo, String)> = Vec::new();
+
+        for (batch_index, batch) in self.batch_info.iter().enumerate() {
+            let _ = writeln!(
+                dot,
+                "{}subgraph cluster_{}_{} {{",
+                indent, prefix, batch_index
+            );
+            let _ = writeln!(dot, "{}    label=\"batch {}\";", indent, batch_index);
+
+            for (system_index, system) in batch.systems.iter().enumerate() {
+                let id = alloc::format!("{}_{}_{}", prefix, batch_index, system_index);
+
+                let style = match system.conflict {
+                    Some(Conflict::NotSendSync) => ", style=dashed",
+                    _ => "",
+                };
+
+                match &system.workload {
+                    Some(workload) => {
+                        let _ = writeln!(dot, "{}    subgraph cluster_{} {{", indent, id);
+                        let _ = write!(dot, "{}        label=", indent);
+                        push_dot_str(dot, &workload.name);
+                        dot.push_str(";\n");
+                        let _ = write!(dot, "{}        {} [label=", indent, id);
+                        push_dot_str(dot, system.name);
+                        let _ = writeln!(dot, ", shape=box3d{}];", style);
+                        workload.write_dot(dot, edges, &id, depth + 2);
+                        let _ = writeln!(dot, "{}    }}", indent);
+                    }
+                    None => {
+                        let _ = write!(dot, "{}    {} [label=", indent, id);
+                        push_dot_str(dot, system.name);
+                        let _ = writeln!(dot, "{}];", style);
+                    }
+                }
+
+                let find = |other: &SystemId| {
+                    ids.iter()
+                        .find(|(system, _)| {
+                            system.name == other.name && system.type_id == other.type_id
+                        })
+                        .map(|(_, id)| id)
+                };
+
+                for predecessor in &system.after {
+                    if let Some(predecessor_id) = find(predecessor) {
+                        let _ = writeln!(
+                            edges,
+                            "    {} -> {} [style=dashed, label=\"after\"];",
+                            predecessor_id, id
+                        );
+                    }
+                }
+
+                if let Some(Conflict::Borrow {
+                    system: other,
+                    type_info,
+                }) = &system.conflict
+                {
+                    if let Some(other_id) = find(other) {
+                        let _ = write!(edges, "    {} -> {} [label=", other_id, id);
+                        push_dot_str(
+                            edges,
+                            &alloc::format!("{} ({:?})", type_info.name, type_info.mutability),
+                        );
+                        edges.push_str("];\n");
+                    }
+                }
+
+                ids.push((system, id));
+            }
+
+            let _ = writeln!(dot, "{}}}", indent);
+        }
+    }
+}
+
+/// Writes `s` as a quoted DOT string.
+fn push_dot_str(dot: &mut String, s: &str) {
+    dot.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => dot.push_str("\\\""),
+            '\\' => dot.push_str("\\\\"),
+            '\n' => dot.push_str("\\n"),
+            c => dot.push(c),
+        }
+    }
+    dot.push('"');
+}
+
 /// Contains information related to a batch.
 ///
 /// A batch is a collection of system that can safely run in parallel.
@@ -30,7 +170,11 @@ pub struct SystemInfo {
     pub name: &'static str,
     pub type_id: TypeId,
     pub borrow: Vec<TypeInfo>,
+    /// Systems this system has to run after because of ordering constraints.
+    pub after: Vec<SystemId>,
     pub conflict: Option<Conflict>,
+    /// Batches of the workload when this system is a workload nested with `WorkloadBuilder::with_workload`.
+    pub workload: Option<WorkloadInfo>,
 }
 
 /// Pinpoints the type and system that made a system unable to get into a batch.
@@ -41,6 +185,10 @@ pub enum Conflict {
         type_info: TypeInfo,
     },
     NotSendSync,
+    /// The system has to run after `system`.
+    Ordering {
+        system: SystemId,
+    },
 }
 
 /// Identify a system.
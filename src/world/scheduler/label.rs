@@ -0,0 +1,45 @@
+use crate::type_id::TypeId;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::any::type_name;
+
+/// Identifies one or multiple systems of a workload.  
+/// Used to order systems with [`WorkloadBuilder::before`] and [`WorkloadBuilder::after`].
+///
+/// A label is either created from a system, using the same tuple given to [`WorkloadBuilder::with_system`],
+/// or from a name given to any number of systems with [`WorkloadBuilder::label`].
+///
+/// [`WorkloadBuilder::before`]: struct.WorkloadBuilder.html#method.before
+/// [`WorkloadBuilder::after`]: struct.WorkloadBuilder.html#method.after
+/// [`WorkloadBuilder::with_system`]: struct.WorkloadBuilder.html#method.with_system
+/// [`WorkloadBuilder::label`]: struct.WorkloadBuilder.html#method.label
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Label {
+    /// `TypeId` and name of the system's function.
+    System(TypeId, &'static str),
+    Name(Cow<'static, str>),
+}
+
+impl<S, F: 'static> From<(S, F)> for Label {
+    fn from(_: (S, F)) -> Self {
+        Label::System(TypeId::of::<F>(), type_name::<F>())
+    }
+}
+
+impl From<&'static str> for Label {
+    fn from(name: &'static str) -> Self {
+        Label::Name(name.into())
+    }
+}
+
+impl From<String> for Label {
+    fn from(name: String) -> Self {
+        Label::Name(name.into())
+    }
+}
+
+impl From<Cow<'static, str>> for Label {
+    fn from(name: Cow<'static, str>) -> Self {
+        Label::Name(name)
+    }
+}
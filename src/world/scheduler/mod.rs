@@ -1,7 +1,15 @@
 mod builder;
+#[cfg(feature = "std")]
+pub(crate) mod current_system;
+mod fixed_timestep;
 pub mod info;
+mod label;
+#[cfg(feature = "std")]
+pub mod profiling;
 
 pub use builder::{Workload, WorkloadBuilder};
+pub use fixed_timestep::{FixedTimestep, FixedTimesteps};
+pub use label::Label;
 
 pub(crate) use info::TypeInfo;
 
@@ -10,8 +18,13 @@ use crate::type_id::TypeId;
 use crate::World;
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use hashbrown::HashMap;
+use info::WorkloadInfo;
+#[cfg(feature = "std")]
+use profiling::Profiler;
 
 /// List of indexes into both systems and system_names
 #[derive(Default)]
@@ -19,6 +32,32 @@ use hashbrown::HashMap;
 pub(super) struct Batches {
     pub(super) parallel: Vec<Vec<usize>>,
     pub(super) sequential: Vec<usize>,
+    /// indexes into both conditions and condition_names
+    pub(super) run_if: Vec<usize>,
+}
+
+/// Borrowed by views depending on the system borrowing them: [`Commands`], [`EventReader`] and [`EventWriter`].  
+/// It isn't a storage and doesn't show up in workload infos.
+///
+/// [`Commands`]: ../struct.Commands.html
+/// [`EventReader`]: ../struct.EventReader.html
+/// [`EventWriter`]: ../struct.EventWriter.html
+pub(crate) struct SystemContext;
+
+impl SystemContext {
+    pub(crate) fn type_info() -> TypeInfo {
+        TypeInfo {
+            name: core::any::type_name::<SystemContext>(),
+            mutability: crate::borrow::Mutability::Shared,
+            storage_id: crate::storage::StorageId::of::<SystemContext>(),
+            is_send: true,
+            is_sync: true,
+        }
+    }
+    /// Returns `true` if `type_info` is `SystemContext`'s.
+    pub(crate) fn is(type_info: &TypeInfo) -> bool {
+        type_info.storage_id == crate::storage::StorageId::of::<SystemContext>()
+    }
 }
 
 // systems are stored in an array to easily find if a system was already added
@@ -27,13 +66,27 @@ pub(super) struct Batches {
 // a batch lists systems that can run in parallel
 #[allow(clippy::type_complexity)]
 pub(crate) struct Scheduler {
-    pub(super) systems: Vec<Box<dyn Fn(&World) -> Result<(), error::Run> + Send + Sync + 'static>>,
+    pub(super) systems:
+        Vec<Box<dyn Fn(&World) -> Result<(), error::RunWorkload> + Send + Sync + 'static>>,
     pub(super) system_names: Vec<&'static str>,
     // system's `TypeId` to an index into both systems and system_names
     lookup_table: HashMap<TypeId, usize>,
+    pub(super) conditions:
+        Vec<Box<dyn Fn(&World) -> Result<bool, error::Run> + Send + Sync + 'static>>,
+    pub(super) condition_names: Vec<&'static str>,
     /// workload name to list of "batches"
     workloads: HashMap<Cow<'static, str>, Batches>,
-    default: Cow<'static, str>,
+    /// workload name to a unique id, used to count each workload's events runs
+    workload_ids: HashMap<Cow<'static, str>, usize>,
+    /// indexed by workload id, `true` if a system of the workload might borrow a view depending on the system running it
+    /// the workload then tracks the system running on each thread and counts its events runs
+    system_contexts: Vec<bool>,
+    /// workload name to all the types borrowed by the workload and its info
+    /// used to schedule workloads nested in other workloads
+    workload_infos: HashMap<Cow<'static, str>, (Vec<TypeInfo>, WorkloadInfo)>,
+    pub(super) default: Cow<'static, str>,
+    #[cfg(feature = "std")]
+    pub(super) profiler: Option<Arc<dyn Profiler>>,
 }
 
 impl Default for Scheduler {
@@ -42,8 +95,15 @@ impl Default for Scheduler {
             systems: Vec::new(),
             system_names: Vec::new(),
             lookup_table: HashMap::new(),
+            conditions: Vec::new(),
+            condition_names: Vec::new(),
             workloads: HashMap::new(),
+            workload_ids: HashMap::new(),
+            system_contexts: Vec::new(),
+            workload_infos: HashMap::new(),
             default: "".into(),
+            #[cfg(feature = "std")]
+            profiler: None,
         }
     }
 }
@@ -67,6 +127,13 @@ impl Scheduler {
             Err(error::RunWorkload::MissingWorkload)
         }
     }
+    pub(super) fn workload_id(&self, name: &str) -> usize {
+        self.workload_ids[name]
+    }
+    /// Returns `true` if a system of the workload might borrow a view depending on the system running it.
+    pub(super) fn has_system_context(&self, workload_id: usize) -> bool {
+        self.system_contexts[workload_id]
+    }
     pub(super) fn default_workload(&self) -> &Batches {
         &self.workloads[&self.default]
     }
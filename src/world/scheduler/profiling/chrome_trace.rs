@@ -0,0 +1,252 @@
+use super::{BatchEvent, Profiler, SystemEvent};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+/// [`Profiler`] recording workload runs in Chrome's `trace_event` format.
+///
+/// Each thread is a track, each system run is a slice and the start of each batch is a marker.
+/// Nested workloads are slices containing the slices of their systems.
+/// The JSON can be opened in `about:tracing` or [Perfetto](https://ui.perfetto.dev).
+///
+/// ### Example
+/// ```
+/// use shipyard::profiling::ChromeTrace;
+/// use shipyard::{system, View, ViewMut, Workload, World};
+/// use std::sync::Arc;
+///
+/// fn read(_: View<u32>) {}
+///
+/// fn write(_: ViewMut<u32>) {}
+///
+/// let world = World::new();
+///
+/// let trace = Arc::new(ChromeTrace::new());
+/// world.set_profiler(trace.clone());
+///
+/// Workload::builder("Systems")
+///     .with_system(system!(read))
+///     .with_system(system!(write))
+///     .add_to_world(&world)
+///     .unwrap();
+///
+/// world.run_workload("Systems");
+///
+/// let json = trace.to_json();
+/// assert!(json.starts_with("{\"traceEvents\":["));
+/// // trace.write_json(std::fs::File::create("trace.json").unwrap()).unwrap();
+/// ```
+///
+/// [`Profiler`]: trait.Profiler.html
+pub struct ChromeTrace {
+    start: Instant,
+    state: Mutex<TraceState>,
+}
+
+#[derive(Default)]
+struct TraceState {
+    /// Threads in order of appearance, their position is used as track id.
+    threads: Vec<(ThreadId, Option<String>)>,
+    running: HashMap<(ThreadId, &'static str), Duration>,
+    events: Vec<TraceEvent>,
+}
+
+enum TraceEvent {
+    System {
+        name: &'static str,
+        workload: String,
+        batch: usize,
+        thread: usize,
+        start: Duration,
+        duration: Duration,
+    },
+    Batch {
+        workload: String,
+        batch: usize,
+        thread: usize,
+        start: Duration,
+    },
+}
+
+impl TraceState {
+    /// Returns the track id of the current thread.
+    fn thread(&mut self, thread: ThreadId) -> usize {
+        match self.threads.iter().position(|&(id, _)| id == thread) {
+            Some(index) => index,
+            None => {
+                // the profiler is called from the thread running the system
+                let name = std::thread::current().name().map(String::from);
+                self.threads.push((thread, name));
+                self.threads.len() - 1
+            }
+        }
+    }
+}
+
+impl Default for ChromeTrace {
+    fn default() -> Self {
+        ChromeTrace::new()
+    }
+}
+
+impl ChromeTrace {
+    /// Creates an empty trace, timestamps are relative to its creation.
+    pub fn new() -> Self {
+        ChromeTrace {
+            start: Instant::now(),
+            state: Mutex::new(TraceState::default()),
+        }
+    }
+    /// Forgets all recorded events.
+    pub fn clear(&self) {
+        let mut state = self.state.lock();
+        state.running.clear();
+        state.events.clear();
+    }
+    /// Returns the recorded events as a `trace_event` JSON object.
+    pub fn to_json(&self) -> String {
+        let state = self.state.lock();
+        let mut json = String::from("{\"traceEvents\":[");
+        let mut is_first = true;
+
+        for (thread, (_, name)) in state.threads.iter().enumerate() {
+            let name = match name {
+                Some(name) => name.clone(),
+                None => alloc::format!("thread {}", thread),
+            };
+
+            separator(&mut json, &mut is_first);
+            json.push_str("{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":");
+            let _ = write!(json, "{}", thread);
+            json.push_str(",\"args\":{\"name\":");
+            push_json_str(&mut json, &name);
+            json.push_str("}}");
+        }
+
+        for event in &state.events {
+            separator(&mut json, &mut is_first);
+
+            match event {
+                TraceEvent::System {
+                    name,
+                    workload,
+                    batch,
+                    thread,
+                    start,
+                    duration,
+                } => {
+                    json.push_str("{\"name\":");
+                    push_json_str(&mut json, name);
+                    json.push_str(",\"cat\":\"system\",\"ph\":\"X\",\"pid\":0");
+                    let _ = write!(
+                        json,
+                        ",\"tid\":{},\"ts\":{},\"dur\":{}",
+                        thread,
+                        micros(*start),
+                        micros(*duration)
+                    );
+                    json.push_str(",\"args\":{\"workload\":");
+                    push_json_str(&mut json, workload);
+                    let _ = write!(json, ",\"batch\":{}}}}}", batch);
+                }
+                TraceEvent::Batch {
+                    workload,
+                    batch,
+                    thread,
+                    start,
+                } => {
+                    let _ = write!(json, "{{\"name\":\"batch {}\"", batch);
+                    json.push_str(",\"cat\":\"batch\",\"ph\":\"i\",\"s\":\"t\",\"pid\":0");
+                    let _ = write!(json, ",\"tid\":{},\"ts\":{}", thread, micros(*start));
+                    json.push_str(",\"args\":{\"workload\":");
+                    push_json_str(&mut json, workload);
+                    json.push_str("}}");
+                }
+            }
+        }
+
+        json.push_str("]}");
+        json
+    }
+    /// Writes the recorded events as a `trace_event` JSON object.
+    pub fn write_json<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(self.to_json().as_bytes())
+    }
+}
+
+impl Profiler for ChromeTrace {
+    fn begin(&self, event: &SystemEvent<'_>) {
+        let start = self.start.elapsed();
+
+        self.state
+            .lock()
+            .running
+            .insert((event.thread, event.system), start);
+    }
+    fn end(&self, event: &SystemEvent<'_>) {
+        let end = self.start.elapsed();
+        let mut state = self.state.lock();
+
+        let start = match state.running.remove(&(event.thread, event.system)) {
+            Some(start) => start,
+            None => return,
+        };
+        let thread = state.thread(event.thread);
+
+        state.events.push(TraceEvent::System {
+            name: event.system,
+            workload: event.workload.into(),
+            batch: event.batch,
+            thread,
+            start,
+            duration: end - start,
+        });
+    }
+    fn batch(&self, event: &BatchEvent<'_>) {
+        let start = self.start.elapsed();
+        let mut state = self.state.lock();
+        let thread = state.thread(event.thread);
+
+        state.events.push(TraceEvent::Batch {
+            workload: event.workload.into(),
+            batch: event.batch,
+            thread,
+            start,
+        });
+    }
+}
+
+fn separator(json: &mut String, is_first: &mut bool) {
+    if *is_first {
+        *is_first = false;
+    } else {
+        json.push(',');
+    }
+}
+
+/// Trace timestamps are in microseconds.
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000.
+}
+
+fn push_json_str(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
@@ -0,0 +1,171 @@
+//! Hooks called around each system when running workloads.
+
+mod chrome_trace;
+
+pub use chrome_trace::ChromeTrace;
+
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+/// Receives an event before and after each system of a workload runs
+/// and when each batch starts.
+///
+/// Install it with [`World::set_profiler`].
+/// With the `parallel` feature, systems of the same batch run on multiple threads
+/// and the hooks can be called concurrently.
+///
+/// [`World::set_profiler`]: ../struct.World.html#method.set_profiler
+pub trait Profiler: Send + Sync {
+    /// Called right before the system runs.
+    fn begin(&self, event: &SystemEvent<'_>);
+    /// Called right after the system ran, even if it returned an error.
+    fn end(&self, event: &SystemEvent<'_>);
+    /// Called before the systems of a batch start running.
+    fn batch(&self, _event: &BatchEvent<'_>) {}
+}
+
+/// Describes a system about to run or that just ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemEvent<'a> {
+    /// Name of the workload being run.
+    pub workload: &'a str,
+    /// Name of the system, nested workloads use their name.
+    pub system: &'static str,
+    /// Index of the batch the system is part of.
+    /// Without the `parallel` feature systems run one after the other
+    /// and this is the position of the system in the workload.
+    pub batch: usize,
+    /// Thread running the system.
+    pub thread: ThreadId,
+}
+
+/// Describes a batch about to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchEvent<'a> {
+    /// Name of the workload being run.
+    pub workload: &'a str,
+    /// Index of the batch in the workload.
+    /// Without the `parallel` feature each system is its own batch.
+    pub batch: usize,
+    /// Thread starting the batch.
+    pub thread: ThreadId,
+}
+
+/// Durations of all the runs of a system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemTiming {
+    pub min: Duration,
+    pub max: Duration,
+    pub total: Duration,
+    pub count: u32,
+}
+
+impl SystemTiming {
+    /// Returns the average duration of the system.
+    pub fn avg(&self) -> Duration {
+        self.total / self.count
+    }
+}
+
+/// [`Profiler`] measuring how long each system takes to run.
+///
+/// Systems are identified by name, runs of the same system from different workloads are aggregated.
+///
+/// ### Example
+/// ```
+/// use shipyard::profiling::TimingCollector;
+/// use shipyard::{system, UniqueViewMut, Workload, World};
+/// use std::sync::Arc;
+///
+/// fn increment(mut count: UniqueViewMut<u32>) {
+///     *count += 1;
+/// }
+///
+/// let world = World::new();
+/// world.add_unique(0u32);
+///
+/// let timings = Arc::new(TimingCollector::new());
+/// world.set_profiler(timings.clone());
+///
+/// Workload::builder("Increment")
+///     .with_system(system!(increment))
+///     .add_to_world(&world)
+///     .unwrap();
+///
+/// world.run_default();
+/// world.run_default();
+///
+/// let (name, timing) = timings.timings().pop().unwrap();
+/// assert!(name.ends_with("increment"));
+/// assert_eq!(timing.count, 2);
+/// assert!(timing.min <= timing.avg() && timing.avg() <= timing.max);
+/// ```
+///
+/// [`Profiler`]: trait.Profiler.html
+#[derive(Default)]
+pub struct TimingCollector {
+    running: Mutex<HashMap<(ThreadId, &'static str), Instant>>,
+    timings: Mutex<HashMap<&'static str, SystemTiming>>,
+}
+
+impl TimingCollector {
+    /// Creates an empty `TimingCollector`.
+    pub fn new() -> Self {
+        TimingCollector::default()
+    }
+    /// Returns the timings of the system named `system`, if it ran at least once.
+    pub fn timing(&self, system: &str) -> Option<SystemTiming> {
+        self.timings.lock().get(system).copied()
+    }
+    /// Returns the timings of all systems that ran at least once, sorted by name.
+    pub fn timings(&self) -> Vec<(&'static str, SystemTiming)> {
+        let mut timings: Vec<_> = self
+            .timings
+            .lock()
+            .iter()
+            .map(|(&name, &timing)| (name, timing))
+            .collect();
+
+        timings.sort_unstable_by_key(|&(name, _)| name);
+
+        timings
+    }
+    /// Forgets all timings.
+    pub fn clear(&self) {
+        self.timings.lock().clear();
+    }
+}
+
+impl Profiler for TimingCollector {
+    fn begin(&self, event: &SystemEvent<'_>) {
+        self.running
+            .lock()
+            .insert((event.thread, event.system), Instant::now());
+    }
+    fn end(&self, event: &SystemEvent<'_>) {
+        let start = match self.running.lock().remove(&(event.thread, event.system)) {
+            Some(start) => start,
+            None => return,
+        };
+        let duration = start.elapsed();
+
+        self.timings
+            .lock()
+            .entry(event.system)
+            .and_modify(|timing| {
+                timing.min = timing.min.min(duration);
+                timing.max = timing.max.max(duration);
+                timing.total += duration;
+                timing.count += 1;
+            })
+            .or_insert(SystemTiming {
+                min: duration,
+                max: duration,
+                total: duration,
+                count: 1,
+            });
+    }
+}
@@ -0,0 +1,135 @@
+use core::any::type_name;
+use shipyard::error;
+use shipyard::hierarchy::{Child, Hierarchy, Parent};
+use shipyard::*;
+use std::sync::{Arc, Mutex};
+
+struct NotClone;
+
+#[test]
+fn clone() {
+    let world = World::new();
+    let mut all_storages = world.try_borrow::<AllStoragesViewMut>().unwrap();
+
+    all_storages
+        .try_run(|mut u32s: ViewMut<u32>, mut usizes: ViewMut<usize>| {
+            u32s.enable_cloning();
+            usizes.enable_cloning();
+        })
+        .unwrap();
+
+    let entity = all_storages.add_entity((0u32, 1usize));
+    let clone = all_storages.try_clone_entity(entity).unwrap();
+
+    assert_ne!(entity, clone);
+    all_storages
+        .try_run(|u32s: View<u32>, usizes: View<usize>| {
+            assert_eq!(u32s.get(clone), Ok(&0));
+            assert_eq!(usizes.get(clone), Ok(&1));
+            assert_eq!(u32s.len(), 2);
+        })
+        .unwrap();
+}
+
+#[test]
+fn not_cloneable() {
+    let world = World::new();
+    let mut all_storages = world.try_borrow::<AllStoragesViewMut>().unwrap();
+
+    all_storages
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .enable_cloning();
+
+    let entity = all_storages.add_entity((0u32, NotClone, 1usize));
+
+    match all_storages.try_clone_entity(entity) {
+        Err(error::CloneEntity::NotCloneable(mut names)) => {
+            names.sort_unstable();
+
+            let mut expected = vec![type_name::<NotClone>(), type_name::<usize>()];
+            expected.sort_unstable();
+
+            assert_eq!(names, expected);
+        }
+        _ => panic!(),
+    }
+
+    // nothing was cloned
+    assert_eq!(all_storages.try_borrow::<View<u32>>().unwrap().len(), 1);
+
+    all_storages
+        .try_borrow::<ViewMut<NotClone>>()
+        .unwrap()
+        .skip_cloning();
+    all_storages
+        .try_borrow::<ViewMut<usize>>()
+        .unwrap()
+        .skip_cloning();
+
+    let clone = all_storages.try_clone_entity(entity).unwrap();
+
+    all_storages
+        .try_run(|u32s: View<u32>, not_clones: View<NotClone>| {
+            assert_eq!(u32s.get(clone), Ok(&0));
+            assert!(!not_clones.contains(clone));
+        })
+        .unwrap();
+
+    all_storages.delete_entity(entity);
+    assert_eq!(
+        all_storages.try_clone_entity(entity),
+        Err(error::CloneEntity::EntityIsNotAlive)
+    );
+}
+
+#[test]
+fn hierarchy() {
+    let world = World::new();
+    let mut all_storages = world.try_borrow::<AllStoragesViewMut>().unwrap();
+
+    let (root, child) = all_storages
+        .try_run(
+            |mut hierarchy: (EntitiesViewMut, ViewMut<Parent>, ViewMut<Child>)| {
+                let root = hierarchy.0.add_entity((), ());
+                let child = hierarchy.attach_new(root);
+
+                (root, child)
+            },
+        )
+        .unwrap();
+
+    let root_clone = all_storages.try_clone_entity(root).unwrap();
+    let child_clone = all_storages.try_clone_entity(child).unwrap();
+
+    all_storages
+        .try_run(|parents: View<Parent>, children: View<Child>| {
+            assert!(!parents.contains(root_clone));
+            assert!(!children.contains(child_clone));
+            assert_eq!(parents[root].num_children(), 1);
+        })
+        .unwrap();
+}
+
+#[test]
+fn on_insert_global() {
+    let world = World::new();
+    let mut all_storages = world.try_borrow::<AllStoragesViewMut>().unwrap();
+
+    let inserted = Arc::new(Mutex::new(Vec::new()));
+    let callback_inserted = inserted.clone();
+
+    all_storages
+        .try_run(|mut u32s: ViewMut<u32>| {
+            u32s.enable_cloning();
+            u32s.on_insert_global(move |entity, _, _| {
+                callback_inserted.lock().unwrap().push(entity);
+            });
+        })
+        .unwrap();
+
+    let entity = all_storages.add_entity((0u32,));
+    let clone = all_storages.try_clone_entity(entity).unwrap();
+
+    assert_eq!(*inserted.lock().unwrap(), vec![entity, clone]);
+}
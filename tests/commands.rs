@@ -0,0 +1,188 @@
+use shipyard::*;
+
+fn spawn_first(mut commands: Commands) {
+    for _ in 0..100 {
+        commands.add_entity((0u32,));
+    }
+}
+
+fn spawn_second(mut commands: Commands) {
+    for _ in 0..100 {
+        commands.add_entity((1u32,));
+    }
+}
+
+fn check(u32s: View<u32>) {
+    assert_eq!(u32s.len(), 200);
+}
+
+#[test]
+fn deterministic_order() {
+    let world = World::new();
+
+    let info = Workload::builder("Spawn")
+        .try_with_system(system!(spawn_first))
+        .unwrap()
+        .try_with_system(system!(spawn_second))
+        .unwrap()
+        .try_with_system(system!(check))
+        .unwrap()
+        .after(system!(spawn_second))
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    assert_eq!(info.batch_info[0].systems.len(), 2);
+    // Commands doesn't borrow any storage
+    assert!(info.batch_info[0].systems[0].borrow.is_empty());
+
+    world.try_run_default().unwrap();
+
+    let (entities, u32s) = world.try_borrow::<(EntitiesView, View<u32>)>().unwrap();
+    for (entity, &i) in u32s.iter().with_id() {
+        assert!(entities.is_alive(entity));
+        assert_eq!(i, (entity.index() >= 100) as u32);
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn par_spawn_second(commands: Commands) {
+    use rayon::prelude::*;
+
+    // clones keep the position of the system, whatever thread they're used on
+    (0..100)
+        .into_par_iter()
+        .for_each_with(commands, |commands, _| commands.add_entity((1u32,)));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn deterministic_order_clones() {
+    let world = World::new();
+
+    Workload::builder("Spawn")
+        .try_with_system(system!(spawn_first))
+        .unwrap()
+        .try_with_system(system!(par_spawn_second))
+        .unwrap()
+        .try_with_system(system!(check))
+        .unwrap()
+        .after(system!(par_spawn_second))
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_default().unwrap();
+
+    let u32s = world.try_borrow::<View<u32>>().unwrap();
+    for (entity, &i) in u32s.iter().with_id() {
+        assert_eq!(i, (entity.index() >= 100) as u32);
+    }
+}
+
+#[test]
+fn operations() {
+    let mut world = World::new();
+
+    let entity0 = world.add_entity((0u32, 0usize));
+    let entity1 = world.add_entity((1u32,));
+
+    world
+        .try_run(|mut commands: Commands| {
+            commands.remove::<(usize,)>(entity0);
+            commands.add_component(entity1, (1usize,));
+            commands.delete_entity(entity0);
+            commands.push(|all_storages: &mut AllStorages| {
+                all_storages.add_entity((2u32,));
+            });
+
+            assert_eq!(commands.len(), 4);
+        })
+        .unwrap();
+
+    // commands are only recorded until applied
+    world
+        .try_run(|u32s: View<u32>, usizes: View<usize>| {
+            assert_eq!(u32s.len(), 2);
+            assert_eq!(usizes.len(), 1);
+        })
+        .unwrap();
+
+    world.try_apply_commands().unwrap();
+
+    world
+        .try_run(|u32s: View<u32>, usizes: View<usize>| {
+            assert_eq!(u32s.len(), 2);
+            assert_eq!(usizes.len(), 1);
+            assert_eq!(u32s.get(entity1), Ok(&1));
+            assert_eq!(usizes.get(entity1), Ok(&1));
+            assert!(u32s.get(entity0).is_err());
+        })
+        .unwrap();
+}
+
+#[test]
+fn nested_workload() {
+    let world = World::new();
+
+    Workload::builder("Spawn")
+        .try_with_system(system!(spawn_second))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+    Workload::builder("Game")
+        .try_with_system(system!(spawn_first))
+        .unwrap()
+        .with_workload("Spawn")
+        .try_with_system(system!(check))
+        .unwrap()
+        .after("Spawn")
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_workload("Game").unwrap();
+
+    world
+        .try_run(|u32s: View<u32>| {
+            for (entity, &i) in u32s.iter().with_id() {
+                assert_eq!(i, (entity.index() >= 100) as u32);
+            }
+        })
+        .unwrap();
+}
+
+fn reserve_first(entities: EntitiesView, mut commands: Commands) {
+    for _ in 0..100 {
+        let entity = entities.reserve_entity();
+        commands.add_component(entity, (0u32,));
+    }
+}
+
+fn reserve_second(entities: EntitiesView, mut commands: Commands) {
+    for _ in 0..100 {
+        let entity = entities.reserve_entity();
+        commands.add_component(entity, (1u32,));
+    }
+}
+
+#[test]
+fn reserve_entity() {
+    let world = World::new();
+
+    let info = Workload::builder("Reserve")
+        .try_with_system(system!(reserve_first))
+        .unwrap()
+        .try_with_system(system!(reserve_second))
+        .unwrap()
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    assert_eq!(info.batch_info[0].systems.len(), 2);
+
+    world.try_run_default().unwrap();
+
+    let (entities, u32s) = world.try_borrow::<(EntitiesView, View<u32>)>().unwrap();
+    assert_eq!(u32s.len(), 200);
+    assert_eq!(entities.iter().count(), 200);
+    for (entity, _) in u32s.iter().with_id() {
+        assert!(entities.is_alive(entity));
+    }
+}
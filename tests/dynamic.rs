@@ -0,0 +1,254 @@
+use core::alloc::Layout;
+use shipyard::error;
+use shipyard::*;
+use std::sync::Arc;
+
+const HEALTH: u64 = 0;
+const SHARED: u64 = 1;
+
+unsafe fn drop_arc(component: *mut u8) {
+    core::ptr::drop_in_place(component as *mut Arc<()>);
+}
+
+fn insert_u32(storage: &mut DynamicSparseSet, entity: EntityId, mut value: u32) {
+    unsafe { storage.insert(entity, &mut value as *mut u32 as *mut u8) };
+}
+
+fn insert_arc(storage: &mut DynamicSparseSet, entity: EntityId, value: &Arc<()>) {
+    let mut value = value.clone();
+    unsafe { storage.insert(entity, &mut value as *mut Arc<()> as *mut u8) };
+    core::mem::forget(value);
+}
+
+#[test]
+fn insert_get_delete() {
+    let mut world = World::new();
+    world.register_dynamic_component(
+        SHARED,
+        ComponentDescriptor::new("shared", Layout::new::<Arc<()>>(), Some(drop_arc)),
+    );
+
+    let arc = Arc::new(());
+    let entity0 = world.add_entity(());
+    let entity1 = world.add_entity(());
+
+    {
+        let mut shared = world.borrow_dynamic_mut(SHARED);
+        insert_arc(&mut shared, entity0, &arc);
+        insert_arc(&mut shared, entity1, &arc);
+        // replaces and drops the previous component
+        insert_arc(&mut shared, entity1, &arc);
+
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared.descriptor().name(), "shared");
+        assert!(shared.get(entity1).is_some());
+        assert_eq!(Arc::strong_count(&arc), 3);
+
+        assert!(shared.delete(entity0));
+        assert!(!shared.delete(entity0));
+        assert!(!shared.contains(entity0));
+        assert_eq!(shared.id_at(0), Some(entity1));
+        assert_eq!(Arc::strong_count(&arc), 2);
+    }
+
+    world.delete_entity(entity1);
+    assert!(world.borrow_dynamic(SHARED).is_empty());
+    assert_eq!(Arc::strong_count(&arc), 1);
+
+    let entity2 = world.add_entity(());
+    insert_arc(&mut world.borrow_dynamic_mut(SHARED), entity2, &arc);
+    drop(world);
+    assert_eq!(Arc::strong_count(&arc), 1);
+}
+
+#[test]
+fn missing_and_borrowed() {
+    let world = World::new();
+
+    assert!(matches!(
+        world.try_borrow_dynamic(HEALTH),
+        Err(error::GetStorage::MissingStorage(_))
+    ));
+
+    world.register_dynamic_component(HEALTH, ComponentDescriptor::of::<u32>("health"));
+
+    let _healths = world.borrow_dynamic_mut(HEALTH);
+    assert!(matches!(
+        world.try_borrow_dynamic(HEALTH),
+        Err(error::GetStorage::StorageBorrow(_))
+    ));
+}
+
+#[test]
+fn iter_with_typed_views() {
+    let world = World::new();
+    world.register_dynamic_component(HEALTH, ComponentDescriptor::of::<u32>("health"));
+
+    let (entity0, entity1) = world.run(
+        |mut entities: EntitiesViewMut, mut usizes: ViewMut<usize>| {
+            (
+                entities.add_entity(&mut usizes, 10usize),
+                entities.add_entity(&mut usizes, 20usize),
+            )
+        },
+    );
+    let entity2 = world.borrow::<EntitiesViewMut>().add_entity((), ());
+
+    let mut healths = world.borrow_dynamic_mut(HEALTH);
+    insert_u32(&mut healths, entity1, 1);
+    insert_u32(&mut healths, entity2, 2);
+
+    let usizes = world.borrow::<View<usize>>();
+    for (&x, health) in (&usizes, &mut healths).iter() {
+        unsafe { *(health as *mut u32) += x as u32 };
+    }
+
+    let mut iter = (&usizes, &healths).iter().with_id();
+    let (id, (&x, health)) = iter.next().unwrap();
+    assert_eq!(id, entity1);
+    assert_eq!(x, 20);
+    assert_eq!(unsafe { *(health as *const u32) }, 21);
+    assert!(iter.next().is_none());
+
+    assert!(!healths.contains(entity0));
+    assert_eq!(unsafe { *(healths.get(entity2).unwrap() as *const u32) }, 2);
+    assert_eq!(healths.iter().count(), 2);
+}
+
+#[test]
+fn workload_borrow_info() {
+    fn script(world: &World) -> Result<(), error::Run> {
+        let mut healths = world.try_borrow_dynamic_mut(HEALTH)?;
+        let entity = world.try_borrow::<EntitiesViewMut>()?.add_entity((), ());
+        insert_u32(&mut healths, entity, 0);
+
+        Ok(())
+    }
+
+    let world = World::new();
+    world.register_dynamic_component(HEALTH, ComponentDescriptor::of::<u32>("health"));
+
+    let info = Workload::builder("Scripts")
+        .with_system((script, || {}))
+        .with_dynamic_borrow(HEALTH, "health", Mutability::Exclusive)
+        .with_system((script, || {}))
+        .with_dynamic_borrow(HEALTH, "health", Mutability::Exclusive)
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    assert_eq!(info.batch_info.len(), 2);
+    assert_eq!(
+        info.batch_info[0].systems[0].borrow[0].storage_id,
+        StorageId::Custom(HEALTH)
+    );
+    assert_eq!(info.batch_info[0].systems[0].borrow[0].name, "health");
+
+    assert_eq!(
+        Workload::builder("Invalid")
+            .with_system((script, || {}))
+            .with_dynamic_borrow(HEALTH, "health", Mutability::Shared)
+            .try_with_dynamic_borrow(HEALTH, "health", Mutability::Exclusive)
+            .err(),
+        Some(error::InvalidSystem::MultipleViews)
+    );
+
+    world.run_default();
+    assert_eq!(world.borrow_dynamic(HEALTH).len(), 2);
+}
+
+#[test]
+fn move_and_merge() {
+    let mut world = World::new();
+    let mut other = World::new();
+    world.register_dynamic_component(HEALTH, ComponentDescriptor::of::<u32>("health"));
+
+    let entity = world.add_entity(());
+    insert_u32(&mut world.borrow_dynamic_mut(HEALTH), entity, 5);
+
+    let moved = world.move_entities_to(&mut other, &[entity]);
+    assert!(world.borrow_dynamic(HEALTH).is_empty());
+    assert_eq!(
+        unsafe { *(other.borrow_dynamic(HEALTH).get(moved[0].1).unwrap() as *const u32) },
+        5
+    );
+
+    let merged = world.merge(other);
+    assert_eq!(
+        unsafe { *(world.borrow_dynamic(HEALTH).get(merged[0].1).unwrap() as *const u32) },
+        5
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "Dynamic component \"health\" doesn't have the same layout in both Worlds."
+)]
+fn move_layout_mismatch() {
+    let mut world = World::new();
+    let mut other = World::new();
+    world.register_dynamic_component(HEALTH, ComponentDescriptor::of::<u32>("health"));
+    other.register_dynamic_component(HEALTH, ComponentDescriptor::of::<u64>("health"));
+
+    let entity = world.add_entity(());
+    insert_u32(&mut world.borrow_dynamic_mut(HEALTH), entity, 5);
+
+    world.move_entities_to(&mut other, &[entity]);
+}
+
+#[test]
+fn merge_panicking_drop() {
+    unsafe fn drop_then_panic(component: *mut u8) {
+        drop_arc(component);
+        panic!("drop");
+    }
+
+    let shared = Arc::new(());
+    let mut world = World::new();
+    let chunk = World::new();
+    chunk.register_dynamic_component(
+        SHARED,
+        ComponentDescriptor::new("shared", Layout::new::<Arc<()>>(), Some(drop_then_panic)),
+    );
+
+    chunk.run(|mut entities: EntitiesViewMut| {
+        let mut storage = chunk.borrow_dynamic_mut(SHARED);
+        let dead = entities.add_entity((), ());
+        insert_arc(&mut storage, dead, &shared);
+        insert_arc(&mut storage, entities.add_entity((), ()), &shared);
+
+        // the component of `dead` is dropped by the merge
+        entities.delete_unchecked(dead);
+    });
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| world.merge(chunk)));
+    assert!(result.is_err());
+
+    // the dropped component isn't dropped again, the other one is leaked
+    assert_eq!(Arc::strong_count(&shared), 2);
+}
+
+#[test]
+fn clone_entity() {
+    let mut world = World::new();
+    world.register_dynamic_component(HEALTH, ComponentDescriptor::of::<u32>("health"));
+
+    let entity = world.add_entity((0u32,));
+    insert_u32(&mut world.borrow_dynamic_mut(HEALTH), entity, 5);
+    world.borrow::<ViewMut<u32>>().enable_cloning();
+
+    assert_eq!(
+        world
+            .borrow::<AllStoragesViewMut>()
+            .try_clone_entity(entity),
+        Err(error::CloneEntity::NotCloneable(vec!["health"]))
+    );
+
+    world.borrow_dynamic_mut(HEALTH).skip_cloning();
+    let clone = world
+        .borrow::<AllStoragesViewMut>()
+        .try_clone_entity(entity)
+        .unwrap();
+
+    assert_eq!(world.borrow::<View<u32>>().get(clone), Ok(&0));
+    assert!(!world.borrow_dynamic(HEALTH).contains(clone));
+}
@@ -0,0 +1,185 @@
+use shipyard::*;
+
+struct Hit(u32);
+
+#[derive(Default)]
+struct Received(Vec<u32>);
+
+fn send(mut hits: EventWriter<Hit>, mut sent: UniqueViewMut<u32>) {
+    hits.send(Hit(*sent));
+    *sent += 1;
+}
+
+fn receive(mut hits: EventReader<Hit>, mut received: UniqueViewMut<Received>) {
+    received.0.extend(hits.iter().map(|hit| hit.0));
+}
+
+#[test]
+fn double_buffered() {
+    let world = World::new();
+    world.add_unique(0u32);
+    world.add_unique(Received::default());
+
+    // the reader runs first, it only sees the previous run's events
+    Workload::builder("Hits")
+        .with_system(system!(receive))
+        .with_system(system!(send))
+        .after(system!(receive))
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_default().unwrap();
+    world
+        .try_run(|received: UniqueView<Received>| assert!(received.0.is_empty()))
+        .unwrap();
+
+    world.try_run_default().unwrap();
+    world.try_run_default().unwrap();
+    world
+        .try_run(|received: UniqueView<Received>| assert_eq!(received.0, vec![0, 1]))
+        .unwrap();
+
+    // events are dropped after two runs
+    world.try_run_default().unwrap();
+    world.try_run_default().unwrap();
+    world
+        .try_run(|received: UniqueView<Received>| assert_eq!(received.0, vec![0, 1, 2, 3]))
+        .unwrap();
+
+    // readers outside of workloads have their own cursor
+    world
+        .try_run(|mut hits: EventReader<Hit>| {
+            assert_eq!(hits.iter().map(|hit| hit.0).collect::<Vec<_>>(), vec![3, 4]);
+        })
+        .unwrap();
+}
+
+#[test]
+fn expired() {
+    let world = World::new();
+
+    world
+        .try_run(|mut hits: EventWriter<Hit>| hits.send(Hit(0)))
+        .unwrap();
+
+    fn read(_: EventReader<Hit>) {}
+
+    fn count(mut count: UniqueViewMut<usize>) {
+        *count += 1;
+    }
+
+    world.try_add_unique(0usize).unwrap();
+    Workload::builder("Events")
+        .try_with_system(system!(read))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+    Workload::builder("Count")
+        .try_with_system(system!(count))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_default().unwrap();
+    world
+        .try_run(|hits: EventReader<Hit>| assert_eq!(hits.len(), 1))
+        .unwrap();
+
+    // workloads that can't borrow events don't count as a run
+    world.try_run_workload("Count").unwrap();
+    world.try_run_workload("Count").unwrap();
+    world
+        .try_run(|hits: EventReader<Hit>| assert_eq!(hits.len(), 1))
+        .unwrap();
+
+    world.try_run_default().unwrap();
+    world
+        .try_run(|hits: EventReader<Hit>| assert!(hits.is_empty()))
+        .unwrap();
+}
+
+fn count_first(mut hits: EventReader<Hit>, mut count: UniqueViewMut<usize>) {
+    *count += hits.iter().count();
+}
+
+fn count_second(mut hits: EventReader<Hit>, mut count: UniqueViewMut<u64>) {
+    *count += hits.iter().count() as u64;
+}
+
+#[test]
+fn multiple_readers() {
+    let world = World::new();
+    world.add_unique(0u32);
+    world.add_unique(0usize);
+    world.add_unique(0u64);
+
+    let info = Workload::builder("Hits")
+        .with_system(system!(send))
+        .with_system(system!(count_first))
+        .with_system(system!(count_second))
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    // the writer conflicts with the readers, the readers don't conflict with each other
+    assert_eq!(info.batch_info.len(), 2);
+    assert_eq!(info.batch_info[1].systems.len(), 2);
+
+    world.try_run_default().unwrap();
+    world.try_run_default().unwrap();
+
+    world
+        .try_run(|(first, second): (UniqueView<usize>, UniqueView<u64>)| {
+            assert_eq!(*first, 2);
+            assert_eq!(*second, 2);
+        })
+        .unwrap();
+}
+
+#[test]
+fn fixed_timestep() {
+    let world = World::new();
+    world.add_unique(0u32);
+    world.add_unique(Received::default());
+    world.add_unique(FixedTimestep::new(core::time::Duration::from_millis(10)));
+
+    Workload::builder("Fixed").add_to_world(&world).unwrap();
+    Workload::builder("Variable")
+        .with_system(system!(receive))
+        .with_system(system!(send))
+        .after(system!(receive))
+        .add_to_world(&world)
+        .unwrap();
+
+    // the fixed steps don't drop the events sent by the other workload
+    world
+        .try_run_fixed_timestep("Fixed", "Variable", core::time::Duration::from_millis(30))
+        .unwrap();
+    world
+        .try_run_fixed_timestep("Fixed", "Variable", core::time::Duration::from_millis(30))
+        .unwrap();
+
+    world
+        .try_run(|received: UniqueView<Received>| assert_eq!(received.0, vec![0]))
+        .unwrap();
+}
+
+#[test]
+fn same_system_twice() {
+    let world = World::new();
+    world.add_unique(0u32);
+    world.add_unique(Received::default());
+
+    Workload::builder("Hits")
+        .with_system(system!(send))
+        .with_system(system!(receive))
+        .with_system(system!(receive))
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_default().unwrap();
+
+    // each occurrence of the system has its own cursor
+    world
+        .try_run(|received: UniqueView<Received>| assert_eq!(received.0, vec![0, 0]))
+        .unwrap();
+}
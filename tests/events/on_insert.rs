@@ -24,51 +24,154 @@ fn on_insert() {
     assert_eq!(unsafe { &*E }, &[e0, e1, e3]);
 }
 
-// #[test]
-// fn on_insert_global() {
-//     static mut E: Vec<EntityId> = Vec::new();
+#[test]
+fn on_insert_global() {
+    static mut E: Vec<EntityId> = Vec::new();
 
-//     let world = World::new();
+    let world = World::new();
 
-//     let (mut entities, mut u32s) = world
-//         .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
-//         .unwrap();
+    let (mut entities, mut u32s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
+        .unwrap();
 
-//     u32s.on_insert_global(|entity, _, _| unsafe {
-//         E.push(entity);
-//     });
+    u32s.on_insert_global(|entity, _, _| unsafe {
+        E.push(entity);
+    });
 
-//     let e0 = entities.add_entity(&mut u32s, 0);
-//     let e1 = entities.add_entity(&mut u32s, 1);
+    let e0 = entities.add_entity(&mut u32s, 0);
+    let e1 = entities.add_entity(&mut u32s, 1);
 
-//     drop(u32s);
+    drop(u32s);
 
-//     assert_eq!(unsafe { &*E }, &[e0, e1]);
-// }
+    assert_eq!(unsafe { &*E }, &[e0, e1]);
+}
 
-// #[test]
-// fn on_insert_both() {
-//     static mut E: Vec<EntityId> = Vec::new();
+#[test]
+fn on_insert_both() {
+    static mut E: Vec<EntityId> = Vec::new();
 
-//     let world = World::new();
+    let world = World::new();
 
-//     let (mut entities, mut u32s) = world
-//         .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
-//         .unwrap();
+    let (mut entities, mut u32s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
+        .unwrap();
 
-//     u32s.on_insert(|entity, _| unsafe { E.push(entity) });
-//     u32s.on_insert_global(|entity, _, _| unsafe {
-//         E.push(entity);
-//     });
+    u32s.on_insert(|entity, _| unsafe { E.push(entity) });
+    u32s.on_insert_global(|entity, _, _| unsafe {
+        E.push(entity);
+    });
 
-//     let e0 = entities.add_entity(&mut u32s, 0);
-//     let e1 = entities.add_entity(&mut u32s, 1);
-//     let e3 = entities.add_entity((), ());
+    let e0 = entities.add_entity(&mut u32s, 0);
+    let e1 = entities.add_entity(&mut u32s, 1);
+    let e3 = entities.add_entity((), ());
 
-//     entities.add_component(e0, &mut u32s, 2);
-//     entities.add_component(e3, &mut u32s, 3);
+    entities.add_component(e0, &mut u32s, 2);
+    entities.add_component(e3, &mut u32s, 3);
+
+    drop(u32s);
+
+    assert_eq!(unsafe { &*E }, &[e0, e1, e3, e0, e1, e3]);
+}
+
+#[test]
+fn on_insert_global_all_storages() {
+    let mut world = World::new();
+
+    world
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .on_insert_global(|entity, u32s, all_storages| {
+            let (entities, mut usizes) = all_storages
+                .try_borrow::<(EntitiesView, ViewMut<usize>)>()
+                .unwrap();
+            entities.add_component(entity, &mut usizes, u32s[entity] as usize);
+        });
+
+    // runs at the end of the operation
+    let e0 = world.add_entity((0u32,));
+    assert_eq!(world.try_borrow::<View<usize>>().unwrap()[e0], 0);
+
+    let e1 = world.add_entity(());
+    world.add_component(e1, (1u32,)).unwrap();
+    assert_eq!(world.try_borrow::<View<usize>>().unwrap()[e1], 1);
+
+    // runs when the view is dropped
+    let e2 = {
+        let (mut entities, mut u32s) = world
+            .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
+            .unwrap();
+        let e2 = entities.add_entity(&mut u32s, 2);
+        assert!(!world.try_borrow::<View<usize>>().unwrap().contains(e2));
+
+        // the callback borrows `Entities`
+        drop(entities);
+        drop(u32s);
+        e2
+    };
+    assert_eq!(world.try_borrow::<View<usize>>().unwrap()[e2], 2);
+}
+
+#[test]
+fn capture_and_remove_callback() {
+    let mut world = World::new();
+
+    let inserted = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let local_inserted = inserted.clone();
+    let global_inserted = inserted.clone();
 
-//     drop(u32s);
+    let (local, global) = {
+        let mut u32s = world.try_borrow::<ViewMut<u32>>().unwrap();
 
-//     assert_eq!(unsafe { &*E }, &[e0, e1, e3, e0, e1, e3]);
-// }
+        let local = u32s.on_insert(move |_, _| {
+            local_inserted.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        });
+        let global = u32s.on_insert_global(move |_, _, _| {
+            global_inserted.fetch_add(10, std::sync::atomic::Ordering::Relaxed);
+        });
+
+        (local, global)
+    };
+
+    world.add_entity((0u32,));
+    assert_eq!(inserted.load(std::sync::atomic::Ordering::Relaxed), 11);
+
+    {
+        let mut u32s = world.try_borrow::<ViewMut<u32>>().unwrap();
+        assert!(u32s.remove_callback(local));
+        assert!(!u32s.remove_callback(local));
+    }
+
+    world.add_entity((1u32,));
+    assert_eq!(inserted.load(std::sync::atomic::Ordering::Relaxed), 21);
+
+    assert!(world
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .remove_callback(global));
+
+    world.add_entity((2u32,));
+    assert_eq!(inserted.load(std::sync::atomic::Ordering::Relaxed), 21);
+}
+
+#[test]
+fn remove_callback_inside_callback() {
+    let mut world = World::new();
+
+    let count = std::sync::Arc::new(std::sync::Mutex::new((0, None)));
+    let callback_count = count.clone();
+
+    let callback = world
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .on_insert(move |_, u32s| {
+            let mut count = callback_count.lock().unwrap();
+            count.0 += 1;
+            assert!(u32s.remove_callback(count.1.unwrap()));
+        });
+    count.lock().unwrap().1 = Some(callback);
+
+    world.add_entity((0u32,));
+    world.add_entity((1u32,));
+
+    assert_eq!(count.lock().unwrap().0, 1);
+}
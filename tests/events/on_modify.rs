@@ -0,0 +1,82 @@
+use shipyard::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn on_modify() {
+    let mut world = World::new();
+
+    let modified = Arc::new(Mutex::new(Vec::new()));
+    let callback_modified = modified.clone();
+
+    {
+        let mut u32s = world.try_borrow::<ViewMut<u32>>().unwrap();
+        u32s.update_pack();
+        u32s.on_modify(move |entity, u32s| {
+            // runs before the modification
+            callback_modified
+                .lock()
+                .unwrap()
+                .push((entity, u32s[entity]));
+        });
+    }
+
+    let e0 = world.add_entity((0u32,));
+    let e1 = world.add_entity((1u32,));
+
+    world
+        .try_run(|mut u32s: ViewMut<u32>| {
+            // inserted components are not modified
+            u32s[e0] += 10;
+            u32s.clear_inserted();
+
+            u32s[e0] += 10;
+            u32s[e1] += 10;
+            u32s[e0] += 10;
+            u32s.clear_modified();
+
+            u32s[e1] += 10;
+        })
+        .unwrap();
+
+    assert_eq!(*modified.lock().unwrap(), vec![(e0, 10), (e1, 1), (e1, 11)]);
+}
+
+#[test]
+fn without_update_pack() {
+    let mut world = World::new();
+
+    world
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .on_modify(|_, _| panic!());
+
+    let entity = world.add_entity((0u32,));
+
+    world
+        .try_run(|mut u32s: ViewMut<u32>| u32s[entity] += 1)
+        .unwrap();
+}
+
+#[test]
+fn remove_inside_callback() {
+    let mut world = World::new();
+
+    {
+        let mut u32s = world.try_borrow::<ViewMut<u32>>().unwrap();
+        u32s.update_pack();
+        u32s.on_modify(|entity, u32s| {
+            u32s.remove(entity);
+        });
+    }
+
+    let entity = world.add_entity((0u32,));
+
+    world
+        .try_run(|mut u32s: ViewMut<u32>| {
+            u32s.clear_inserted();
+            // the component is removed before it's modified
+            assert!((&mut u32s).fast_get(entity).is_err());
+            assert!(!u32s.contains(entity));
+        })
+        .unwrap();
+}
@@ -30,57 +30,112 @@ fn on_remove() {
     assert_eq!(unsafe { &*E }, &[e0, e1]);
 }
 
-// #[test]
-// fn on_remove_global() {
-//     static mut E: Vec<EntityId> = Vec::new();
+#[test]
+fn on_remove_global() {
+    static mut E: Vec<EntityId> = Vec::new();
+
+    let world = World::new();
+
+    let (mut entities, mut u32s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
+        .unwrap();
+
+    u32s.on_remove_global(|entity, _, _| unsafe {
+        E.push(entity);
+    });
+
+    let e0 = entities.add_entity(&mut u32s, 0);
+    let e1 = entities.add_entity(&mut u32s, 1);
+
+    u32s.remove(e0);
+    u32s.remove(e0);
 
-//     let world = World::new();
+    drop((entities, u32s));
+
+    world
+        .try_borrow::<AllStoragesViewMut>()
+        .unwrap()
+        .delete_entity(e1);
+
+    assert_eq!(unsafe { &*E }, &[e0, e1]);
+}
 
-//     let (mut entities, mut u32s) = world
-//         .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
-//         .unwrap();
+#[test]
+fn on_remove_both() {
+    static mut E: Vec<EntityId> = Vec::new();
 
-//     u32s.on_remove_global(|entity, _, _| unsafe {
-//         E.push(entity);
-//     });
+    let world = World::new();
 
-//     let e0 = entities.add_entity(&mut u32s, 0);
-//     let e1 = entities.add_entity(&mut u32s, 1);
+    let (mut entities, mut u32s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
+        .unwrap();
 
-//     u32s.remove(e0);
-//     u32s.remove(e0);
+    u32s.on_insert(|entity, _| unsafe { E.push(entity) });
+    u32s.on_remove_global(|entity, _, _| unsafe {
+        E.push(entity);
+    });
 
-//     drop((entities, u32s));
+    let e0 = entities.add_entity(&mut u32s, 0);
+    let e1 = entities.add_entity(&mut u32s, 1);
 
-//     world.try_borrow::<AllStoragesViewMut>().unwrap().delete(e1);
+    u32s.remove(e0);
+    u32s.remove(e0);
 
-//     assert_eq!(unsafe { &*E }, &[e0, e1]);
-// }
+    drop((entities, u32s));
 
-// #[test]
-// fn on_remove_both() {
-//     static mut E: Vec<EntityId> = Vec::new();
+    world
+        .try_borrow::<AllStoragesViewMut>()
+        .unwrap()
+        .delete_entity(e1);
 
-//     let world = World::new();
+    assert_eq!(unsafe { &*E }, &[e0, e1, e0, e1]);
+}
 
-//     let (mut entities, mut u32s) = world
-//         .try_borrow::<(EntitiesViewMut, ViewMut<u32>)>()
-//         .unwrap();
+#[test]
+fn on_remove_global_all_storages() {
+    struct Body(EntityId);
 
-//     u32s.on_insert(|entity, _| unsafe { E.push(entity) });
-//     u32s.on_remove_global(|entity, _, _| unsafe {
-//         E.push(entity);
-//     });
+    let mut world = World::new();
 
-//     let e0 = entities.add_entity(&mut u32s, 0);
-//     let e1 = entities.add_entity(&mut u32s, 1);
+    world
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .on_remove_global(|entity, _, all_storages| {
+            let mut bodies = all_storages.try_borrow::<ViewMut<Body>>().unwrap();
+            let body = bodies.remove(entity);
+            assert_eq!(body.map(|body| body.0), Some(entity));
+        });
+
+    let e0 = world.add_entity((0u32,));
+    let e1 = world.add_entity((1u32,));
+    world.add_component(e0, (Body(e0),)).unwrap();
+    world.add_component(e1, (Body(e1),)).unwrap();
+
+    // runs right after the u32 storage is stripped
+    world.delete_entity(e0);
+    assert!(!world.try_borrow::<View<Body>>().unwrap().contains(e0));
+
+    // runs at the end of the operation
+    world.remove::<(u32,)>(e1);
+    assert!(!world.try_borrow::<View<Body>>().unwrap().contains(e1));
+}
 
-//     u32s.remove(e0);
-//     u32s.remove(e0);
+#[test]
+fn unique_removal_inside_callback() {
+    let mut world = World::new();
+    world.add_unique(0usize);
 
-//     drop((entities, u32s));
+    world
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .on_remove_global(|_, _, all_storages| {
+            assert!(all_storages.try_remove_unique::<usize>().is_err());
+        });
 
-//     world.try_borrow::<AllStoragesViewMut>().unwrap().delete(e1);
+    let entity = world.add_entity((0u32,));
+    world.delete_entity(entity);
 
-//     assert_eq!(unsafe { &*E }, &[e0, e1, e0, e1]);
-// }
+    // the flag is reset once callbacks are done
+    let all_storages = world.try_borrow::<AllStoragesViewMut>().unwrap();
+    assert_eq!(all_storages.try_remove_unique::<usize>().unwrap(), 0);
+}
@@ -0,0 +1,339 @@
+use shipyard::hierarchy::{self, Child, Hierarchy, HierarchyIter, Parent, Propagate};
+use shipyard::*;
+
+type HierarchyViews<'a> = (EntitiesViewMut<'a>, ViewMut<'a, Parent>, ViewMut<'a, Child>);
+
+#[test]
+fn iterators() {
+    let world = World::new();
+
+    let mut hierarchy = world.try_borrow::<HierarchyViews>().unwrap();
+
+    let root = hierarchy.0.add_entity((), ());
+    let e0 = hierarchy.attach_new(root);
+    let e1 = hierarchy.attach_new(root);
+    let e00 = hierarchy.attach_new(e0);
+    let e01 = hierarchy.attach_new(e0);
+    let e10 = hierarchy.attach_new(e1);
+    let e000 = hierarchy.attach_new(e00);
+
+    let iter = (&hierarchy.1, &hierarchy.2);
+
+    assert_eq!(iter.children(root).len(), 2);
+    assert!(iter.children(root).eq(vec![e0, e1]));
+    assert!(iter.ancestors(e000).eq(vec![e00, e0, root]));
+    assert!(iter.descendants(root).eq(vec![e0, e00, e000, e01, e1, e10]));
+    assert!(iter
+        .breadth_first(root)
+        .eq(vec![e0, e1, e00, e01, e10, e000]));
+    assert!(iter.breadth_first(e10).eq(None));
+
+    assert_eq!(hierarchy.1[root].num_children(), 2);
+    assert_eq!(hierarchy.2[e01].parent(), e0);
+    assert_eq!(hierarchy.2[e01].next(), e00);
+}
+
+#[test]
+fn attach_detach() {
+    let world = World::new();
+
+    let mut hierarchy = world.try_borrow::<HierarchyViews>().unwrap();
+
+    let root0 = hierarchy.0.add_entity((), ());
+    let root1 = hierarchy.0.add_entity((), ());
+    let e0 = hierarchy.attach_new(root0);
+    let e1 = hierarchy.attach_new(root0);
+    let e2 = hierarchy.attach_new(e1);
+
+    hierarchy.attach(e1, root1);
+    assert!((&hierarchy.1, &hierarchy.2).children(root0).eq(vec![e0]));
+    assert!((&hierarchy.1, &hierarchy.2)
+        .descendants(root1)
+        .eq(vec![e1, e2]));
+
+    hierarchy.detach(e0);
+    assert!(!hierarchy.1.contains(root0));
+    assert!(!hierarchy.2.contains(e0));
+
+    hierarchy.remove(e1);
+    assert!((&hierarchy.1, &hierarchy.2).children(root1).eq(None));
+    assert!((&hierarchy.1, &hierarchy.2).ancestors(e2).eq(None));
+
+    hierarchy.attach(e2, root1);
+    hierarchy.attach(e1, e2);
+    hierarchy.remove_all(root1);
+    assert!(hierarchy.1.is_empty());
+    assert!(hierarchy.2.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "An entity can't be attached to itself or one of its descendants.")]
+fn attach_to_descendant() {
+    let world = World::new();
+
+    let mut hierarchy = world.try_borrow::<HierarchyViews>().unwrap();
+
+    let root = hierarchy.0.add_entity((), ());
+    let e0 = hierarchy.attach_new(root);
+    let e1 = hierarchy.attach_new(e0);
+
+    hierarchy.attach(root, e1);
+}
+
+#[test]
+fn sort_children() {
+    let world = World::new();
+
+    let (mut hierarchy, mut usizes) = world
+        .try_borrow::<(HierarchyViews, ViewMut<usize>)>()
+        .unwrap();
+
+    let root = hierarchy.0.add_entity((), ());
+    let e0 = hierarchy.attach_new(root);
+    let e1 = hierarchy.attach_new(root);
+    let e2 = hierarchy.attach_new(root);
+
+    hierarchy.0.add_component(e0, &mut usizes, 2);
+    hierarchy.0.add_component(e1, &mut usizes, 0);
+    hierarchy.0.add_component(e2, &mut usizes, 1);
+
+    hierarchy.sort_children_by(root, |a, b| usizes[*a].cmp(&usizes[*b]));
+
+    assert!((&hierarchy.1, &hierarchy.2)
+        .children(root)
+        .eq(vec![e1, e2, e0]));
+    assert_eq!(hierarchy.2[e1].prev(), e0);
+}
+
+#[test]
+fn delete_entity() {
+    let mut world = World::new();
+
+    let (root, e0, e1, e00) = world
+        .try_run(|mut hierarchy: HierarchyViews| {
+            let root = hierarchy.0.add_entity((), ());
+            let e0 = hierarchy.attach_new(root);
+            let e1 = hierarchy.attach_new(root);
+            let e00 = hierarchy.attach_new(e0);
+
+            (root, e0, e1, e00)
+        })
+        .unwrap();
+
+    // deletes the subtree and detaches it from its parent
+    world.delete_entity(e0);
+
+    world
+        .try_run(
+            |entities: EntitiesView, parents: View<Parent>, children: View<Child>| {
+                assert!(!entities.is_alive(e0));
+                assert!(!entities.is_alive(e00));
+                assert!(entities.is_alive(e1));
+                assert!((&parents, &children).children(root).eq(vec![e1]));
+            },
+        )
+        .unwrap();
+
+    world.delete_entity(root);
+
+    world
+        .try_run(
+            |entities: EntitiesView, parents: View<Parent>, children: View<Child>| {
+                assert!(!entities.is_alive(e1));
+                assert!(parents.is_empty());
+                assert!(children.is_empty());
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn strip() {
+    let world = World::new();
+
+    let (root, e0, e00) = world
+        .try_run(|mut hierarchy: HierarchyViews| {
+            let root = hierarchy.0.add_entity((), ());
+            let e0 = hierarchy.attach_new(root);
+            let e00 = hierarchy.attach_new(e0);
+
+            (root, e0, e00)
+        })
+        .unwrap();
+
+    world.try_borrow::<AllStoragesViewMut>().unwrap().strip(e0);
+
+    world
+        .try_run(
+            |entities: EntitiesView, parents: View<Parent>, children: View<Child>| {
+                assert!(entities.is_alive(e00));
+                assert!((&parents, &children).descendants(root).eq(None));
+                assert!((&parents, &children).ancestors(e00).eq(None));
+            },
+        )
+        .unwrap();
+}
+
+#[derive(Clone, Copy)]
+struct Local(u32);
+#[derive(Debug, PartialEq)]
+struct Global(u32);
+
+impl Propagate for Local {
+    type Global = Global;
+
+    fn root(local: &Self) -> Global {
+        Global(local.0)
+    }
+    fn combine(parent: &Global, local: &Self) -> Global {
+        Global(parent.0 + local.0)
+    }
+}
+
+#[test]
+fn propagate() {
+    let world = World::new();
+
+    let (mut hierarchy, mut locals, mut globals) = world
+        .try_borrow::<(HierarchyViews, ViewMut<Local>, ViewMut<Global>)>()
+        .unwrap();
+
+    locals.update_pack();
+    hierarchy.2.update_pack();
+
+    let root = hierarchy.0.add_entity(&mut locals, Local(1));
+    let e0 = hierarchy.attach_new(root);
+    let e1 = hierarchy.attach_new(root);
+    let e00 = hierarchy.attach_new(e0);
+    let e10 = hierarchy.attach_new(e1);
+    for (entity, local) in [(e0, 10), (e1, 20), (e00, 100), (e10, 200)] {
+        hierarchy.0.add_component(entity, &mut locals, Local(local));
+    }
+    // without a local component the subtree isn't reached
+    let e100 = hierarchy.attach_new(e10);
+    let other_root = hierarchy.0.add_entity(&mut locals, Local(2));
+
+    hierarchy::propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+
+    assert_eq!(globals[root], Global(1));
+    assert_eq!(globals[e0], Global(11));
+    assert_eq!(globals[e00], Global(111));
+    assert_eq!(globals[e10], Global(221));
+    assert_eq!(globals[other_root], Global(2));
+    assert!(!globals.contains(e100));
+
+    // only modified subtrees are processed
+    locals.clear_inserted_and_modified();
+    hierarchy.2.clear_inserted_and_modified();
+    globals[e00] = Global(0);
+    globals[e10] = Global(0);
+    locals[e1].0 = 30;
+
+    hierarchy::propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+
+    assert_eq!(globals[e1], Global(31));
+    assert_eq!(globals[e10], Global(231));
+    assert_eq!(globals[e00], Global(0));
+}
+
+#[test]
+fn propagate_reparent() {
+    let world = World::new();
+
+    let (mut hierarchy, mut locals, mut globals) = world
+        .try_borrow::<(HierarchyViews, ViewMut<Local>, ViewMut<Global>)>()
+        .unwrap();
+
+    locals.update_pack();
+    hierarchy.2.update_pack();
+
+    let root0 = hierarchy.0.add_entity(&mut locals, Local(1));
+    let root1 = hierarchy.0.add_entity(&mut locals, Local(10));
+    let child = hierarchy.attach_new(root0);
+    hierarchy.0.add_component(child, &mut locals, Local(100));
+
+    hierarchy::propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+    assert_eq!(globals[child], Global(101));
+
+    locals.clear_inserted_and_modified();
+    hierarchy.2.clear_inserted_and_modified();
+
+    hierarchy.attach(child, root1);
+    hierarchy::propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+    assert_eq!(globals[child], Global(110));
+
+    hierarchy.2.clear_inserted_and_modified();
+
+    hierarchy.detach(child);
+    hierarchy::propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+    assert_eq!(globals[child], Global(100));
+
+    // without tracking `Child` everything is recomputed
+    let world = World::new();
+
+    let (mut hierarchy, mut locals, mut globals) = world
+        .try_borrow::<(HierarchyViews, ViewMut<Local>, ViewMut<Global>)>()
+        .unwrap();
+
+    locals.update_pack();
+
+    let root0 = hierarchy.0.add_entity(&mut locals, Local(1));
+    let root1 = hierarchy.0.add_entity(&mut locals, Local(10));
+    let child = hierarchy.attach_new(root0);
+    hierarchy.0.add_component(child, &mut locals, Local(100));
+
+    hierarchy::propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+    locals.clear_inserted_and_modified();
+
+    hierarchy.attach(child, root1);
+    hierarchy::propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+    assert_eq!(globals[child], Global(110));
+}
+
+#[test]
+fn propagate_deep() {
+    let world = World::new();
+
+    let (mut hierarchy, mut locals, mut globals) = world
+        .try_borrow::<(HierarchyViews, ViewMut<Local>, ViewMut<Global>)>()
+        .unwrap();
+
+    let chain = (0..100_000)
+        .map(|_| hierarchy.0.add_entity(&mut locals, Local(1)))
+        .collect::<Vec<_>>();
+    // attached from the bottom so parents don't have ancestors yet
+    for pair in chain.windows(2).rev() {
+        hierarchy.attach(pair[1], pair[0]);
+    }
+
+    hierarchy::propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+
+    assert_eq!(globals[chain[99_999]], Global(100_000));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn par_propagate() {
+    let world = World::new();
+
+    let (mut hierarchy, mut locals, mut globals) = world
+        .try_borrow::<(HierarchyViews, ViewMut<Local>, ViewMut<Global>)>()
+        .unwrap();
+
+    let roots = (0..10)
+        .map(|i| {
+            let root = hierarchy.0.add_entity(&mut locals, Local(i));
+            let child = hierarchy.attach_new(root);
+            hierarchy.0.add_component(child, &mut locals, Local(1));
+
+            (root, child)
+        })
+        .collect::<Vec<_>>();
+
+    hierarchy::par_propagate(&hierarchy.1, &hierarchy.2, &locals, &mut globals);
+
+    for (i, (root, child)) in roots.into_iter().enumerate() {
+        assert_eq!(globals[root], Global(i as u32));
+        assert_eq!(globals[child], Global(i as u32 + 1));
+    }
+}
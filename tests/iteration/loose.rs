@@ -0,0 +1,163 @@
+use shipyard::error;
+use shipyard::*;
+
+#[test]
+fn basic() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>)>()
+        .unwrap();
+
+    for i in 0..6 {
+        entities.add_entity(&mut u32s, i);
+    }
+    let entity0 = entities.add_entity((&mut u32s, &mut i16s), (10, 20));
+    for i in 0..6 {
+        entities.add_entity(&mut i16s, i);
+    }
+
+    (&mut u32s, &mut i16s).try_loose_pack().unwrap();
+
+    let entity1 = entities.add_entity(&mut u32s, 11);
+    entities.add_component(entity1, &mut i16s, 21);
+    entities.add_entity((&mut u32s, &mut i16s), (12, 22));
+
+    let iter = (&u32s, &i16s).iter();
+    assert_eq!(iter.size_hint(), (0, Some(3)));
+    assert_eq!(
+        iter.collect::<Vec<_>>(),
+        vec![(&10, &20), (&11, &21), (&12, &22)]
+    );
+
+    u32s.remove(entity0);
+    i16s.delete(entity1);
+
+    let iter = (&u32s, &i16s).iter();
+    assert_eq!(iter.size_hint(), (0, Some(1)));
+    assert_eq!(iter.collect::<Vec<_>>(), vec![(&12, &22)]);
+
+    u32s.clear();
+
+    assert_eq!((&u32s, &i16s).iter().size_hint(), (0, Some(0)));
+}
+
+#[test]
+fn with_id() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_loose_pack().unwrap();
+
+    for i in 0..6 {
+        entities.add_entity(&mut u32s, i);
+    }
+    let entity0 = entities.add_entity((&mut u32s, &mut i16s), (10, 20));
+    let entity1 = entities.add_entity((&mut u32s, &mut i16s), (11, 21));
+
+    let mut iter = (&mut u32s, &i16s).iter().with_id();
+    assert_eq!(
+        iter.next().map(|(id, (x, &y))| (id, *x, y)),
+        Some((entity0, 10, 20))
+    );
+    assert_eq!(
+        iter.next().map(|(id, (x, &y))| (id, *x, y)),
+        Some((entity1, 11, 21))
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn not() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s, mut f32s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>, ViewMut<f32>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_loose_pack().unwrap();
+
+    for i in 0..6 {
+        entities.add_entity((&mut u32s, &mut f32s), (i, i as f32));
+        entities.add_entity((&mut i16s, &mut f32s), (i as i16, i as f32));
+    }
+    entities.add_entity((&mut u32s, &mut i16s, &mut f32s), (10, 20, 30.));
+    entities.add_entity((&mut u32s, &mut i16s), (11, 21));
+    entities.add_entity((&mut u32s, &mut i16s), (12, 22));
+
+    let iter = (&u32s, &i16s, !&f32s).iter();
+    assert_eq!(iter.size_hint(), (0, Some(3)));
+    assert_eq!(
+        iter.collect::<Vec<_>>(),
+        vec![(&11, &21, ()), (&12, &22, ())]
+    );
+
+    let iter = (&u32s, &i16s, &f32s).fast_iter();
+    assert_eq!(iter.size_hint(), (0, Some(3)));
+    assert_eq!(iter.collect::<Vec<_>>(), vec![(&10, &20, &30.)]);
+}
+
+#[test]
+fn all_storages() {
+    let mut world = World::new();
+
+    world
+        .try_run(|(mut u32s, mut i16s): (ViewMut<u32>, ViewMut<i16>)| {
+            (&mut u32s, &mut i16s).try_loose_pack().unwrap();
+        })
+        .unwrap();
+
+    let entity0 = world.add_entity((0u32, 10i16));
+    world.bulk_add_entity((1..5).map(|i| (i as u32,)));
+    world.bulk_add_entity((1..5).map(|i| (i as i16,)));
+    let entity1 = world.add_entity((1u32, 11i16));
+    world.bulk_add_entity((2..4).map(|i| (i as u32, i as i16 + 10)));
+    let entity4 = world.add_entity(());
+    world.add_component(entity4, (4u32, 14i16)).unwrap();
+
+    world.remove::<(u32,)>(entity0);
+    world.delete_entity(entity1);
+
+    world
+        .try_run(|(u32s, i16s): (View<u32>, View<i16>)| {
+            let iter = (&u32s, &i16s).iter();
+            assert_eq!(iter.size_hint(), (0, Some(3)));
+            assert_eq!(
+                iter.collect::<Vec<_>>(),
+                vec![(&2, &12), (&3, &13), (&4, &14)]
+            );
+        })
+        .unwrap();
+}
+
+#[test]
+fn already_packed() {
+    let world = World::new();
+
+    let (mut u32s, mut i16s, mut f32s) = world
+        .try_borrow::<(ViewMut<u32>, ViewMut<i16>, ViewMut<f32>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_loose_pack().unwrap();
+    (&mut u32s, &mut i16s).try_loose_pack().unwrap();
+
+    assert_eq!(
+        (&mut u32s, &mut f32s).try_loose_pack(),
+        Err(error::Pack::AlreadyLoosePack(core::any::type_name::<u32>()))
+    );
+    assert_eq!(
+        (&mut u32s, &mut i16s).try_tight_pack(),
+        Err(error::Pack::AlreadyLoosePack(core::any::type_name::<u32>()))
+    );
+
+    let (mut u64s, mut f64s) = world.try_borrow::<(ViewMut<u64>, ViewMut<f64>)>().unwrap();
+    (&mut u64s, &mut f64s).try_tight_pack().unwrap();
+
+    assert_eq!(
+        (&mut u64s, &mut f64s).try_loose_pack(),
+        Err(error::Pack::AlreadyTightPack(core::any::type_name::<u64>()))
+    );
+}
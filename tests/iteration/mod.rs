@@ -1,2 +1,4 @@
+mod loose;
 mod non_packed;
+mod tight;
 mod update;
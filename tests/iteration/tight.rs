@@ -0,0 +1,256 @@
+use shipyard::error;
+use shipyard::*;
+
+#[test]
+fn basic() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>)>()
+        .unwrap();
+
+    entities.add_entity(&mut u32s, 1);
+    entities.add_entity((&mut u32s, &mut i16s), (0, 10));
+    entities.add_entity(&mut i16s, 13);
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    entities.add_entity((&mut u32s, &mut i16s), (2, 12));
+    entities.add_entity((&mut u32s, &mut i16s), (4, 14));
+
+    if let iter::Iter::Tight(mut iter) = (&u32s, &i16s).iter() {
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert_eq!(iter.next(), Some((&0, &10)));
+        assert_eq!(iter.next(), Some((&2, &12)));
+        assert_eq!(iter.next(), Some((&4, &14)));
+        assert_eq!(iter.next(), None);
+    } else {
+        panic!("not packed");
+    }
+
+    if let iter::Iter::Tight(iter) = (&mut u32s, &i16s).iter() {
+        iter.for_each(|(mut x, &y)| *x += y as u32);
+    } else {
+        panic!("not packed");
+    }
+
+    assert_eq!(
+        (&u32s, &i16s).iter().collect::<Vec<_>>(),
+        vec![(&10, &10), (&14, &12), (&18, &14)]
+    );
+    assert_eq!(u32s.len(), 4);
+    assert_eq!(i16s.len(), 4);
+}
+
+#[test]
+fn with_id() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    let entity0 = entities.add_entity((&mut u32s, &mut i16s), (0, 10));
+    entities.add_entity(&mut u32s, 1);
+    let entity2 = entities.add_entity((&mut u32s, &mut i16s), (2, 12));
+
+    let mut iter = (&u32s, &i16s).iter().with_id();
+    assert_eq!(iter.next(), Some((entity0, (&0, &10))));
+    assert_eq!(iter.next(), Some((entity2, (&2, &12))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn chunk_exact() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    for i in 0..5 {
+        entities.add_entity((&mut u32s, &mut i16s), (i, i as i16 * 10));
+        entities.add_entity(&mut i16s, -1);
+    }
+
+    let mut iter = (&mut u32s, &i16s)
+        .fast_iter()
+        .into_chunk_exact(2)
+        .ok()
+        .unwrap();
+
+    assert_eq!(iter.next(), Some((&mut [0, 1][..], &[0, 10][..])));
+    assert_eq!(iter.next(), Some((&mut [2, 3][..], &[20, 30][..])));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.remainder(), (&mut [4][..], &[40][..]));
+}
+
+#[test]
+fn superset() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s, mut f32s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>, ViewMut<f32>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    entities.add_entity((&mut u32s, &mut i16s, &mut f32s), (0, 10, 20.));
+    entities.add_entity((&mut u32s, &mut i16s), (1, 11));
+    entities.add_entity((&mut u32s, &mut i16s, &mut f32s), (2, 12, 22.));
+    entities.add_entity((&mut u32s, &mut i16s), (3, 13));
+    entities.add_entity((&mut u32s, &mut i16s), (4, 14));
+
+    assert_eq!(
+        (&u32s, &i16s, &f32s).iter().collect::<Vec<_>>(),
+        vec![(&0, &10, &20.), (&2, &12, &22.)]
+    );
+    assert_eq!(
+        (&u32s, &i16s, !&f32s).iter().collect::<Vec<_>>(),
+        vec![(&1, &11, ()), (&3, &13, ()), (&4, &14, ())]
+    );
+}
+
+#[test]
+fn remove_and_delete() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    let entity0 = entities.add_entity((&mut u32s, &mut i16s), (0, 10));
+    entities.add_entity(&mut u32s, 1);
+    let entity2 = entities.add_entity((&mut u32s, &mut i16s), (2, 12));
+    entities.add_entity((&mut u32s, &mut i16s), (3, 13));
+
+    assert_eq!(
+        Remove::remove(&mut (&mut u32s, &mut i16s), entity0),
+        (Some(0), Some(10))
+    );
+    assert!(Delete::delete(&mut (&mut u32s, &mut i16s), entity2));
+
+    if let iter::Iter::Tight(iter) = (&u32s, &i16s).iter() {
+        assert_eq!(iter.collect::<Vec<_>>(), vec![(&3, &13)]);
+    } else {
+        panic!("not packed");
+    }
+}
+
+#[test]
+fn broken_pack() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    entities.add_entity((&mut u32s, &mut i16s), (0, 10));
+    let entity1 = entities.add_entity(&mut u32s, 1);
+    entities.add_entity((&mut u32s, &mut i16s), (2, 12));
+
+    // adding a single component breaks the pack
+    entities.add_component(entity1, &mut i16s, 11);
+
+    if let iter::Iter::Tight(_) = (&u32s, &i16s).iter() {
+        panic!("broken pack used");
+    }
+
+    let mut components = (&u32s, &i16s).iter().collect::<Vec<_>>();
+    components.sort_unstable();
+    assert_eq!(components, vec![(&0, &10), (&1, &11), (&2, &12)]);
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    if let iter::Iter::Tight(iter) = (&u32s, &i16s).iter() {
+        assert_eq!(iter.count(), 3);
+    } else {
+        panic!("not packed");
+    }
+}
+
+#[test]
+fn already_packed() {
+    let world = World::new();
+
+    let (mut u32s, mut i16s, mut f32s) = world
+        .try_borrow::<(ViewMut<u32>, ViewMut<i16>, ViewMut<f32>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    assert_eq!(
+        (&mut u32s, &mut f32s).try_tight_pack(),
+        Err(error::Pack::AlreadyTightPack(core::any::type_name::<u32>()))
+    );
+}
+
+#[test]
+fn all_storages() {
+    let mut world = World::new();
+
+    world
+        .try_run(|(mut u32s, mut i16s): (ViewMut<u32>, ViewMut<i16>)| {
+            (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+        })
+        .unwrap();
+
+    let entity0 = world.add_entity((0u32, 10i16));
+    world.add_entity((1u32,));
+    let entity2 = world.add_entity((2u32, 12i16));
+    world.bulk_add_entity((3..5).map(|i| (i as u32, i as i16 + 10)));
+    let entity5 = world.add_entity(());
+    world.add_component(entity5, (5u32, 15i16)).unwrap();
+
+    world.remove::<(u32, i16)>(entity0);
+    world.delete_entity(entity2);
+
+    world
+        .try_run(|(u32s, i16s): (View<u32>, View<i16>)| {
+            if let iter::Iter::Tight(iter) = (&u32s, &i16s).iter() {
+                assert_eq!(
+                    iter.collect::<Vec<_>>(),
+                    vec![(&5, &15), (&4, &14), (&3, &13)]
+                );
+            } else {
+                panic!("not packed");
+            }
+        })
+        .unwrap();
+}
+
+#[test]
+fn delete_partially_owned() {
+    let world = World::new();
+
+    let (mut entities, mut u32s, mut i16s) = world
+        .try_borrow::<(EntitiesViewMut, ViewMut<u32>, ViewMut<i16>)>()
+        .unwrap();
+
+    (&mut u32s, &mut i16s).try_tight_pack().unwrap();
+
+    entities.add_entity((&mut u32s, &mut i16s), (0, 10));
+    let only_i16 = entities.add_entity(&mut i16s, 100);
+    let only_u32 = entities.add_entity(&mut u32s, 200);
+
+    assert!(!(&mut u32s, &mut i16s).delete(only_i16));
+    assert!(!(&mut i16s, &mut u32s).delete(only_u32));
+
+    assert!(!i16s.contains(only_i16));
+    assert!(!u32s.contains(only_u32));
+
+    if let iter::Iter::Tight(iter) = (&u32s, &i16s).iter() {
+        assert_eq!(iter.collect::<Vec<_>>(), vec![(&0, &10)]);
+    } else {
+        panic!("not packed");
+    }
+}
@@ -7,6 +7,7 @@ mod serde;
 mod workload;
 mod events {
     mod on_insert;
+    mod on_modify;
     mod on_remove;
 }
 
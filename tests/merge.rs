@@ -0,0 +1,130 @@
+use shipyard::hierarchy::{Child, Hierarchy, HierarchyIter, Parent};
+use shipyard::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn merge() {
+    let mut world = World::new();
+    let mut chunk = World::new();
+
+    world.add_unique(0u64);
+    chunk.add_unique(1u64);
+
+    let existing = world.add_entity((0u32,));
+    let entity0 = chunk.add_entity((1u32, 1usize));
+    let deleted = chunk.add_entity((2u32,));
+    let entity1 = chunk.add_entity((3u32,));
+    chunk.delete_entity(deleted);
+    let entity2 = chunk.add_entity((4usize,));
+
+    let merged = world.merge(chunk);
+
+    assert_eq!(merged.len(), 3);
+    let new_id = |entity| merged.iter().find(|(old, _)| *old == entity).unwrap().1;
+
+    world
+        .try_run(
+            |entities: EntitiesView,
+             u32s: View<u32>,
+             usizes: View<usize>,
+             unique: UniqueView<u64>| {
+                assert_eq!(entities.iter().count(), 4);
+                assert_eq!(u32s.len(), 3);
+                assert_eq!(usizes.len(), 2);
+                assert_eq!(u32s.get(existing), Ok(&0));
+                assert_eq!(u32s.get(new_id(entity0)), Ok(&1));
+                assert_eq!(usizes.get(new_id(entity0)), Ok(&1));
+                assert_eq!(u32s.get(new_id(entity1)), Ok(&3));
+                assert_eq!(usizes.get(new_id(entity2)), Ok(&4));
+                assert!(u32s.get(new_id(entity2)).is_err());
+                assert_eq!(*unique, 0);
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn update_pack_and_callbacks() {
+    let mut world = World::new();
+    let mut chunk = World::new();
+
+    let inserted = Arc::new(Mutex::new(Vec::new()));
+    let callback_inserted = inserted.clone();
+
+    world
+        .try_run(|mut u32s: ViewMut<u32>| {
+            u32s.update_pack();
+            u32s.on_insert_global(move |entity, _, _| {
+                callback_inserted.lock().unwrap().push(entity)
+            });
+        })
+        .unwrap();
+
+    chunk.bulk_add_entity((0..3).map(|i| (i as u32,)));
+
+    let merged = world.merge(chunk);
+
+    world
+        .try_run(|u32s: View<u32>| {
+            assert_eq!(u32s.inserted().iter().count(), 3);
+        })
+        .unwrap();
+
+    assert_eq!(
+        *inserted.lock().unwrap(),
+        merged.iter().map(|&(_, new)| new).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn hierarchy_and_relations() {
+    let mut world = World::new();
+    let chunk = World::new();
+
+    world.add_entity(());
+
+    let (root, child0, child1) = chunk
+        .try_run(
+            |mut hierarchy: (EntitiesViewMut, ViewMut<Parent>, ViewMut<Child>),
+             mut relations: RelationViewMut<u32>| {
+                let root = hierarchy.0.add_entity((), ());
+                let child0 = hierarchy.attach_new(root);
+                let child1 = hierarchy.attach_new(root);
+
+                relations.insert(child0, child1, 0);
+                relations.insert(child0, root, 1);
+
+                (root, child0, child1)
+            },
+        )
+        .unwrap();
+
+    let merged = world.merge(chunk);
+    let new_id = |entity| merged.iter().find(|(old, _)| *old == entity).unwrap().1;
+
+    world
+        .try_run(
+            |parents: View<Parent>, children: View<Child>, relations: RelationView<u32>| {
+                assert!((&parents, &children)
+                    .children(new_id(root))
+                    .eq(vec![new_id(child0), new_id(child1)]));
+                assert_eq!(children[new_id(child1)].parent(), new_id(root));
+
+                assert!(relations
+                    .targets(new_id(child0))
+                    .eq(vec![new_id(child1), new_id(root)]));
+                assert_eq!(relations.get(new_id(child0), new_id(root)), Some(&1));
+            },
+        )
+        .unwrap();
+
+    // the hierarchy storages created by the merge keep deleting descendants
+    world.delete_entity(new_id(root));
+    world
+        .try_run(|entities: EntitiesView, children: View<Child>| {
+            assert!(!entities.is_alive(new_id(child0)));
+            assert!(!entities.is_alive(new_id(child1)));
+            assert!(children.is_empty());
+        })
+        .unwrap();
+}
@@ -0,0 +1,118 @@
+use shipyard::hierarchy::{Child, Hierarchy, Parent};
+use shipyard::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn move_entities() {
+    let mut staging = World::new();
+    let mut world = World::new();
+
+    staging.add_unique(0u64);
+    world.add_unique(1u64);
+
+    let existing = world.add_entity((10u32,));
+    let entity0 = staging.add_entity((0u32, 0usize));
+    let entity1 = staging.add_entity((1u32,));
+    let entity2 = staging.add_entity((2u32,));
+    let dead = staging.add_entity(());
+    staging.delete_entity(dead);
+
+    let moved = staging.move_entities_to(&mut world, &[entity0, entity2, dead, entity0]);
+
+    assert_eq!(moved.len(), 2);
+    assert_eq!(moved[0].0, entity0);
+    assert_eq!(moved[1].0, entity2);
+    assert!(moved.iter().all(|&(_, new_entity)| new_entity != existing));
+
+    staging
+        .try_run(
+            |entities: EntitiesView,
+             u32s: View<u32>,
+             usizes: View<usize>,
+             unique: UniqueView<u64>| {
+                assert!(!entities.is_alive(entity0));
+                assert!(!entities.is_alive(entity2));
+                assert_eq!(u32s.len(), 1);
+                assert_eq!(u32s.get(entity1), Ok(&1));
+                assert!(usizes.is_empty());
+                assert_eq!(*unique, 0);
+            },
+        )
+        .unwrap();
+
+    world
+        .try_run(
+            |entities: EntitiesView,
+             u32s: View<u32>,
+             usizes: View<usize>,
+             unique: UniqueView<u64>| {
+                assert!(entities.is_alive(moved[0].1));
+                assert!(entities.is_alive(moved[1].1));
+                assert_eq!(u32s.get(existing), Ok(&10));
+                assert_eq!(u32s.get(moved[0].1), Ok(&0));
+                assert_eq!(u32s.get(moved[1].1), Ok(&2));
+                assert_eq!(usizes.get(moved[0].1), Ok(&0));
+                assert_eq!(*unique, 1);
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn hierarchy() {
+    let mut staging = World::new();
+    let mut world = World::new();
+
+    let (root, child) = staging
+        .try_run(
+            |mut hierarchy: (EntitiesViewMut, ViewMut<Parent>, ViewMut<Child>)| {
+                let root = hierarchy.0.add_entity((), ());
+                let child = hierarchy.attach_new(root);
+
+                (root, child)
+            },
+        )
+        .unwrap();
+
+    let moved = staging.move_entities_to(&mut world, &[child]);
+
+    staging
+        .try_run(
+            |entities: EntitiesView, parents: View<Parent>, children: View<Child>| {
+                assert!(entities.is_alive(root));
+                assert!(parents.is_empty());
+                assert!(children.is_empty());
+            },
+        )
+        .unwrap();
+
+    world
+        .try_run(|children: View<Child>| assert!(!children.contains(moved[0].1)))
+        .unwrap();
+}
+
+#[test]
+fn callbacks() {
+    let mut staging = World::new();
+    let mut world = World::new();
+
+    let inserted = Arc::new(Mutex::new(Vec::new()));
+    let removed = Arc::new(Mutex::new(Vec::new()));
+
+    let callback_inserted = inserted.clone();
+    world
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .on_insert_global(move |entity, _, _| callback_inserted.lock().unwrap().push(entity));
+    let callback_removed = removed.clone();
+    staging
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .on_remove_global(move |entity, _, _| callback_removed.lock().unwrap().push(entity));
+
+    let entity = staging.add_entity((0u32,));
+    let moved = staging.move_entities_to(&mut world, &[entity]);
+
+    assert_eq!(*inserted.lock().unwrap(), vec![moved[0].1]);
+    assert_eq!(*removed.lock().unwrap(), vec![entity]);
+}
@@ -0,0 +1,90 @@
+use shipyard::*;
+
+struct DockedAt(u32);
+
+#[test]
+fn queries() {
+    let world = World::new();
+
+    let (mut entities, mut docked) = world
+        .try_borrow::<(EntitiesViewMut, RelationViewMut<DockedAt>)>()
+        .unwrap();
+
+    let station = entities.add_entity((), ());
+    let ship0 = entities.add_entity((), ());
+    let ship1 = entities.add_entity((), ());
+
+    assert!(docked.insert(ship0, station, DockedAt(0)).is_none());
+    assert!(docked.insert(ship1, station, DockedAt(1)).is_none());
+    assert_eq!(docked.insert(ship1, station, DockedAt(2)).unwrap().0, 1);
+
+    assert_eq!(docked.len(), 2);
+    assert!(docked.contains(ship0, station));
+    assert!(!docked.contains(station, ship0));
+    assert_eq!(docked.get(ship1, station).unwrap().0, 2);
+    assert!(docked.sources(station).eq(vec![ship0, ship1]));
+    assert!(docked.targets(ship0).eq(vec![station]));
+    assert!(docked.targets(station).eq(None));
+
+    docked.get_mut(ship0, station).unwrap().0 = 3;
+    assert_eq!(docked.remove(ship0, station).unwrap().0, 3);
+    assert!(docked.remove(ship0, station).is_none());
+    assert!(docked.sources(station).eq(vec![ship1]));
+    assert!(docked.targets(ship0).eq(None));
+}
+
+#[test]
+fn delete_entity() {
+    let mut world = World::new();
+
+    let (a, b, c) = world
+        .try_run(
+            |mut entities: EntitiesViewMut, mut targets: RelationViewMut<()>| {
+                let a = entities.add_entity((), ());
+                let b = entities.add_entity((), ());
+                let c = entities.add_entity((), ());
+
+                targets.insert(a, b, ());
+                targets.insert(b, c, ());
+                targets.insert(c, a, ());
+                targets.insert(b, b, ());
+
+                (a, b, c)
+            },
+        )
+        .unwrap();
+
+    world.delete_entity(b);
+
+    world
+        .try_run(|targets: RelationView<()>| {
+            assert_eq!(targets.len(), 1);
+            assert!(targets.contains(c, a));
+            assert!(targets.targets(a).eq(None));
+            assert!(targets.sources(c).eq(None));
+        })
+        .unwrap();
+}
+
+#[test]
+fn strip() {
+    let world = World::new();
+
+    let mut all_storages = world.try_borrow::<AllStoragesViewMut>().unwrap();
+
+    let a = all_storages.add_entity((0u32,));
+    let b = all_storages.add_entity((1u32,));
+
+    all_storages
+        .try_run(|mut targets: RelationViewMut<u32>| {
+            targets.insert(a, b, 0);
+            targets.insert(b, a, 1);
+        })
+        .unwrap();
+
+    all_storages.strip(a);
+
+    all_storages
+        .try_run(|targets: RelationView<u32>| assert!(targets.is_empty()))
+        .unwrap();
+}
@@ -35,6 +35,56 @@ fn entity_id_serde() {
     );
 }
 
+#[test]
+fn out_of_range() {
+    let index = serde_json::from_str::<EntityId>("[18446744073709551615,0]");
+    assert!(index.unwrap_err().to_string().contains("an index"));
+
+    let gen = serde_json::from_str::<EntityId>("[0,18446744073709551615]");
+    assert!(gen.unwrap_err().to_string().contains("a generation"));
+}
+
+#[test]
+fn other_layout() {
+    use serde::Deserialize;
+
+    // raw ids of binary formats record if they were serialized with `gen32`
+    let gen32 = EntityId::deserialize(Binary(3 << 62 | 1));
+    let default = EntityId::deserialize(Binary(1));
+
+    if cfg!(feature = "gen32") {
+        assert_eq!(gen32.unwrap().index(), 0);
+        assert!(default.unwrap_err().to_string().contains("with the gen32"));
+    } else {
+        assert!(gen32.unwrap_err().to_string().contains("without the gen32"));
+        assert_eq!(default.unwrap().index(), 0);
+    }
+}
+
+/// Non human readable deserializer holding a raw `EntityId`.
+struct Binary(u64);
+
+impl<'de> serde::Deserializer<'de> for Binary {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_u64(self.0)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
 fn check_roundtrip(entity_id: EntityId, expected: &str) {
     assert_eq!(expected, serde_json::to_string(&entity_id).unwrap());
     let new_entity_id: EntityId = serde_json::from_str(expected).unwrap();
@@ -1,52 +1,353 @@
 mod entity_id;
 
-// use shipyard::*;
-
-// #[test]
-// fn test() {
-//     let world = World::new();
-
-//     let [entity1, entity2] = world.run(
-//         |mut entities: EntitiesViewMut, mut u32s: ViewMut<u32>, mut strings: ViewMut<String>| {
-//             strings.setup_serde(SerConfig::default());
-//             u32s.setup_serde(SerConfig::default());
-
-//             [
-//                 entities.add_entity(&mut strings, "Test1212".to_string()),
-//                 entities.add_entity((&mut u32s, &mut strings), (545, "Test741".to_string())),
-//             ]
-//         },
-//     );
-
-//     let mut output = Vec::new();
-
-//     world
-//         .serialize(
-//             GlobalSerConfig::default(),
-//             &mut serde_json::Serializer::pretty(&mut output),
-//         )
-//         .unwrap();
-
-//     println!("{}", String::from_utf8(output.clone()).unwrap());
-
-//     let world_copy = World::new_deserialized(
-//         GlobalDeConfig::default(),
-//         &mut serde_json::Deserializer::from_slice(&output),
-//     )
-//     .unwrap();
-
-//     world_copy.run(
-//         |entities: EntitiesView, strings: View<String>, u32s: View<u32>| {
-//             assert!(entities.is_alive(entity1));
-//             assert!(entities.is_alive(entity2));
-
-//             assert_eq!(strings.get(entity1).map(AsRef::as_ref), Ok("Test1212"));
-//             assert_eq!(strings.get(entity2).map(AsRef::as_ref), Ok("Test741"));
-//             assert_eq!(strings.len(), 2);
-
-//             assert!(u32s.get(entity1).is_err());
-//             assert_eq!(u32s.get(entity2), Ok(&545));
-//             assert_eq!(u32s.len(), 1);
-//         },
-//     );
-// }
+use serde_derive::{Deserialize, Serialize};
+use shipyard::*;
+
+#[test]
+fn test() {
+    let world = World::new();
+
+    let [entity1, entity2] = world.run(
+        |mut entities: EntitiesViewMut, mut u32s: ViewMut<u32>, mut strings: ViewMut<String>| {
+            strings.setup_serde(SerConfig::default());
+            u32s.setup_serde(SerConfig::default());
+
+            [
+                entities.add_entity(&mut strings, "Test1212".to_string()),
+                entities.add_entity((&mut u32s, &mut strings), (545, "Test741".to_string())),
+            ]
+        },
+    );
+
+    let mut output = Vec::new();
+
+    world
+        .serialize(
+            GlobalSerConfig::default(),
+            &mut serde_json::Serializer::pretty(&mut output),
+        )
+        .unwrap();
+
+    let world_copy = World::new();
+    world_copy.run(|mut u32s: ViewMut<u32>, mut strings: ViewMut<String>| {
+        strings.setup_serde(SerConfig::default());
+        u32s.setup_serde(SerConfig::default());
+    });
+
+    world_copy
+        .deserialize(
+            GlobalDeConfig::default(),
+            &mut serde_json::Deserializer::from_slice(&output),
+        )
+        .unwrap();
+
+    world_copy.run(
+        |entities: EntitiesView, strings: View<String>, u32s: View<u32>| {
+            assert!(entities.is_alive(entity1));
+            assert!(entities.is_alive(entity2));
+
+            assert_eq!(strings.get(entity1).map(AsRef::as_ref), Ok("Test1212"));
+            assert_eq!(strings.get(entity2).map(AsRef::as_ref), Ok("Test741"));
+            assert_eq!(strings.len(), 2);
+
+            assert!(u32s.get(entity1).is_err());
+            assert_eq!(u32s.get(entity2), Ok(&545));
+            assert_eq!(u32s.len(), 1);
+        },
+    );
+}
+
+#[test]
+fn existing_entities() {
+    let mut world = World::new();
+    world
+        .borrow::<ViewMut<u32>>()
+        .setup_serde(SerConfig::default());
+    let entity = world.add_entity((0u32,));
+
+    let json = world
+        .serialize(GlobalSerConfig::default(), serde_json::value::Serializer)
+        .unwrap();
+
+    world.deserialize(GlobalDeConfig::default(), json).unwrap();
+
+    world.run(|entities: EntitiesView, u32s: View<u32>| {
+        assert_eq!(entities.iter().count(), 2);
+        assert_eq!(u32s.len(), 2);
+        assert_eq!(u32s.get(entity), Ok(&0));
+
+        let new_entity = entities.iter().find(|&id| id != entity).unwrap();
+        assert_eq!(u32s.get(new_entity), Ok(&0));
+    });
+}
+
+#[test]
+fn without_entities() {
+    let mut world = World::new();
+    world
+        .borrow::<ViewMut<u32>>()
+        .setup_serde(SerConfig::default());
+    let entity0 = world.add_entity((0u32,));
+    let entity1 = world.add_entity(());
+    let entity2 = world.add_entity((2u32,));
+
+    let json = world
+        .serialize(
+            GlobalSerConfig {
+                with_entities: false,
+            },
+            serde_json::value::Serializer,
+        )
+        .unwrap();
+
+    let mut new_world = World::new();
+    new_world
+        .borrow::<ViewMut<u32>>()
+        .setup_serde(SerConfig::default());
+    new_world
+        .deserialize(GlobalDeConfig::default(), json)
+        .unwrap();
+
+    new_world.run(|entities: EntitiesView, u32s: View<u32>| {
+        assert!(entities.is_alive(entity0));
+        assert!(!entities.is_alive(entity1));
+        assert!(entities.is_alive(entity2));
+        assert_eq!(u32s.get(entity2), Ok(&2));
+    });
+
+    // the index without component is reused
+    let new_entity = new_world.add_entity(());
+    assert_eq!(new_entity.index(), entity1.index());
+    assert!(new_world.borrow::<View<u32>>().get(new_entity).is_err());
+    let new_entity = new_world.add_entity(());
+    assert_eq!(new_entity.index(), 3);
+
+    // components of entities that aren't alive can't be loaded
+    world.borrow::<EntitiesViewMut>().delete_unchecked(entity0);
+    let json = world
+        .serialize(GlobalSerConfig::default(), serde_json::value::Serializer)
+        .unwrap();
+
+    let new_world = World::new();
+    new_world
+        .borrow::<ViewMut<u32>>()
+        .setup_serde(SerConfig::default());
+    assert!(new_world
+        .deserialize(GlobalDeConfig::default(), json)
+        .is_err());
+}
+
+#[test]
+fn identifier() {
+    let mut world = World::new();
+    world.borrow::<ViewMut<u32>>().setup_serde(SerConfig {
+        identifier: Some(Identifier::new("u32")),
+    });
+    world.add_unique(0usize);
+    UniqueViewMut::setup_serde(
+        &mut world.borrow::<UniqueViewMut<usize>>(),
+        SerConfig {
+            identifier: Some(Identifier::new("count")),
+        },
+    );
+    let entity = world.add_entity((5u32,));
+    *world.borrow::<UniqueViewMut<usize>>() = 1;
+
+    let json = world
+        .serialize(GlobalSerConfig::default(), serde_json::value::Serializer)
+        .unwrap();
+
+    assert!(json["storages"]["u32"].is_object());
+    assert_eq!(json["storages"]["count"], 1);
+
+    let world_copy = World::new();
+    world_copy.add_unique(0usize);
+    world_copy.borrow::<ViewMut<u32>>().setup_serde(SerConfig {
+        identifier: Some(Identifier::new("u32")),
+    });
+
+    // the unique storage wasn't setup
+    assert!(world_copy
+        .deserialize(GlobalDeConfig::default(), json.clone())
+        .is_err());
+
+    let world_copy = World::new();
+    world_copy.add_unique(0usize);
+    world_copy.borrow::<ViewMut<u32>>().setup_serde(SerConfig {
+        identifier: Some(Identifier::new("u32")),
+    });
+    UniqueViewMut::setup_serde(
+        &mut world_copy.borrow::<UniqueViewMut<usize>>(),
+        SerConfig {
+            identifier: Some(Identifier::new("count")),
+        },
+    );
+    world_copy
+        .deserialize(GlobalDeConfig::default(), json)
+        .unwrap();
+
+    assert_eq!(world_copy.borrow::<View<u32>>().get(entity), Ok(&5));
+    assert_eq!(*world_copy.borrow::<UniqueView<usize>>(), 1);
+}
+
+#[derive(Serialize, Deserialize)]
+struct Parent(EntityId);
+
+impl VisitEntityIds for Parent {
+    fn visit_entity_ids(&mut self, visitor: &mut dyn FnMut(&mut EntityId)) {
+        self.0.visit_entity_ids(visitor);
+    }
+}
+
+#[test]
+fn remap() {
+    let mut world = World::new();
+    world
+        .borrow::<ViewMut<u32>>()
+        .setup_serde(SerConfig::default());
+    world
+        .borrow::<ViewMut<Parent>>()
+        .setup_serde_remap(SerConfig::default());
+
+    let parent = world.add_entity((0u32,));
+    let child = world.add_entity((1u32, Parent(parent)));
+
+    let json = world
+        .serialize(GlobalSerConfig::default(), serde_json::value::Serializer)
+        .unwrap();
+
+    let new_world = World::new();
+    new_world
+        .borrow::<ViewMut<u32>>()
+        .setup_serde(SerConfig::default());
+    new_world
+        .borrow::<ViewMut<Parent>>()
+        .setup_serde_remap(SerConfig::default());
+
+    let entities_map = new_world
+        .deserialize(
+            GlobalDeConfig {
+                remap_entities: true,
+            },
+            json.clone(),
+        )
+        .unwrap();
+
+    assert!(entities_map.is_remapped());
+    assert_eq!(entities_map.len(), 2);
+
+    let new_parent = entities_map.get(parent).unwrap();
+    let new_child = entities_map.get(child).unwrap();
+
+    new_world.run(|u32s: View<u32>, parents: View<Parent>| {
+        assert_eq!(u32s.get(new_parent), Ok(&0));
+        assert_eq!(u32s.get(new_child), Ok(&1));
+        assert_eq!(parents.get(new_child).unwrap().0, new_parent);
+    });
+
+    // entities are needed to remap
+    let json = world
+        .serialize(
+            GlobalSerConfig {
+                with_entities: false,
+            },
+            serde_json::value::Serializer,
+        )
+        .unwrap();
+
+    assert!(new_world
+        .deserialize(
+            GlobalDeConfig {
+                remap_entities: true,
+            },
+            json.clone(),
+        )
+        .is_err());
+
+    // without entities, ids could collide with the ones already in the world
+    assert!(new_world
+        .deserialize(GlobalDeConfig::default(), json.clone())
+        .is_err());
+
+    let mut empty_world = World::new();
+    empty_world
+        .borrow::<ViewMut<u32>>()
+        .setup_serde(SerConfig::default());
+    empty_world
+        .borrow::<ViewMut<Parent>>()
+        .setup_serde_remap(SerConfig::default());
+
+    assert!(!empty_world
+        .deserialize(GlobalDeConfig::default(), json)
+        .unwrap()
+        .is_remapped());
+
+    empty_world.run(|entities: EntitiesView, u32s: View<u32>| {
+        assert!(entities.is_alive(parent));
+        assert!(entities.is_alive(child));
+        assert_eq!(u32s.get(child), Ok(&1));
+    });
+
+    // loaded ids aren't given to new entities
+    let new_entity = empty_world.add_entity(());
+    assert_ne!(new_entity, parent);
+    assert_ne!(new_entity, child);
+    assert!(empty_world.borrow::<View<u32>>().get(new_entity).is_err());
+}
+
+#[test]
+fn templates() {
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Health(u32);
+
+    let mut world = World::new();
+
+    world
+        .try_run(
+            |mut healths: ViewMut<Health>, mut strings: ViewMut<String>| {
+                healths.setup_serde(SerConfig {
+                    identifier: Some(Identifier::new("health")),
+                });
+                healths.setup_serde_template();
+                strings.setup_serde(SerConfig {
+                    identifier: Some(Identifier::new("name")),
+                });
+                strings.setup_serde_template();
+            },
+        )
+        .unwrap();
+
+    world
+        .load_templates(serde_json::json!({
+            "orc": { "health": 50, "name": "orc" },
+            "goblin": { "health": 20 },
+        }))
+        .unwrap();
+
+    let orc = world.instantiate("orc", (Health(60),)).unwrap();
+    let goblins = world
+        .bulk_instantiate("goblin", (0..2).map(|_| ()))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+    world
+        .try_run(|healths: View<Health>, strings: View<String>| {
+            assert_eq!(healths.get(orc), Ok(&Health(60)));
+            assert_eq!(strings.get(orc).map(String::as_str), Ok("orc"));
+            assert_eq!(healths.get(goblins[1]), Ok(&Health(20)));
+            assert!(strings.get(goblins[1]).is_err());
+        })
+        .unwrap();
+
+    world
+        .try_borrow::<ViewMut<u32>>()
+        .unwrap()
+        .setup_serde(SerConfig::default());
+
+    assert!(world
+        .load_templates(serde_json::json!({ "troll": { "u32": 1 } }))
+        .is_err());
+    assert!(world
+        .load_templates(serde_json::json!({ "troll": { "u64": 1 } }))
+        .is_err());
+}
@@ -0,0 +1,145 @@
+use shipyard::error;
+use shipyard::*;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Health(u32);
+#[derive(Clone, Debug, PartialEq)]
+struct Name(&'static str);
+
+#[test]
+fn instantiate() {
+    let mut world = World::new();
+
+    world.add_template("orc", Template::new().with(Health(50)).with(Name("orc")));
+
+    let orc = world.instantiate("orc", ()).unwrap();
+    let boss = world.instantiate("orc", (Health(500), 0u32)).unwrap();
+
+    world
+        .try_run(
+            |healths: View<Health>, names: View<Name>, u32s: View<u32>| {
+                assert_eq!(healths.get(orc), Ok(&Health(50)));
+                assert_eq!(names.get(orc), Ok(&Name("orc")));
+                assert!(u32s.get(orc).is_err());
+
+                assert_eq!(healths.get(boss), Ok(&Health(500)));
+                assert_eq!(names.get(boss), Ok(&Name("orc")));
+                assert_eq!(u32s.get(boss), Ok(&0));
+            },
+        )
+        .unwrap();
+
+    assert_eq!(world.instantiate("goblin", ()), Err(error::MissingTemplate));
+}
+
+#[test]
+fn replace() {
+    let mut world = World::new();
+
+    let mut template = Template::new().with(Health(50)).with(Health(60));
+    assert_eq!(template.len(), 1);
+
+    world.add_template("orc", template.clone());
+    template.insert(Name("orc"));
+    world.add_template("named_orc", template);
+
+    let orc = world.instantiate("orc", ()).unwrap();
+    let named_orc = world.instantiate("named_orc", ()).unwrap();
+
+    world
+        .try_run(|healths: View<Health>, names: View<Name>| {
+            assert_eq!(healths.get(orc), Ok(&Health(60)));
+            assert!(names.get(orc).is_err());
+            assert_eq!(names.get(named_orc), Ok(&Name("orc")));
+        })
+        .unwrap();
+}
+
+#[test]
+fn bulk_instantiate() {
+    let mut world = World::new();
+
+    world.add_template("orc", Template::new().with(Health(50)).with(Name("orc")));
+
+    let entity = world.add_entity(());
+    world.delete_entity(entity);
+
+    let orcs = world
+        .bulk_instantiate("orc", (0..3).map(|_| ()))
+        .unwrap()
+        .collect::<Vec<_>>();
+    let bosses = world
+        .bulk_instantiate("orc", (0..2).map(|i| (Health(500 + i),)))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+    assert_eq!(orcs.len(), 3);
+    assert_eq!(bosses.len(), 2);
+
+    world
+        .try_run(|healths: View<Health>, names: View<Name>| {
+            assert_eq!(healths.len(), 5);
+            assert_eq!(names.len(), 5);
+
+            for &orc in &orcs {
+                assert_eq!(healths.get(orc), Ok(&Health(50)));
+            }
+            assert_eq!(healths.get(bosses[0]), Ok(&Health(500)));
+            assert_eq!(healths.get(bosses[1]), Ok(&Health(501)));
+            assert_eq!(names.get(bosses[1]), Ok(&Name("orc")));
+        })
+        .unwrap();
+
+    assert!(world
+        .bulk_instantiate("goblin", (0..2).map(|_| ()))
+        .is_err());
+}
+
+#[test]
+fn bulk_instantiate_spawning_callback() {
+    let mut world = World::new();
+
+    world.add_template("orc", Template::new().with(Health(50)));
+    world.borrow::<ViewMut<Health>>().on_insert_global(
+        |_, _: &mut SparseSet<Health>, all_storages: &AllStorages| {
+            all_storages.borrow::<EntitiesViewMut>().add_entity((), ());
+        },
+    );
+
+    let orcs = world
+        .bulk_instantiate("orc", (0..2).map(|_| ()))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+    // entities spawned by the callback aren't instances of the template
+    assert_eq!(orcs.len(), 2);
+    assert_eq!(world.borrow::<EntitiesView>().iter().count(), 4);
+    world.run(|healths: View<Health>| {
+        assert_eq!(healths.len(), 2);
+        assert!(orcs.iter().all(|&orc| healths.get(orc) == Ok(&Health(50))));
+    });
+}
+
+#[test]
+fn tight_pack() {
+    let mut world = World::new();
+
+    world.run(|mut healths: ViewMut<Health>, mut names: ViewMut<Name>| {
+        (&mut healths, &mut names).try_tight_pack().unwrap();
+    });
+    world.add_template("orc", Template::new().with(Health(50)));
+
+    world.instantiate("orc", (Name("boss"),)).unwrap();
+    world
+        .bulk_instantiate("orc", (0..2).map(|_| (Name("orc"),)))
+        .unwrap();
+    world.instantiate("orc", ()).unwrap();
+
+    world.run(|healths: View<Health>, names: View<Name>| {
+        if let iter::Iter::Tight(iter) = (&healths, &names).iter() {
+            assert_eq!(iter.count(), 3);
+        } else {
+            panic!("not packed");
+        }
+    });
+}
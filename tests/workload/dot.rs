@@ -0,0 +1,68 @@
+use shipyard::*;
+
+fn read(_: View<u32>) {}
+
+fn write(_: ViewMut<u32>) {}
+
+fn other(_: View<u64>) {}
+
+#[test]
+fn batches() {
+    let world = World::new();
+
+    let info = Workload::builder("Systems")
+        .try_with_system(system!(write))
+        .unwrap()
+        .try_with_system(system!(read))
+        .unwrap()
+        .try_with_system(system!(other))
+        .unwrap()
+        .after(system!(read))
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    let dot = info.to_dot();
+
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains("label=\"Systems\";"));
+    assert!(dot.contains("subgraph cluster_s_0 {"));
+    assert!(dot.contains("subgraph cluster_s_1 {"));
+    assert!(dot.contains("subgraph cluster_s_2 {"));
+    assert!(
+        dot.contains("s_0_0 -> s_1_0 [label=\"shipyard::sparse_set::SparseSet<u32> (Shared)\"];")
+    );
+    assert!(dot.contains("s_1_0 -> s_2_0 [style=dashed, label=\"after\"];"));
+}
+
+#[test]
+fn nested() {
+    let world = World::new();
+
+    Workload::builder("Inner")
+        .try_with_system(system!(write))
+        .unwrap()
+        .try_with_system(system!(read))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    let info = Workload::builder("Outer")
+        .with_workload("Inner")
+        .try_with_system(system!(read))
+        .unwrap()
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    let dot = info.to_dot();
+
+    assert!(dot.contains("subgraph cluster_s_0_0 {"));
+    assert!(dot.contains("s_0_0 [label=\"Inner\", shape=box3d];"));
+    assert!(dot.contains("subgraph cluster_s_0_0_1 {"));
+    assert!(dot.contains(
+        "s_0_0_0_0 -> s_0_0_1_0 [label=\"shipyard::sparse_set::SparseSet<u32> (Shared)\"];"
+    ));
+    assert!(
+        dot.contains("s_0_0 -> s_1_0 [label=\"shipyard::sparse_set::SparseSet<u32> (Shared)\"];")
+    );
+}
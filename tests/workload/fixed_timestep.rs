@@ -0,0 +1,202 @@
+use core::time::Duration;
+use shipyard::*;
+
+fn physics(mut steps: UniqueViewMut<u32>) {
+    *steps += 1;
+}
+
+fn render(mut frames: UniqueViewMut<u64>) {
+    *frames += 1;
+}
+
+fn add_workloads(world: &World) {
+    Workload::builder("Physics")
+        .try_with_system(system!(physics))
+        .unwrap()
+        .add_to_world(world)
+        .unwrap();
+    Workload::builder("Render")
+        .try_with_system(system!(render))
+        .unwrap()
+        .add_to_world(world)
+        .unwrap();
+}
+
+#[test]
+fn accumulate() {
+    let world = World::new();
+    world.try_add_unique(0u32).unwrap();
+    world.try_add_unique(0u64).unwrap();
+    world
+        .try_add_unique(FixedTimestep::new(Duration::from_millis(500)))
+        .unwrap();
+    add_workloads(&world);
+
+    world
+        .try_run_fixed_timestep("Physics", "Render", Duration::from_millis(250))
+        .unwrap();
+    world
+        .try_run(
+            |steps: UniqueView<u32>, frames: UniqueView<u64>, fixed: UniqueView<FixedTimestep>| {
+                assert_eq!(*steps, 0);
+                assert_eq!(*frames, 1);
+                assert_eq!(fixed.alpha(), 0.5);
+            },
+        )
+        .unwrap();
+
+    world
+        .try_run_fixed_timestep("Physics", "Render", Duration::from_millis(1000))
+        .unwrap();
+    world
+        .try_run(
+            |steps: UniqueView<u32>, frames: UniqueView<u64>, fixed: UniqueView<FixedTimestep>| {
+                assert_eq!(*steps, 2);
+                assert_eq!(*frames, 2);
+                assert_eq!(fixed.accumulator(), Duration::from_millis(250));
+                assert_eq!(fixed.alpha(), 0.5);
+            },
+        )
+        .unwrap();
+
+    world
+        .try_run_fixed_timestep("Physics", "Render", Duration::from_millis(250))
+        .unwrap();
+    world
+        .try_run(
+            |steps: UniqueView<u32>, frames: UniqueView<u64>, fixed: UniqueView<FixedTimestep>| {
+                assert_eq!(*steps, 3);
+                assert_eq!(*frames, 3);
+                assert_eq!(fixed.alpha(), 0.);
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn max_steps() {
+    let world = World::new();
+    world.try_add_unique(0u32).unwrap();
+    world.try_add_unique(0u64).unwrap();
+    world
+        .try_add_unique(FixedTimestep::new(Duration::from_millis(500)).with_max_steps(2))
+        .unwrap();
+    add_workloads(&world);
+
+    // a long frame only runs the maximum number of steps and drops the excess time
+    world
+        .try_run_fixed_timestep("Physics", "Render", Duration::from_millis(5250))
+        .unwrap();
+    world
+        .try_run(
+            |steps: UniqueView<u32>, frames: UniqueView<u64>, fixed: UniqueView<FixedTimestep>| {
+                assert_eq!(*steps, 2);
+                assert_eq!(*frames, 1);
+                assert_eq!(fixed.accumulator(), Duration::from_millis(250));
+                assert_eq!(fixed.alpha(), 0.5);
+            },
+        )
+        .unwrap();
+
+    // the next frame isn't affected
+    world
+        .try_run_fixed_timestep("Physics", "Render", Duration::from_millis(750))
+        .unwrap();
+    world
+        .try_run(|steps: UniqueView<u32>, fixed: UniqueView<FixedTimestep>| {
+            assert_eq!(*steps, 4);
+            assert_eq!(fixed.accumulator(), Duration::from_millis(0));
+        })
+        .unwrap();
+}
+
+#[test]
+fn missing_unique() {
+    let world = World::new();
+    world.try_add_unique(0u32).unwrap();
+    world.try_add_unique(0u64).unwrap();
+    add_workloads(&world);
+
+    match world.try_run_fixed_timestep("Physics", "Render", Duration::from_millis(250)) {
+        Err(error::RunWorkload::FixedTimestep(_)) => {}
+        _ => panic!("FixedTimestep isn't present"),
+    }
+    assert!(world
+        .try_run_fixed_timestep("Physics", "Missing", Duration::from_millis(250))
+        .is_err());
+}
+
+#[test]
+fn multi_rate() {
+    fn ai(mut thinks: UniqueViewMut<i32>) {
+        *thinks += 1;
+    }
+
+    let world = World::new();
+    world.try_add_unique(0u32).unwrap();
+    world.try_add_unique(0i32).unwrap();
+    world.try_add_unique(0u64).unwrap();
+    world
+        .try_add_unique(
+            FixedTimesteps::new()
+                .with_workload("Physics", FixedTimestep::new(Duration::from_millis(250)))
+                .with_workload("AI", FixedTimestep::new(Duration::from_millis(1000))),
+        )
+        .unwrap();
+    add_workloads(&world);
+    Workload::builder("AI")
+        .try_with_system(system!(ai))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    world
+        .try_run_multi_rate("Render", Duration::from_millis(750))
+        .unwrap();
+    world
+        .try_run(
+            |steps: UniqueView<u32>,
+             thinks: UniqueView<i32>,
+             frames: UniqueView<u64>,
+             fixed: UniqueView<FixedTimesteps>| {
+                assert_eq!(*steps, 3);
+                assert_eq!(*thinks, 0);
+                assert_eq!(*frames, 1);
+                assert_eq!(fixed.get("Physics").unwrap().alpha(), 0.);
+                assert_eq!(fixed.get("AI").unwrap().alpha(), 0.75);
+            },
+        )
+        .unwrap();
+
+    world
+        .try_run_multi_rate("Render", Duration::from_millis(1375))
+        .unwrap();
+    world
+        .try_run(
+            |steps: UniqueView<u32>,
+             thinks: UniqueView<i32>,
+             frames: UniqueView<u64>,
+             fixed: UniqueView<FixedTimesteps>| {
+                assert_eq!(*steps, 8);
+                assert_eq!(*thinks, 2);
+                assert_eq!(*frames, 2);
+                assert_eq!(fixed.get("Physics").unwrap().alpha(), 0.5);
+                assert_eq!(fixed.get("AI").unwrap().alpha(), 0.125);
+                assert!(fixed.get("Render").is_none());
+            },
+        )
+        .unwrap();
+
+    // a fixed workload has to exist
+    world
+        .try_run(|mut fixed: UniqueViewMut<FixedTimesteps>| {
+            *fixed = fixed
+                .clone()
+                .with_workload("Missing", FixedTimestep::new(Duration::from_millis(250)));
+        })
+        .unwrap();
+    assert!(matches!(
+        world.try_run_multi_rate("Render", Duration::from_millis(250)),
+        Err(error::RunWorkload::MissingWorkload)
+    ));
+}
@@ -1,5 +1,12 @@
+mod dot;
+mod fixed_timestep;
+mod nested;
 #[cfg(all(feature = "non_send", feature = "non_sync"))]
 mod non_send_sync;
+mod ordering;
+#[cfg(feature = "std")]
+mod profiling;
+mod run_if;
 
 use shipyard::*;
 
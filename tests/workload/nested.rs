@@ -0,0 +1,153 @@
+use shipyard::*;
+
+fn gravity(mut speed: UniqueViewMut<f32>) {
+    *speed -= 1.;
+}
+
+fn movement(speed: UniqueView<f32>, mut height: UniqueViewMut<f64>) {
+    *height += *speed as f64;
+}
+
+fn count_frames(mut frames: UniqueViewMut<u32>) {
+    *frames += 1;
+}
+
+fn render(_: UniqueView<f64>) {}
+
+fn is_enabled(enabled: UniqueView<bool>) -> bool {
+    *enabled
+}
+
+#[test]
+fn basic() {
+    let world = World::new();
+    world.try_add_unique(0f32).unwrap();
+    world.try_add_unique(0f64).unwrap();
+    world.try_add_unique(0u32).unwrap();
+
+    Workload::builder("Physics")
+        .try_with_system(system!(gravity))
+        .unwrap()
+        .try_with_system(system!(movement))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    let info = Workload::builder("Game")
+        .with_workload("Physics")
+        .try_with_system(system!(count_frames))
+        .unwrap()
+        .try_with_system(system!(render))
+        .unwrap()
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    assert_eq!(info.batch_info.len(), 2);
+    assert_eq!(info.batch_info[0].systems.len(), 2);
+    assert_eq!(info.batch_info[0].systems[0].name, "Physics");
+    assert_eq!(info.batch_info[0].systems[0].borrow.len(), 2);
+    let physics = info.batch_info[0].systems[0].workload.as_ref().unwrap();
+    assert_eq!(physics.name, "Physics");
+    assert_eq!(physics.batch_info.len(), 2);
+    assert!(info.batch_info[0].systems[1].workload.is_none());
+    assert!(info.batch_info[1].systems[0].name.ends_with("render"));
+
+    world.try_run_workload("Game").unwrap();
+    world.try_run_workload("Game").unwrap();
+    world.try_run_workload("Physics").unwrap();
+    world
+        .try_run(|height: UniqueView<f64>, frames: UniqueView<u32>| {
+            assert_eq!(*height, -6.);
+            assert_eq!(*frames, 2);
+        })
+        .unwrap();
+}
+
+#[test]
+fn ordering() {
+    let world = World::new();
+    world.try_add_unique(0f32).unwrap();
+    world.try_add_unique(0f64).unwrap();
+    world.try_add_unique(0u32).unwrap();
+
+    Workload::builder("Physics")
+        .try_with_system(system!(gravity))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    let info = Workload::builder("Game")
+        .try_with_system(system!(count_frames))
+        .unwrap()
+        .after("Physics")
+        .with_workload("Physics")
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    assert_eq!(info.batch_info.len(), 2);
+    assert_eq!(info.batch_info[0].systems[0].name, "Physics");
+    assert!(info.batch_info[1].systems[0].name.ends_with("count_frames"));
+}
+
+#[test]
+fn run_if() {
+    let world = World::new();
+    world.try_add_unique(false).unwrap();
+    world.try_add_unique(0f32).unwrap();
+    world.try_add_unique(0f64).unwrap();
+
+    Workload::builder("Physics")
+        .try_with_system(system!(gravity))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    Workload::builder("Game")
+        .with_workload("Physics")
+        .try_run_if((|world: &World| world.try_run(is_enabled), is_enabled))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_workload("Game").unwrap();
+    world
+        .try_run(|mut enabled: UniqueViewMut<bool>| *enabled = true)
+        .unwrap();
+    world.try_run_workload("Game").unwrap();
+    world
+        .try_run(|speed: UniqueView<f32>| assert_eq!(*speed, -1.))
+        .unwrap();
+}
+
+#[test]
+fn missing_workload() {
+    let world = World::new();
+
+    assert_eq!(
+        Workload::builder("Game")
+            .with_workload("Physics")
+            .add_to_world(&world)
+            .err(),
+        Some(error::AddWorkload::MissingWorkload("Physics"))
+    );
+}
+
+#[test]
+fn error() {
+    let world = World::new();
+
+    Workload::builder("Physics")
+        .try_with_system(system!(gravity))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+    Workload::builder("Game")
+        .with_workload("Physics")
+        .add_to_world(&world)
+        .unwrap();
+
+    match world.try_run_workload("Game") {
+        Err(error::RunWorkload::Run((system, _))) => assert!(system.ends_with("gravity")),
+        _ => panic!("missing unique storage"),
+    }
+}
@@ -0,0 +1,126 @@
+use shipyard::info::Conflict;
+use shipyard::*;
+
+fn system1(_: View<usize>) {}
+fn system2(_: ViewMut<u32>) {}
+fn system3(_: View<u64>) {}
+
+#[test]
+fn before_after() {
+    let world = World::new();
+
+    let info = Workload::builder("Systems")
+        .try_with_system(system!(system1))
+        .unwrap()
+        .after(system!(system2))
+        .try_with_system(system!(system2))
+        .unwrap()
+        .try_with_system(system!(system3))
+        .unwrap()
+        .before(system!(system2))
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    let names = info
+        .batch_info
+        .iter()
+        .map(|batch| {
+            batch
+                .systems
+                .iter()
+                .map(|system| system.name)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(names.len(), 3);
+    assert!(names[0][0].ends_with("system3"));
+    assert!(names[1][0].ends_with("system2"));
+    assert!(names[2][0].ends_with("system1"));
+
+    world.try_run_default().unwrap();
+}
+
+#[test]
+fn labels() {
+    let world = World::new();
+
+    let info = Workload::builder("Systems")
+        .try_with_system(system!(system1))
+        .unwrap()
+        .label("first")
+        .try_with_system(system!(system2))
+        .unwrap()
+        .label("first")
+        .try_with_system(system!(system3))
+        .unwrap()
+        .after("first")
+        .after("missing")
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    assert_eq!(info.batch_info.len(), 2);
+    assert_eq!(info.batch_info[0].systems.len(), 2);
+
+    let system = &info.batch_info[1].systems[0];
+    assert!(system.name.ends_with("system3"));
+    assert_eq!(system.after.len(), 2);
+    match &system.conflict {
+        Some(Conflict::Ordering { system }) => {
+            assert!(system.name.ends_with("system2"))
+        }
+        _ => panic!("expected an ordering conflict"),
+    }
+}
+
+#[test]
+fn cycle() {
+    let world = World::new();
+
+    match Workload::builder("Systems")
+        .try_with_system(system!(system1))
+        .unwrap()
+        .try_with_system(system!(system2))
+        .unwrap()
+        .label("cycle")
+        .after(system!(system3))
+        .try_with_system(system!(system3))
+        .unwrap()
+        .before("unknown")
+        .label("cycle")
+        .after("cycle")
+        .add_to_world(&world)
+    {
+        Err(error::AddWorkload::Cycle(system)) => assert!(system.ends_with("system3")),
+        _ => panic!("expected a cycle"),
+    }
+
+    assert!(world.try_run_workload("Systems").is_err());
+}
+
+#[test]
+fn closures() {
+    let world = World::new();
+
+    // closures defined in the same function have the same name
+    let x = |_: View<usize>| {};
+    let y = |_: View<u32>| {};
+    let z = |_: View<u64>| {};
+
+    let info = Workload::builder("Closures")
+        .try_with_system((move |world: &World| world.try_run(x), x))
+        .unwrap()
+        .try_with_system((move |world: &World| world.try_run(y), y))
+        .unwrap()
+        .after((move |world: &World| world.try_run(z), z))
+        .try_with_system((move |world: &World| world.try_run(z), z))
+        .unwrap()
+        .after((move |world: &World| world.try_run(x), x))
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    assert_eq!(info.batch_info.len(), 3);
+    assert_eq!(info.batch_info[0].systems.len(), 1);
+
+    world.try_run_default().unwrap();
+}
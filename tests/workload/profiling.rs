@@ -0,0 +1,135 @@
+use shipyard::profiling::{ChromeTrace, Profiler, SystemEvent, TimingCollector};
+use shipyard::*;
+use std::sync::{Arc, Mutex};
+
+fn read(_: View<u32>) {}
+
+fn write(_: ViewMut<u32>) {}
+
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<(bool, String, &'static str, usize)>>,
+}
+
+impl Profiler for Recorder {
+    fn begin(&self, event: &SystemEvent<'_>) {
+        self.events.lock().unwrap().push((
+            true,
+            event.workload.to_string(),
+            event.system,
+            event.batch,
+        ));
+    }
+    fn end(&self, event: &SystemEvent<'_>) {
+        self.events.lock().unwrap().push((
+            false,
+            event.workload.to_string(),
+            event.system,
+            event.batch,
+        ));
+    }
+}
+
+#[test]
+fn events() {
+    let world = World::new();
+    let recorder = Arc::new(Recorder::default());
+    world.try_set_profiler(recorder.clone()).unwrap();
+
+    Workload::builder("Systems")
+        .try_with_system(system!(write))
+        .unwrap()
+        .try_with_system(system!(read))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_workload("Systems").unwrap();
+
+    let events = recorder.events.lock().unwrap().clone();
+    assert_eq!(events.len(), 4);
+    assert!(events[0].0 && events[0].2.ends_with("write") && events[0].3 == 0);
+    assert!(!events[1].0 && events[1].2.ends_with("write") && events[1].3 == 0);
+    assert!(events[2].0 && events[2].2.ends_with("read") && events[2].3 == 1);
+    assert!(!events[3].0 && events[3].2.ends_with("read") && events[3].3 == 1);
+    assert!(events.iter().all(|event| event.1 == "Systems"));
+
+    assert!(world.try_remove_profiler().unwrap().is_some());
+    world.try_run_workload("Systems").unwrap();
+    assert_eq!(recorder.events.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn timings() {
+    let world = World::new();
+    let timings = Arc::new(TimingCollector::new());
+    world.try_set_profiler(timings.clone()).unwrap();
+
+    Workload::builder("Read")
+        .try_with_system(system!(read))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+    Workload::builder("Read & Write")
+        .try_with_system(system!(read))
+        .unwrap()
+        .try_with_system(system!(write))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_workload("Read").unwrap();
+    world.try_run_workload("Read & Write").unwrap();
+
+    let all_timings = timings.timings();
+    assert_eq!(all_timings.len(), 2);
+    assert!(all_timings[0].0.ends_with("read"));
+    assert_eq!(all_timings[0].1.count, 2);
+    assert_eq!(all_timings[1].1.count, 1);
+    assert_eq!(timings.timing(all_timings[1].0), Some(all_timings[1].1));
+
+    timings.clear();
+    assert!(timings.timings().is_empty());
+}
+
+#[test]
+fn chrome_trace() {
+    let world = World::new();
+    let trace = Arc::new(ChromeTrace::new());
+    world.try_set_profiler(trace.clone()).unwrap();
+
+    Workload::builder("Systems")
+        .try_with_system(system!(write))
+        .unwrap()
+        .try_with_system(system!(read))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+    Workload::builder("Nested")
+        .with_workload("Systems")
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_workload("Nested").unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&trace.to_json()).unwrap();
+    let events = json["traceEvents"].as_array().unwrap();
+
+    let slices: Vec<_> = events.iter().filter(|event| event["ph"] == "X").collect();
+    assert_eq!(slices.len(), 3);
+    assert!(slices[0]["name"].as_str().unwrap().ends_with("write"));
+    assert_eq!(slices[0]["args"]["workload"], "Systems");
+    assert!(slices[1]["name"].as_str().unwrap().ends_with("read"));
+    assert_eq!(slices[1]["args"]["batch"], 1);
+    assert_eq!(slices[2]["name"], "Systems");
+    assert_eq!(slices[2]["args"]["workload"], "Nested");
+
+    let batches = events.iter().filter(|event| event["ph"] == "i").count();
+    assert_eq!(batches, 3);
+    assert!(events
+        .iter()
+        .any(|event| event["ph"] == "M" && event["name"] == "thread_name"));
+
+    trace.clear();
+    assert_eq!(trace.to_json().matches("\"ph\":\"X\"").count(), 0);
+}
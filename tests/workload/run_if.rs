@@ -0,0 +1,132 @@
+use shipyard::*;
+
+fn is_enabled(enabled: UniqueView<bool>) -> bool {
+    *enabled
+}
+
+fn increment(mut count: UniqueViewMut<u32>) {
+    *count += 1;
+}
+
+fn increment_other(mut count: UniqueViewMut<u64>) {
+    *count += 1;
+}
+
+#[test]
+fn system() {
+    let world = World::new();
+    world.try_add_unique(false).unwrap();
+    world.try_add_unique(0u32).unwrap();
+    world.try_add_unique(0u64).unwrap();
+
+    let info = Workload::builder("Increment")
+        .try_with_system(system!(increment))
+        .unwrap()
+        .try_run_if((|world: &World| world.try_run(is_enabled), is_enabled))
+        .unwrap()
+        .try_with_system(system!(increment_other))
+        .unwrap()
+        .add_to_world_with_info(&world)
+        .unwrap();
+
+    assert_eq!(info.batch_info.len(), 1);
+    assert_eq!(info.batch_info[0].systems[0].borrow.len(), 2);
+
+    world.try_run_default().unwrap();
+    world
+        .try_run(|count: UniqueView<u32>, other: UniqueView<u64>| {
+            assert_eq!(*count, 0);
+            assert_eq!(*other, 1);
+        })
+        .unwrap();
+
+    world
+        .try_run(|mut enabled: UniqueViewMut<bool>| *enabled = true)
+        .unwrap();
+    world.try_run_default().unwrap();
+    world
+        .try_run(|count: UniqueView<u32>, other: UniqueView<u64>| {
+            assert_eq!(*count, 1);
+            assert_eq!(*other, 2);
+        })
+        .unwrap();
+}
+
+#[test]
+fn shared_system() {
+    let world = World::new();
+    world.try_add_unique(false).unwrap();
+    world.try_add_unique(0u32).unwrap();
+
+    Workload::builder("Conditional")
+        .try_with_system(system!(increment))
+        .unwrap()
+        .try_run_if((|world: &World| world.try_run(is_enabled), is_enabled))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+    Workload::builder("Always")
+        .try_with_system(system!(increment))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_workload("Conditional").unwrap();
+    world.try_run_workload("Always").unwrap();
+    world
+        .try_run(|count: UniqueView<u32>| assert_eq!(*count, 1))
+        .unwrap();
+}
+
+#[test]
+fn workload() {
+    let world = World::new();
+    world.try_add_unique(false).unwrap();
+    world.try_add_unique(0u32).unwrap();
+    world.try_add_unique(0u64).unwrap();
+
+    Workload::builder("Increment")
+        .try_with_system(system!(increment))
+        .unwrap()
+        .try_with_system(system!(increment_other))
+        .unwrap()
+        .try_run_workload_if((|world: &World| world.try_run(is_enabled), is_enabled))
+        .unwrap()
+        .add_to_world(&world)
+        .unwrap();
+
+    world.try_run_default().unwrap();
+    world
+        .try_run(|mut enabled: UniqueViewMut<bool>| *enabled = true)
+        .unwrap();
+    world.try_run_default().unwrap();
+
+    world
+        .try_run(|count: UniqueView<u32>, other: UniqueView<u64>| {
+            assert_eq!(*count, 1);
+            assert_eq!(*other, 1);
+        })
+        .unwrap();
+}
+
+#[test]
+fn invalid_condition() {
+    fn invalid(_: View<u32>, _: ViewMut<u32>) -> bool {
+        true
+    }
+
+    assert_eq!(
+        Workload::builder("Invalid")
+            .try_with_system(system!(increment))
+            .unwrap()
+            .try_run_if((|world: &World| world.try_run(invalid), invalid))
+            .err(),
+        Some(error::InvalidSystem::MultipleViews)
+    );
+    assert_eq!(
+        Workload::builder("Invalid")
+            .try_run_workload_if((|world: &World| world.try_run(invalid), invalid))
+            .err(),
+        Some(error::InvalidSystem::MultipleViews)
+    );
+}
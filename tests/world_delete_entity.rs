@@ -56,3 +56,85 @@ fn update() {
     assert_eq!(usizes.try_take_deleted().unwrap(), vec![(entity1, 0)]);
     assert_eq!(usizes.try_removed().unwrap().len(), 0);
 }
+
+#[cfg(not(feature = "gen32"))]
+#[test]
+fn generation_exhausted() {
+    let mut world = World::new();
+
+    let mut entity = world.add_entity((0u32,));
+    loop {
+        world.delete_entity(entity);
+
+        let new_entity = world.add_entity((0u32,));
+        if new_entity.index() != entity.index() {
+            break;
+        }
+
+        entity = new_entity;
+    }
+
+    assert!(!world.try_borrow::<EntitiesView>().unwrap().is_alive(entity));
+    world
+        .try_run(|mut exhausted: EventReader<GenerationExhausted>| {
+            assert_eq!(
+                exhausted.iter().copied().collect::<Vec<_>>(),
+                vec![GenerationExhausted(entity)]
+            );
+        })
+        .unwrap();
+}
+
+#[cfg(not(feature = "gen32"))]
+fn exhaust_generation(mut entities: EntitiesViewMut, mut last: UniqueViewMut<Option<EntityId>>) {
+    let mut entity = entities.add_entity((), ());
+    loop {
+        entities.delete_unchecked(entity);
+
+        let new_entity = entities.add_entity((), ());
+        if new_entity.index() != entity.index() {
+            break;
+        }
+
+        entity = new_entity;
+    }
+
+    *last = Some(entity);
+}
+
+#[cfg(not(feature = "gen32"))]
+fn read_exhausted(
+    mut exhausted: EventReader<GenerationExhausted>,
+    mut read: UniqueViewMut<Vec<GenerationExhausted>>,
+) {
+    read.extend(exhausted.iter().copied());
+}
+
+#[cfg(not(feature = "gen32"))]
+#[test]
+fn generation_exhausted_in_workload() {
+    let world = World::new();
+    world.add_unique(None::<EntityId>);
+    world.add_unique(Vec::<GenerationExhausted>::new());
+
+    Workload::builder("Exhaust")
+        .with_system(system!(exhaust_generation))
+        .with_system(system!(read_exhausted))
+        .after(system!(exhaust_generation))
+        .add_to_world(&world)
+        .unwrap();
+
+    // entities deleted with `EntitiesViewMut` are reported at the end of the batch
+    world.try_run_default().unwrap();
+
+    world
+        .try_run(
+            |(last, read): (
+                UniqueView<Option<EntityId>>,
+                UniqueView<Vec<GenerationExhausted>>,
+            )| {
+                assert_eq!(*read, vec![GenerationExhausted(last.unwrap())]);
+            },
+        )
+        .unwrap();
+}